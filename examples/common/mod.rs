@@ -61,6 +61,8 @@ pub fn open_sample_db(ctx: &mut gpu::Context) -> Result<DB, Box<dyn Error>> {
         base_dir: &base_dir_str,
         layout_file: Some(&layout_str),
         pooled_geometry_uploads: false,
+        bundle: None,
+        overlays: &[],
     };
 
     Ok(DB::new_with_ctx(&info, Some(ctx))?)
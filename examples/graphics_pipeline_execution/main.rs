@@ -32,6 +32,8 @@ fn run() -> Result<(), Box<dyn Error>> {
         base_dir: base_dir_str,
         layout_file: Some(layout_str),
         pooled_geometry_uploads: false,
+        bundle: None,
+        overlays: &[],
     };
 
     let mut db = noren::DB::new(&info)?;
@@ -0,0 +1,273 @@
+use std::{
+    sync::{Arc, Mutex, mpsc},
+    thread::JoinHandle,
+};
+
+use crate::{
+    RDBRawEntry,
+    rdb::{
+        AnimationClip, AudioClip, HostCubemap, HostFont, HostGeometry, HostImage, LoadedFont,
+        ShaderModule, Skeleton, TerrainChunk, TerrainChunkArtifact,
+    },
+    utils::NorenError,
+};
+
+/// Identifies a request made with [`crate::DB::request_async`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AssetTicket(u64);
+
+/// Host-side asset decoded by the async loader, ready for a GPU upload.
+pub enum HostAsset {
+    Geometry(HostGeometry),
+    Image(HostImage),
+    Cubemap(HostCubemap),
+    Audio(AudioClip),
+    Font(LoadedFont),
+    Skeleton(Skeleton),
+    Animation(AnimationClip),
+    Shader(ShaderModule),
+    TerrainChunk(TerrainChunk),
+    TerrainChunkArtifact(TerrainChunkArtifact),
+}
+
+impl HostAsset {
+    /// Returns whether the entry stores a type [`HostAsset::decode`] understands.
+    pub(crate) fn decodable(raw: &RDBRawEntry) -> bool {
+        raw.holds::<HostGeometry>()
+            || raw.holds::<HostImage>()
+            || raw.holds::<HostCubemap>()
+            || raw.holds::<AudioClip>()
+            || raw.holds::<HostFont>()
+            || raw.holds::<Skeleton>()
+            || raw.holds::<AnimationClip>()
+            || raw.holds::<ShaderModule>()
+            || raw.holds::<TerrainChunk>()
+            || raw.holds::<TerrainChunkArtifact>()
+    }
+
+    /// Decodes a detached entry according to its stored type tag.
+    pub(crate) fn decode(raw: &RDBRawEntry) -> Result<Self, NorenError> {
+        if raw.holds::<HostGeometry>() {
+            return Ok(Self::Geometry(raw.decode::<HostGeometry>()?.with_counts()));
+        }
+        if raw.holds::<HostImage>() {
+            return Ok(Self::Image(raw.decode()?));
+        }
+        if raw.holds::<HostCubemap>() {
+            return Ok(Self::Cubemap(raw.decode()?));
+        }
+        if raw.holds::<AudioClip>() {
+            return Ok(Self::Audio(raw.decode()?));
+        }
+        if raw.holds::<HostFont>() {
+            return Ok(Self::Font(LoadedFont::from_host(raw.decode()?)?));
+        }
+        if raw.holds::<Skeleton>() {
+            return Ok(Self::Skeleton(raw.decode()?));
+        }
+        if raw.holds::<AnimationClip>() {
+            return Ok(Self::Animation(raw.decode()?));
+        }
+        if raw.holds::<ShaderModule>() {
+            let module: ShaderModule = raw.decode()?;
+            if !module.is_spirv() {
+                return Err(NorenError::DataFailure());
+            }
+            return Ok(Self::Shader(module));
+        }
+        if raw.holds::<TerrainChunk>() {
+            return Ok(Self::TerrainChunk(raw.decode()?));
+        }
+        if raw.holds::<TerrainChunkArtifact>() {
+            return Ok(Self::TerrainChunkArtifact(raw.decode()?));
+        }
+
+        Err(NorenError::DataFailure())
+    }
+}
+
+/// A finished async request handed back by [`crate::DB::poll_completed`].
+pub struct CompletedAsset {
+    pub ticket: AssetTicket,
+    pub entry: String,
+    pub result: Result<HostAsset, NorenError>,
+}
+
+/// Work a loader thread runs to resolve one request.
+pub(crate) type LoadJob = Box<dyn FnOnce() -> Result<HostAsset, NorenError> + Send>;
+
+type Job = (AssetTicket, String, LoadJob);
+
+/// Worker pool decoding host assets off the owning thread.
+pub(crate) struct AsyncLoader {
+    jobs: mpsc::Sender<Job>,
+    results_tx: mpsc::Sender<CompletedAsset>,
+    results: mpsc::Receiver<CompletedAsset>,
+    workers: Vec<JoinHandle<()>>,
+    next_ticket: u64,
+    pending: usize,
+}
+
+impl AsyncLoader {
+    /// Spawns one worker per spare core, keeping a core for the owning thread.
+    pub(crate) fn new() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(|count| count.get().saturating_sub(1))
+            .unwrap_or(1)
+            .max(1);
+        Self::with_threads(threads)
+    }
+
+    pub(crate) fn with_threads(threads: usize) -> Self {
+        let (jobs, job_rx) = mpsc::channel::<Job>();
+        let (results_tx, results) = mpsc::channel();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+
+        let workers = (0..threads.max(1))
+            .map(|index| {
+                let job_rx = Arc::clone(&job_rx);
+                let results_tx = results_tx.clone();
+                std::thread::Builder::new()
+                    .name(format!("noren-loader-{index}"))
+                    .spawn(move || worker(&job_rx, &results_tx))
+                    .expect("spawn loader thread")
+            })
+            .collect();
+
+        Self {
+            jobs,
+            results_tx,
+            results,
+            workers,
+            next_ticket: 0,
+            pending: 0,
+        }
+    }
+
+    fn ticket(&mut self) -> AssetTicket {
+        let ticket = AssetTicket(self.next_ticket);
+        self.next_ticket += 1;
+        self.pending += 1;
+        ticket
+    }
+
+    /// Queues `job` to resolve `entry` on a worker.
+    pub(crate) fn submit(&mut self, entry: &str, job: LoadJob) -> AssetTicket {
+        let ticket = self.ticket();
+        if self.jobs.send((ticket, entry.to_string(), job)).is_err() {
+            self.finish(ticket, entry, Err(NorenError::Unknown()));
+        }
+        ticket
+    }
+
+    /// Records a request that was resolved on the owning thread.
+    pub(crate) fn complete(
+        &mut self,
+        entry: &str,
+        result: Result<HostAsset, NorenError>,
+    ) -> AssetTicket {
+        let ticket = self.ticket();
+        self.finish(ticket, entry, result);
+        ticket
+    }
+
+    fn finish(&self, ticket: AssetTicket, entry: &str, result: Result<HostAsset, NorenError>) {
+        // The receiver lives in `self`, so this send cannot fail.
+        let _ = self.results_tx.send(CompletedAsset {
+            ticket,
+            entry: entry.to_string(),
+            result,
+        });
+    }
+
+    /// Drains the requests finished so far.
+    pub(crate) fn poll(&mut self) -> Vec<CompletedAsset> {
+        let completed: Vec<CompletedAsset> = self.results.try_iter().collect();
+        self.pending -= completed.len();
+        completed
+    }
+
+    /// Returns the number of requests not yet handed back by [`AsyncLoader::poll`].
+    pub(crate) fn pending(&self) -> usize {
+        self.pending
+    }
+}
+
+impl Drop for AsyncLoader {
+    fn drop(&mut self) {
+        // Closing the job channel lets every worker finish its queue and exit.
+        self.jobs = mpsc::channel().0;
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn worker(jobs: &Mutex<mpsc::Receiver<Job>>, results: &mpsc::Sender<CompletedAsset>) {
+    loop {
+        let job = match jobs.lock() {
+            Ok(jobs) => jobs.recv(),
+            Err(_) => return,
+        };
+        let Ok((ticket, entry, job)) = job else {
+            return;
+        };
+
+        let completed = CompletedAsset {
+            ticket,
+            entry,
+            result: job(),
+        };
+        if results.send(completed).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AsyncLoader, HostAsset};
+    use crate::{
+        RDBFile, RDBView,
+        rdb::{AudioClip, AudioFormat},
+        utils::NorenError,
+    };
+
+    #[test]
+    fn workers_decode_submitted_entries() {
+        let path = "target/async_loader.rdb";
+        let mut rdb = RDBFile::new();
+        let clip = AudioClip::new("audio/beep".into(), AudioFormat::default(), vec![1, 2, 3]);
+        rdb.add("audio/beep", &clip).expect("add clip");
+        rdb.add("value", &7u32).expect("add value");
+        rdb.save(path).expect("save");
+        let view = RDBView::load(path).expect("load");
+
+        let raw = view.raw_entry("audio/beep").expect("raw clip");
+        assert!(HostAsset::decodable(&raw));
+        assert!(!HostAsset::decodable(&view.raw_entry("value").unwrap()));
+
+        let mut loader = AsyncLoader::with_threads(2);
+        let decoded = loader.submit("audio/beep", Box::new(move || HostAsset::decode(&raw)));
+        let failed = loader.complete("missing", Err(NorenError::LookupFailure()));
+        assert_ne!(decoded, failed);
+        assert_eq!(loader.pending(), 2);
+
+        let mut completed = Vec::new();
+        while loader.pending() > 0 {
+            completed.extend(loader.poll());
+            std::thread::yield_now();
+        }
+        completed.sort_by_key(|done| done.ticket);
+
+        assert_eq!(completed[0].entry, "audio/beep");
+        match &completed[0].result {
+            Ok(HostAsset::Audio(clip)) => assert_eq!(clip.data, vec![1, 2, 3]),
+            _ => panic!("expected a decoded audio clip"),
+        }
+        assert!(matches!(
+            completed[1].result,
+            Err(NorenError::LookupFailure())
+        ));
+    }
+}
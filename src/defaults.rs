@@ -483,23 +483,29 @@ pub fn default_cubemaps() -> Vec<(String, HostCubemap)> {
     vec![(DEFAULT_CUBEMAP_ENTRY.to_string(), default_cubemap())]
 }
 
+/// Playback lengths of [`DEFAULT_SOUND_ENTRIES`] in seconds. The audio tests
+/// check them against the embedded files, so loading defaults never decodes.
+const DEFAULT_SOUND_DURATIONS: [f32; 2] = [0.2, 0.2];
+
+fn default_sound_clip(index: usize, data: &[u8]) -> AudioClip {
+    AudioClip {
+        duration_secs: DEFAULT_SOUND_DURATIONS[index],
+        ..AudioClip::new(
+            DEFAULT_SOUND_ENTRIES[index].to_string(),
+            AudioFormat::Wav,
+            data.to_vec(),
+        )
+    }
+}
+
 pub fn default_sound() -> AudioClip {
-    let data = include_bytes!("../sample/sample_pre/audio/beep.wav").to_vec();
-    AudioClip::new(DEFAULT_SOUND_ENTRY.to_string(), AudioFormat::Wav, data)
+    default_sound_clip(0, include_bytes!("../sample/sample_pre/audio/beep.wav"))
 }
 
 pub fn default_sounds() -> Vec<AudioClip> {
     vec![
-        AudioClip::new(
-            DEFAULT_SOUND_ENTRIES[0].to_string(),
-            AudioFormat::Wav,
-            include_bytes!("../sample/sample_pre/audio/beep.wav").to_vec(),
-        ),
-        AudioClip::new(
-            DEFAULT_SOUND_ENTRIES[1].to_string(),
-            AudioFormat::Wav,
-            include_bytes!("../sample/sample_pre/audio/tone.wav").to_vec(),
-        ),
+        default_sound(),
+        default_sound_clip(1, include_bytes!("../sample/sample_pre/audio/tone.wav")),
     ]
 }
 
@@ -674,6 +680,7 @@ pub fn default_msdf_font_layout() -> MsdfFontLayout {
         angle_threshold: 3.0,
         metrics: atlas.metrics.clone(),
         glyphs: atlas.glyphs.clone(),
+        kerning: Vec::new(),
     }
 }
 
@@ -688,6 +695,7 @@ pub fn default_sdf_font_layout() -> SdfFontLayout {
         distance_range: 4.0,
         metrics: atlas.metrics.clone(),
         glyphs: atlas.glyphs.clone(),
+        kerning: Vec::new(),
     }
 }
 
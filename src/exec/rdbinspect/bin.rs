@@ -1,12 +1,11 @@
 use std::{any::type_name, env, fs, path::PathBuf, process, sync::OnceLock};
 
-use bincode::deserialize;
 use noren::{
-    RDBEntryMeta, RDBView,
+    RDBCompression, RDBEntryMeta, RDBType, RDBView, decode_payload, legacy_type_tag_for,
     rdb::{
-        AnimationClip, HostCubemap, HostGeometry, HostImage, ShaderModule, Skeleton, TerrainChunk,
-        TerrainChunkArtifact, TerrainChunkState, TerrainGeneratorDefinition, TerrainMutationLayer,
-        TerrainMutationOp, TerrainProjectSettings,
+        AnimationClip, AudioClip, HostCubemap, HostGeometry, HostImage, ShaderModule, Skeleton,
+        TerrainChunk, TerrainChunkArtifact, TerrainChunkState, TerrainGeneratorDefinition,
+        TerrainMutationLayer, TerrainMutationOp, TerrainProjectSettings,
     },
     type_tag_for,
 };
@@ -34,6 +33,8 @@ fn run() -> Result<(), String> {
     let mut all_entries = false;
     let mut hex_limit: usize = 256;
     let mut show_hex = true;
+    let mut verify = false;
+    let mut full_names = false;
 
     let mut iter = rest.into_iter();
     while let Some(arg) = iter.next() {
@@ -62,6 +63,12 @@ fn run() -> Result<(), String> {
             "--no-hex" => {
                 show_hex = false;
             }
+            "--verify" => {
+                verify = true;
+            }
+            "--full-names" => {
+                full_names = true;
+            }
             _ => {
                 if path.is_none() {
                     path = Some(PathBuf::from(arg));
@@ -86,6 +93,13 @@ fn run() -> Result<(), String> {
     if let Ok(meta) = fs::metadata(&path) {
         println!("Size: {} bytes", meta.len());
     }
+    println!("Version: {}", view.version());
+    if view.dead_bytes() > 0 {
+        println!(
+            "Dead bytes: {} (reclaim with `dbgen compact`)",
+            view.dead_bytes()
+        );
+    }
     println!("Entries: {}", entries.len());
 
     if entries.is_empty() {
@@ -93,10 +107,10 @@ fn run() -> Result<(), String> {
     } else {
         let name_width = entries
             .iter()
-            .map(|entry| entry.name.len())
+            .map(|entry| entry.name.chars().count())
             .max()
             .unwrap_or(4)
-            .clamp(4, 48);
+            .clamp(4, if full_names { usize::MAX } else { 48 });
         let type_width = entries
             .iter()
             .map(|entry| type_label(entry.type_tag).len())
@@ -104,17 +118,21 @@ fn run() -> Result<(), String> {
             .unwrap_or(4)
             .clamp(4, 32);
         println!(
-            "\n{:<name_width$}  {:<type_width$}  {:^12}  {:>12}  {:>12}",
+            "\n{:<name_width$}  {:<type_width$}  {:^12}  {:>12}  {:>12}  {:>12}  {:<5}",
             "Name",
             "Type",
             "Type (hex)",
             "Offset",
-            "Length",
+            "Stored",
+            "Raw",
+            "Codec",
             name_width = name_width,
             type_width = type_width
         );
         println!(
-            "{:-<name_width$}  {:-<type_width$}  {:-^12}  {:-<12}  {:-<12}",
+            "{:-<name_width$}  {:-<type_width$}  {:-^12}  {:-<12}  {:-<12}  {:-<12}  {:-<5}",
+            "",
+            "",
             "",
             "",
             "",
@@ -127,25 +145,32 @@ fn run() -> Result<(), String> {
         for entry in &entries {
             let label = type_label(entry.type_tag);
             println!(
-                "{:<name_width$}  {:<type_width$}  {:#010X}  {:>12}  {:>12}",
+                "{:<name_width$}  {:<type_width$}  {:#010X}  {:>12}  {:>12}  {:>12}  {:<5}",
                 truncated_name(&entry.name, name_width),
                 label,
                 entry.type_tag,
                 entry.offset,
                 entry.len,
+                entry.raw_len,
+                entry.compression,
                 name_width = name_width,
                 type_width = type_width
             );
         }
 
-        let total_bytes: u64 = entries.iter().map(|entry| entry.len).sum();
-        println!("\nTotal payload bytes: {total_bytes}");
+        let stored_bytes: u64 = entries.iter().map(|entry| entry.len).sum();
+        let raw_bytes: u64 = entries.iter().map(|entry| entry.raw_len).sum();
+        println!("\nTotal payload bytes: {stored_bytes} stored, {raw_bytes} raw");
     }
 
     if all_entries && entry_to_dump.is_some() {
         return Err("--all-entries cannot be combined with --entry".to_string());
     }
 
+    if verify {
+        print_verification(&view)?;
+    }
+
     if all_entries {
         for meta in &entries {
             inspect_entry(&view, meta, show_hex, hex_limit)?;
@@ -166,15 +191,47 @@ fn run() -> Result<(), String> {
     Ok(())
 }
 
+fn print_verification(view: &RDBView) -> Result<(), String> {
+    let report = view.verify();
+
+    println!("\nVerification:");
+    if !report.has_checksums() {
+        println!(
+            "  Version {} file has no checksums; checked entry bounds only",
+            report.version
+        );
+    }
+    match report.digest_matches {
+        Some(true) => println!("  File digest: ok"),
+        Some(false) => println!("  File digest: MISMATCH"),
+        None => println!("  File digest: (not stored)"),
+    }
+    for corrupt in &report.corrupt_entries {
+        println!("  Corrupt entry '{}': {}", corrupt.name, corrupt.fault);
+    }
+
+    if report.is_ok() {
+        println!("  Result: ok");
+        Ok(())
+    } else {
+        Err(format!(
+            "verification failed ({} corrupt entries)",
+            report.corrupt_entries.len()
+        ))
+    }
+}
+
 fn print_usage(program: &str) {
     println!(
-        "Usage: {program} <RDB_FILE> [--entry <NAME>] [--all-entries] [--limit <BYTES>] [--no-hex]"
+        "Usage: {program} <RDB_FILE> [--entry <NAME>] [--all-entries] [--limit <BYTES>] [--no-hex] [--verify] [--full-names]"
     );
     println!("\nOptions:");
     println!("  --all-entries   Inspect all entries and display their metadata");
     println!("  --entry <NAME>   Inspect a specific entry and display its metadata");
     println!("  --limit <BYTES>  Limit the number of bytes shown in the hex dump (default 256)");
     println!("  --no-hex         Skip the hex dump when inspecting an entry");
+    println!("  --verify         Check entry checksums and the file digest");
+    println!("  --full-names     Do not truncate long entry names in the listing");
     println!("  -h, --help       Show this help message");
 }
 
@@ -192,6 +249,7 @@ fn known_types() -> &'static [KnownType] {
     KNOWN.get_or_init(|| {
         vec![
             KnownType::with::<AnimationClip>(describe_animation),
+            KnownType::with::<AudioClip>(describe_audio),
             KnownType::with::<HostGeometry>(describe_geometry),
             KnownType::with::<HostImage>(describe_image),
             KnownType::with::<HostCubemap>(describe_cubemap),
@@ -209,7 +267,9 @@ fn known_types() -> &'static [KnownType] {
 }
 
 fn known_type(tag: u32) -> Option<&'static KnownType> {
-    known_types().iter().find(|ty| ty.tag == tag)
+    known_types()
+        .iter()
+        .find(|ty| ty.tag == tag || ty.legacy_tag == tag || ty.older_tags.contains(&tag))
 }
 
 fn type_label(tag: u32) -> String {
@@ -232,14 +292,24 @@ fn inspect_entry(
     println!("  Type (hex): {:#010X}", meta.type_tag);
     println!("  Offset: {}", meta.offset);
     println!("  Length: {} bytes", meta.len);
+    if meta.compression != RDBCompression::None {
+        println!(
+            "  Compression: {} ({} bytes uncompressed)",
+            meta.compression, meta.raw_len
+        );
+    }
+    if meta.pod {
+        println!("  Layout: raw POD slice");
+    }
+    println!("  Checksum: {:#010X}", meta.checksum);
 
     let bytes = view
         .entry_bytes(&meta.name)
         .map_err(|err| format!("unable to read entry '{}': {err}", meta.name))?;
 
-    if let Some(known) = known_type(meta.type_tag) {
+    if let Some(known) = known_type(meta.type_tag).filter(|_| !meta.pod) {
         println!("\nDeserialized as {}:", known.display_name());
-        match (known.describe)(bytes) {
+        match (known.describe)(meta, &bytes) {
             Ok(text) => println!("{text}"),
             Err(err) => println!("(failed to decode {}: {err})", known.display_name()),
         }
@@ -247,7 +317,7 @@ fn inspect_entry(
 
     if show_hex {
         println!("\nHex dump (showing up to {hex_limit} bytes):");
-        hexdump(bytes, hex_limit);
+        hexdump(&bytes, hex_limit);
     }
 
     Ok(())
@@ -256,19 +326,25 @@ fn inspect_entry(
 struct KnownType {
     name: &'static str,
     tag: u32,
-    describe: Box<dyn Fn(&[u8]) -> Result<String, String> + Send + Sync>,
+    /// Name-hash tag written by builds that predate stable type tags.
+    legacy_tag: u32,
+    /// Tags of layouts the type replaced, read through [`RDBType::upgrade`].
+    older_tags: &'static [u32],
+    describe: Box<dyn Fn(&RDBEntryMeta, &[u8]) -> Result<String, String> + Send + Sync>,
 }
 
 impl KnownType {
     fn new<T>() -> Self
     where
-        T: DeserializeOwned + std::fmt::Debug + 'static,
+        T: DeserializeOwned + RDBType + std::fmt::Debug + 'static,
     {
         Self {
             name: type_name::<T>(),
             tag: type_tag_for::<T>(),
-            describe: Box::new(|bytes| {
-                deserialize::<T>(bytes)
+            legacy_tag: legacy_type_tag_for::<T>(),
+            older_tags: T::OLDER_TAGS,
+            describe: Box::new(|meta, bytes| {
+                decode_payload::<T>(&meta.name, meta.type_tag, bytes)
                     .map(|value| format!("{value:#?}"))
                     .map_err(|err| err.to_string())
             }),
@@ -277,13 +353,16 @@ impl KnownType {
 
     fn with<T>(describe: fn(&T) -> String) -> Self
     where
-        T: DeserializeOwned + std::fmt::Debug + 'static,
+        T: DeserializeOwned + RDBType + std::fmt::Debug + 'static,
     {
         Self {
             name: type_name::<T>(),
             tag: type_tag_for::<T>(),
-            describe: Box::new(move |bytes| {
-                let value: T = deserialize(bytes).map_err(|err| err.to_string())?;
+            legacy_tag: legacy_type_tag_for::<T>(),
+            older_tags: T::OLDER_TAGS,
+            describe: Box::new(move |meta, bytes| {
+                let value: T = decode_payload(&meta.name, meta.type_tag, bytes)
+                    .map_err(|err| err.to_string())?;
                 Ok(describe(&value))
             }),
         }
@@ -390,10 +469,24 @@ fn describe_image(image: &HostImage) -> String {
     let dimensions = format!("{} x {} x {}", info.dim[0], info.dim[1], info.dim[2]);
     let bytes = image.data.len();
 
-    format!(
+    let mut lines = format!(
         "  Dimensions: {dimensions}\n  Layers: {}\n  Format: {:?}\n  Mip levels: {}\n  Data size: {bytes} bytes",
         info.layers, info.format, info.mip_levels
-    )
+    );
+    if let Some(block) = info.block_format() {
+        let uncompressed: u64 = (0..image.mip_count() as u32)
+            .map(|level| {
+                let width = (info.dim[0] >> level).max(1) as u64;
+                let height = (info.dim[1] >> level).max(1) as u64;
+                width * height * 4
+            })
+            .sum();
+        lines.push_str(&format!(
+            "\n  Block format: {block:?} ({} bytes per 4x4 block)\n  Compressed size: {bytes} bytes ({uncompressed} bytes as RGBA8)",
+            block.block_bytes()
+        ));
+    }
+    lines
 }
 
 fn describe_cubemap(cubemap: &HostCubemap) -> String {
@@ -652,6 +745,42 @@ fn describe_animation(clip: &AnimationClip) -> String {
     lines.join("\n")
 }
 
+fn describe_audio(clip: &AudioClip) -> String {
+    let mut lines = vec![
+        format!("  Name: {}", clip.name),
+        format!("  Format: {:?}", clip.format),
+        format!("  Duration: {:.3} seconds", clip.duration_secs),
+        format!("  Volume: {:.3}", clip.volume),
+        format!(
+            "  Category: {}",
+            clip.category.as_deref().unwrap_or("(none)")
+        ),
+        format!("  Data size: {} bytes", clip.data.len()),
+    ];
+
+    match clip.looping {
+        Some(region) => {
+            let end = region
+                .end
+                .map(|end| end.to_string())
+                .unwrap_or_else(|| "end of clip".to_string());
+            lines.push(format!("  Loop: frames {} .. {end}", region.start));
+        }
+        None => lines.push("  Loop: (none)".to_string()),
+    }
+
+    if !clip.cues.is_empty() {
+        lines.push("  Cues:".to_string());
+        lines.extend(
+            clip.cues
+                .iter()
+                .map(|cue| format!("    {} @ frame {}", cue.name, cue.frame)),
+        );
+    }
+
+    lines.join("\n")
+}
+
 fn describe_skeleton(skeleton: &Skeleton) -> String {
     let mut lines = vec![
         format!("  Name: {}", skeleton.name),
@@ -682,7 +811,7 @@ fn describe_skeleton(skeleton: &Skeleton) -> String {
 }
 
 fn truncated_name(name: &str, width: usize) -> String {
-    if name.len() <= width {
+    if name.chars().count() <= width {
         name.to_string()
     } else if width <= 1 {
         "…".to_string()
@@ -1,6 +1,6 @@
 use std::{
     collections::{BTreeMap, BTreeSet, HashMap, HashSet},
-    path::{Path, PathBuf},
+    path::PathBuf,
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
@@ -1951,7 +1951,7 @@ impl TerrainEditorApp {
                             });
                             continue;
                         }
-                        if let Err(err) = save_rdb_atomic(&rdb, &rdb_path) {
+                        if let Err(err) = rdb.append(&rdb_path).map_err(format_rdb_err) {
                             let _ = sender.send(BuildJobEvent::Failed {
                                 chunk: request,
                                 error: format!("Failed to save RDB: {err}"),
@@ -2178,7 +2178,7 @@ impl TerrainEditorApp {
         }
         self.viewport.last_stamp_pos = Some(world_pos);
         mark_chunks_dirty(rdb, project, &op)?;
-        rdb.save(&project.rdb_path).map_err(format_rdb_err)?;
+        rdb.append(&project.rdb_path).map_err(format_rdb_err)?;
         self.log(format!("Stamped op on layer {}", layer_name));
         Ok(())
     }
@@ -2230,7 +2230,7 @@ impl TerrainEditorApp {
             }
         }
         mark_chunks_dirty(rdb, project, &toggled)?;
-        rdb.save(&project.rdb_path).map_err(format_rdb_err)?;
+        rdb.append(&project.rdb_path).map_err(format_rdb_err)?;
         Ok(())
     }
 
@@ -2317,7 +2317,7 @@ impl TerrainEditorApp {
             )
             .map_err(format_rdb_err)?;
         }
-        rdb.save(&project.rdb_path).map_err(format_rdb_err)?;
+        rdb.append(&project.rdb_path).map_err(format_rdb_err)?;
 
         project.chunks = collect_chunk_artifacts(&rdb.entries(), &project.key);
         self.log("Saved project data to RDB.");
@@ -2626,24 +2626,6 @@ fn format_rdb_err(err: RdbErr) -> String {
     format!("RDB error: {err}")
 }
 
-fn save_rdb_atomic(rdb: &RDBFile, path: &Path) -> Result<(), String> {
-    let parent = path
-        .parent()
-        .ok_or_else(|| "RDB path has no parent directory".to_string())?;
-    let file_name = path
-        .file_name()
-        .and_then(|name| name.to_str())
-        .ok_or_else(|| "Invalid RDB filename".to_string())?;
-    let temp_path = parent.join(format!("{file_name}.tmp"));
-    rdb.save(&temp_path).map_err(format_rdb_err)?;
-    if path.exists() {
-        std::fs::remove_file(path).map_err(|err| format!("Failed to remove old RDB: {err}"))?;
-    }
-    std::fs::rename(&temp_path, path)
-        .map_err(|err| format!("Failed to replace RDB file: {err}"))?;
-    Ok(())
-}
-
 fn collect_mutation_layers(rdb: &mut RDBFile, project_key: &str) -> Vec<TerrainMutationLayer> {
     let prefix = format!("terrain/mutation_layer/{project_key}/");
     let mut layer_versions = BTreeMap::new();
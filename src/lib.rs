@@ -1,15 +1,26 @@
 extern crate self as noren;
 
+mod async_loader;
 pub mod defaults;
 mod furikake_state;
 pub mod meta;
 pub mod parsing;
 pub mod rdb;
+mod reader;
 pub mod terrain;
 pub mod tools;
 mod utils;
-use std::{collections::HashMap, io::ErrorKind, ptr::NonNull};
+use std::{
+    collections::{BTreeSet, HashMap},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    ptr::NonNull,
+    sync::Arc,
+    time::SystemTime,
+};
 
+pub use async_loader::{AssetTicket, CompletedAsset, HostAsset};
+use async_loader::{AsyncLoader, LoadJob};
 use dashi::Context;
 use defaults::inject_default_layouts;
 use furikake::{
@@ -37,14 +48,45 @@ use tracing::info;
 use utils::*;
 
 pub use parsing::DatabaseLayoutFile;
+pub use reader::DBReader;
+pub use utils::bundle::{BUNDLE_LAYOUT_MEMBER, DBBundle, DBBundleWriter};
+pub use utils::data_cache::CacheStats;
 pub use utils::error::{NorenError, RdbErr};
-pub use utils::rdbfile::{RDBEntryMeta, RDBFile, RDBView, type_tag_for};
+pub use utils::handle::{AssetHandle, EntryId};
+pub use utils::layers::RDBLayers;
+pub use utils::rdbfile::{
+    RDBCompression, RDBCorruptEntry, RDBEntryFault, RDBEntryMeta, RDBFile, RDBRawEntry, RDBType,
+    RDBVerifyReport, RDBView, decode_payload, fourcc, legacy_type_tag_for, type_tag_for,
+};
 
 pub struct DBInfo<'a> {
     pub base_dir: &'a str,
     pub layout_file: Option<&'a str>,
     /// Enable pooled geometry uploads into shared vertex/index buffers during initialization.
     pub pooled_geometry_uploads: bool,
+    /// Single-file `.nrb` bundle to open instead of the files under `base_dir`.
+    ///
+    /// The bundle's embedded layout is used and `layout_file` is ignored.
+    pub bundle: Option<&'a str>,
+    /// Patch databases layered over the base one, lowest priority first.
+    ///
+    /// Each is a directory or a `.nrb` bundle laid out with the base layout's
+    /// file names. Entries and metadata in later overlays shadow earlier ones.
+    pub overlays: &'a [&'a str],
+}
+
+/// Resident GPU bytes per module, as reported by [`DB::memory_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryStats {
+    pub geometry: CacheStats,
+    pub imagery: CacheStats,
+}
+
+impl MemoryStats {
+    /// Returns the resident bytes across all modules.
+    pub fn total_bytes(&self) -> u64 {
+        self.geometry.resident_bytes + self.imagery.resident_bytes
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -89,12 +131,14 @@ pub struct DB {
     shaders: ShaderDB,
     terrain: TerrainDB,
     ctx: Option<NonNull<dashi::Context>>,
-    meta_layout: Option<MetaLayout>,
+    meta_layout: Option<Arc<MetaLayout>>,
     graphics_pipeline_layouts: HashMap<String, dashi::Handle<dashi::GraphicsPipelineLayout>>,
     graphics_pipelines: HashMap<String, dashi::Handle<dashi::GraphicsPipeline>>,
     compute_pipeline_layouts: HashMap<String, dashi::Handle<dashi::ComputePipelineLayout>>,
     compute_pipelines: HashMap<String, dashi::Handle<dashi::ComputePipeline>>,
     furikake: Option<FurikakeBindings>,
+    loader: Option<AsyncLoader>,
+    watch: DBWatch,
 }
 
 fn read_database_layout(layout_file: Option<&str>) -> Result<DatabaseLayoutFile, NorenError> {
@@ -114,24 +158,172 @@ fn load_json_file<T: DeserializeOwned + Default>(path: &str) -> Result<Option<T>
     }
 }
 
+/// Where [`DB`] reads module RDB files and metadata JSON from.
+enum DBSource<'a> {
+    Directory(&'a str),
+    Bundle(DBBundle),
+}
+
+impl<'a> DBSource<'a> {
+    /// Opens the base database followed by its overlays, lowest priority first.
+    fn open(info: &DBInfo<'a>) -> Result<(Vec<Self>, DatabaseLayoutFile), NorenError> {
+        let (base, layout) = match info.bundle {
+            Some(path) => {
+                let bundle = DBBundle::open(path)?;
+                let layout = match bundle.member(BUNDLE_LAYOUT_MEMBER) {
+                    Some(raw) => serde_json::from_slice(raw)?,
+                    None => Default::default(),
+                };
+                (DBSource::Bundle(bundle), layout)
+            }
+            None => (
+                DBSource::Directory(info.base_dir),
+                read_database_layout(info.layout_file)?,
+            ),
+        };
+
+        let mut sources = vec![base];
+        for &overlay in info.overlays {
+            sources.push(if Path::new(overlay).is_file() {
+                DBSource::Bundle(DBBundle::open(overlay)?)
+            } else {
+                DBSource::Directory(overlay)
+            });
+        }
+        Ok((sources, layout))
+    }
+
+    fn view(&self, module: &str) -> Option<RDBView> {
+        match self {
+            DBSource::Directory(base_dir) => RDBView::load(format!("{base_dir}/{module}")).ok(),
+            DBSource::Bundle(bundle) => bundle.view(module).ok(),
+        }
+    }
+
+    /// Stacks `module` from every source that has it.
+    fn layers(sources: &[Self], module: &str) -> RDBLayers {
+        RDBLayers::new(sources.iter().filter_map(|source| source.view(module)))
+    }
+
+    fn json<T: DeserializeOwned + Default>(&self, file: &str) -> Result<Option<T>, NorenError> {
+        match self {
+            DBSource::Directory(base_dir) => load_json_file(&format!("{base_dir}/{file}")),
+            DBSource::Bundle(bundle) => match bundle.member(file) {
+                Some(raw) if raw.trim_ascii().is_empty() => Ok(None),
+                Some(raw) => Ok(Some(serde_json::from_slice::<T>(raw)?)),
+                None => Ok(None),
+            },
+        }
+    }
+}
+
+/// Size and modification time of a watched file, or `None` while it is missing.
+type FileStamp = Option<(Option<SystemTime>, u64)>;
+
+fn file_stamp(path: &Path) -> FileStamp {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.modified().ok(), meta.len()))
+}
+
+/// Owned copy of the [`DBInfo`] sources that [`DB::reload_changed`] remaps.
+struct DBWatch {
+    base_dir: String,
+    bundle: Option<String>,
+    overlays: Vec<String>,
+    layout: DatabaseLayoutFile,
+    stamps: HashMap<PathBuf, FileStamp>,
+}
+
+impl DBWatch {
+    fn new(info: &DBInfo, layout: DatabaseLayoutFile) -> Self {
+        let mut watch = Self {
+            base_dir: info.base_dir.to_string(),
+            bundle: info.bundle.map(str::to_string),
+            overlays: info
+                .overlays
+                .iter()
+                .map(|overlay| overlay.to_string())
+                .collect(),
+            layout,
+            stamps: HashMap::new(),
+        };
+        watch.stamps = watch.current_stamps();
+        watch
+    }
+
+    /// Stamps every module and metadata file, or the bundle file, of each source.
+    fn current_stamps(&self) -> HashMap<PathBuf, FileStamp> {
+        let base = self.bundle.as_deref().unwrap_or(&self.base_dir);
+        let mut files = Vec::new();
+        for root in std::iter::once(base).chain(self.overlays.iter().map(String::as_str)) {
+            let root = Path::new(root);
+            if root.is_file() {
+                files.push(root.to_path_buf());
+                continue;
+            }
+            let names = self.layout.module_files().into_iter();
+            files.extend(
+                names
+                    .chain(self.layout.metadata_files())
+                    .map(|file| root.join(file)),
+            );
+        }
+
+        files
+            .into_iter()
+            .map(|file| {
+                let stamp = file_stamp(&file);
+                (file, stamp)
+            })
+            .collect()
+    }
+
+    /// Returns whether `file` changed between the watched stamps and `stamps`,
+    /// either on its own or inside a changed bundle.
+    fn touched(&self, stamps: &HashMap<PathBuf, FileStamp>, file: &str) -> bool {
+        let base = self.bundle.as_deref().unwrap_or(&self.base_dir);
+        let is_root = |path: &Path| {
+            std::iter::once(base)
+                .chain(self.overlays.iter().map(String::as_str))
+                .any(|root| Path::new(root) == path)
+        };
+        stamps
+            .iter()
+            .filter(|(path, stamp)| self.stamps.get(*path) != Some(*stamp))
+            .any(|(path, _)| path.ends_with(file) || is_root(path.as_path()))
+    }
+}
+
 fn load_meta_layout(
-    base_dir: &str,
+    sources: &[DBSource<'_>],
     layout: &DatabaseLayoutFile,
 ) -> Result<Option<MetaLayout>, NorenError> {
-    let textures =
-        load_json_file::<TextureLayoutFile>(&format!("{}/{}", base_dir, layout.textures))?;
-    let atlases =
-        load_json_file::<TextureAtlasLayoutFile>(&format!("{}/{}", base_dir, layout.atlases))?;
-    let msdf_fonts =
-        load_json_file::<MsdfFontLayoutFile>(&format!("{}/{}", base_dir, layout.msdf_fonts))?;
-    let sdf_fonts =
-        load_json_file::<SdfFontLayoutFile>(&format!("{}/{}", base_dir, layout.sdf_fonts))?;
-    let materials =
-        load_json_file::<MaterialLayoutFile>(&format!("{}/{}", base_dir, layout.materials))?;
-    let meshes = load_json_file::<MeshLayoutFile>(&format!("{}/{}", base_dir, layout.meshes))?;
-    let models = load_json_file::<ModelLayoutFile>(&format!("{}/{}", base_dir, layout.models))?;
-    let shader_layouts =
-        load_json_file::<ShaderLayoutFile>(&format!("{}/{}", base_dir, layout.shader_layouts))?;
+    let mut meta_layout = MetaLayout::default();
+    for source in sources {
+        meta_layout.overlay(read_meta_layout(source, layout)?);
+    }
+
+    inject_default_layouts(&mut meta_layout);
+
+    if meta_layout.is_empty() {
+        Ok(None)
+    } else {
+        Ok(Some(meta_layout))
+    }
+}
+
+fn read_meta_layout(
+    source: &DBSource<'_>,
+    layout: &DatabaseLayoutFile,
+) -> Result<MetaLayout, NorenError> {
+    let textures = source.json::<TextureLayoutFile>(&layout.textures)?;
+    let atlases = source.json::<TextureAtlasLayoutFile>(&layout.atlases)?;
+    let msdf_fonts = source.json::<MsdfFontLayoutFile>(&layout.msdf_fonts)?;
+    let sdf_fonts = source.json::<SdfFontLayoutFile>(&layout.sdf_fonts)?;
+    let materials = source.json::<MaterialLayoutFile>(&layout.materials)?;
+    let meshes = source.json::<MeshLayoutFile>(&layout.meshes)?;
+    let models = source.json::<ModelLayoutFile>(&layout.models)?;
+    let shader_layouts = source.json::<ShaderLayoutFile>(&layout.shader_layouts)?;
     let mut meta_layout = MetaLayout::default();
     if let Some(file) = textures {
         meta_layout.textures = file.textures;
@@ -159,13 +351,7 @@ fn load_meta_layout(
         meta_layout.compute_shaders = file.compute_shaders;
     }
 
-    inject_default_layouts(&mut meta_layout);
-
-    if meta_layout.is_empty() {
-        Ok(None)
-    } else {
-        Ok(Some(meta_layout))
-    }
+    Ok(meta_layout)
 }
 
 /// Validates that shader, material, and attachment-format references in the layout are consistent.
@@ -174,11 +360,12 @@ pub fn validate_database_layout(
     layout_file: Option<&str>,
 ) -> Result<(), NorenError> {
     let layout = read_database_layout(layout_file)?;
-    let Some(meta_layout) = load_meta_layout(base_dir, &layout)? else {
+    let sources = [DBSource::Directory(base_dir)];
+    let Some(meta_layout) = load_meta_layout(&sources, &layout)? else {
         return Ok(());
     };
 
-    let shader_modules = ShaderDB::new(&format!("{}/{}", base_dir, layout.shaders));
+    let shader_modules = ShaderDB::from_layers(DBSource::layers(&sources, &layout.shaders));
     let shader_db_ref = shader_modules.has_data().then_some(&shader_modules);
 
     validate_meta_layout(&meta_layout, shader_db_ref)
@@ -203,19 +390,20 @@ impl DB {
 
     /// Creates a database handle that can load assets using the provided configuration.
     pub fn new_with_ctx(info: &DBInfo, ctx: Option<*mut Context>) -> Result<Self, NorenError> {
-        let layout = read_database_layout(info.layout_file)?;
+        let (sources, layout) = DBSource::open(info)?;
+        let layers = |module: &str| DBSource::layers(&sources, module);
 
-        let geometry = GeometryDB::builder(ctx, &format!("{}/{}", info.base_dir, layout.geometry))
+        let geometry = GeometryDBBuilder::from_layers(ctx, layers(&layout.geometry))
             .pooled_uploads(info.pooled_geometry_uploads)
             .build();
-        let imagery = ImageDB::new(ctx, &format!("{}/{}", info.base_dir, layout.imagery));
-        let audio = AudioDB::new(&format!("{}/{}", info.base_dir, layout.audio));
-        let fonts = FontDB::new(&format!("{}/{}", info.base_dir, layout.fonts));
-        let skeletons = SkeletonDB::new(&format!("{}/{}", info.base_dir, layout.skeletons));
-        let animations = AnimationDB::new(&format!("{}/{}", info.base_dir, layout.animations));
-        let shaders = ShaderDB::new(&format!("{}/{}", info.base_dir, layout.shaders));
-        let terrain = TerrainDB::new(&format!("{}/{}", info.base_dir, layout.terrain));
-        let meta_layout = load_meta_layout(info.base_dir, &layout)?;
+        let imagery = ImageDB::from_layers(ctx, layers(&layout.imagery));
+        let audio = AudioDB::from_layers(layers(&layout.audio));
+        let fonts = FontDB::from_layers(layers(&layout.fonts));
+        let skeletons = SkeletonDB::from_layers(layers(&layout.skeletons));
+        let animations = AnimationDB::from_layers(layers(&layout.animations));
+        let shaders = ShaderDB::from_layers(layers(&layout.shaders));
+        let terrain = TerrainDB::from_layers(layers(&layout.terrain));
+        let meta_layout = load_meta_layout(&sources, &layout)?;
 
         if let Some(layout) = meta_layout.as_ref() {
             let shader_db_ref = shaders.has_data().then_some(&shaders);
@@ -232,12 +420,14 @@ impl DB {
             shaders,
             terrain,
             ctx: None,
-            meta_layout,
+            meta_layout: meta_layout.map(Arc::new),
             graphics_pipeline_layouts: HashMap::new(),
             graphics_pipelines: HashMap::new(),
             compute_pipeline_layouts: HashMap::new(),
             compute_pipelines: HashMap::new(),
             furikake: None,
+            loader: None,
+            watch: DBWatch::new(info, layout),
         })
     }
 
@@ -336,6 +526,255 @@ impl DB {
         &mut self.shaders
     }
 
+    /// Returns a thread-safe handle to the host-side read paths.
+    ///
+    /// The handle shares the mapped files with this database and can be cloned
+    /// across threads; GPU uploads stay on `DB`.
+    pub fn reader(&self) -> DBReader {
+        DBReader::new(self)
+    }
+
+    /// Reports the GPU bytes held by the geometry and imagery caches.
+    ///
+    /// Budgets are set per module with [`GeometryDB::set_memory_budget`] and
+    /// [`ImageDB::set_memory_budget`].
+    pub fn memory_stats(&self) -> MemoryStats {
+        MemoryStats {
+            geometry: self.geometry.memory_stats(),
+            imagery: self.imagery.memory_stats(),
+        }
+    }
+
+    /// Remaps RDB modules and metadata JSON that changed on disk since they were loaded.
+    ///
+    /// Only modules whose files changed are remapped; the rest keep their
+    /// caches. Cached GPU copies of changed geometry and images are evicted
+    /// once nothing references them. Returns the
+    /// changed entry and layout names, in name order, so callers can re-fetch them.
+    /// On error the database keeps serving the previously loaded files.
+    pub fn reload_changed(&mut self) -> Result<Vec<String>, NorenError> {
+        let stamps = self.watch.current_stamps();
+        if stamps == self.watch.stamps {
+            return Ok(Vec::new());
+        }
+
+        let mut changed = BTreeSet::new();
+        {
+            let watch = &self.watch;
+            let overlays: Vec<&str> = watch.overlays.iter().map(String::as_str).collect();
+            let info = DBInfo {
+                base_dir: &watch.base_dir,
+                layout_file: None,
+                pooled_geometry_uploads: false,
+                bundle: watch.bundle.as_deref(),
+                overlays: &overlays,
+            };
+            let (sources, _) = DBSource::open(&info)?;
+            let layout = &watch.layout;
+            let layers = |module: &str| DBSource::layers(&sources, module);
+            let touched = |file: &str| watch.touched(&stamps, file);
+
+            // Validate new metadata before swapping anything in.
+            let shaders =
+                touched(&layout.shaders).then(|| ShaderDB::from_layers(layers(&layout.shaders)));
+            let meta_layout = layout
+                .metadata_files()
+                .into_iter()
+                .any(touched)
+                .then(|| load_meta_layout(&sources, layout))
+                .transpose()?;
+            if shaders.is_some() || meta_layout.is_some() {
+                let shaders = shaders.as_ref().unwrap_or(&self.shaders);
+                let meta = match &meta_layout {
+                    Some(meta_layout) => meta_layout.as_ref(),
+                    None => self.meta_layout.as_deref(),
+                };
+                if let Some(meta) = meta {
+                    validate_meta_layout(meta, shaders.has_data().then_some(shaders))?;
+                }
+            }
+
+            if touched(&layout.geometry) {
+                let geometry = layers(&layout.geometry);
+                let names = geometry.changed_entries(self.geometry.layers());
+                self.geometry.reload_layers(geometry, &names);
+                changed.extend(names);
+            }
+
+            if touched(&layout.imagery) {
+                let imagery = layers(&layout.imagery);
+                let names = imagery.changed_entries(self.imagery.layers());
+                self.imagery.reload_layers(imagery, &names);
+                changed.extend(names);
+            }
+
+            if touched(&layout.audio) {
+                let audio = AudioDB::from_layers(layers(&layout.audio));
+                changed.extend(audio.layers().changed_entries(self.audio.layers()));
+                self.audio = audio;
+            }
+
+            if touched(&layout.fonts) {
+                let fonts = FontDB::from_layers(layers(&layout.fonts));
+                changed.extend(fonts.layers().changed_entries(self.fonts.layers()));
+                self.fonts = fonts;
+            }
+
+            if touched(&layout.skeletons) {
+                let skeletons = SkeletonDB::from_layers(layers(&layout.skeletons));
+                changed.extend(skeletons.layers().changed_entries(self.skeletons.layers()));
+                self.skeletons = skeletons;
+            }
+
+            if touched(&layout.animations) {
+                let animations = AnimationDB::from_layers(layers(&layout.animations));
+                changed.extend(
+                    animations
+                        .layers()
+                        .changed_entries(self.animations.layers()),
+                );
+                self.animations = animations;
+            }
+
+            if let Some(shaders) = shaders {
+                changed.extend(shaders.layers().changed_entries(self.shaders.layers()));
+                self.shaders = shaders;
+            }
+
+            if touched(&layout.terrain) {
+                let terrain = TerrainDB::from_layers(layers(&layout.terrain));
+                changed.extend(terrain.layers().changed_entries(self.terrain.layers()));
+                self.terrain = terrain;
+            }
+
+            if let Some(meta_layout) = meta_layout {
+                let empty = MetaLayout::default();
+                changed.extend(
+                    meta_layout
+                        .as_ref()
+                        .unwrap_or(&empty)
+                        .changed_entries(self.meta_layout.as_deref().unwrap_or(&empty)),
+                );
+                self.meta_layout = meta_layout.map(Arc::new);
+            }
+        }
+
+        self.watch.stamps = stamps;
+        info!(changed = changed.len(), "reloaded database files");
+        Ok(changed.into_iter().collect())
+    }
+
+    /// Queues host decoding of `entry` on the loader's worker threads.
+    ///
+    /// The result is handed back by [`DB::poll_completed`]. Zero-copy entries
+    /// written by [`add_mapped_geometry`] or [`add_mapped_image`] are rebuilt on
+    /// the worker through a [`DBReader`]. Entries that only exist as built-in
+    /// defaults are resolved immediately. GPU uploads stay with the caller, e.g.
+    /// through [`GeometryDB::enter_gpu_geometry`] or [`ImageDB::enter_gpu_image`].
+    pub fn request_async(&mut self, entry: DatabaseEntry<'_>) -> AssetTicket {
+        let job = self.async_job(entry);
+
+        let loader = self.loader.get_or_insert_with(AsyncLoader::new);
+        match job {
+            Ok(job) => loader.submit(entry, job),
+            Err(result) => loader.complete(entry, result),
+        }
+    }
+
+    /// Picks how a loader thread resolves `entry` for [`DB::request_async`].
+    fn async_job(
+        &mut self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<LoadJob, Result<HostAsset, NorenError>> {
+        // Mapped entries keep their data in sidecar POD slices, so decoding the
+        // primary entry alone would hand back empty vertices or pixels.
+        if self.geometry.contains(&mapped_vertices_entry(entry, None)) {
+            let (reader, entry) = (self.reader(), entry.to_string());
+            return Ok(Box::new(move || {
+                reader.fetch_raw_geometry(&entry).map(HostAsset::Geometry)
+            }));
+        }
+        if self.imagery.contains(&mapped_pixels_entry(entry)) {
+            let (reader, entry) = (self.reader(), entry.to_string());
+            return Ok(Box::new(move || {
+                reader.fetch_raw_image(&entry).map(HostAsset::Image)
+            }));
+        }
+
+        match self.raw_entry(entry).filter(HostAsset::decodable) {
+            Some(raw) => Ok(Box::new(move || HostAsset::decode(&raw))),
+            None => Err(self.fetch_host_asset(entry)),
+        }
+    }
+
+    /// Returns the async requests finished since the last poll.
+    pub fn poll_completed(&mut self) -> Vec<CompletedAsset> {
+        self.loader
+            .as_mut()
+            .map(AsyncLoader::poll)
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of async requests not yet returned by [`DB::poll_completed`].
+    pub fn pending_async(&self) -> usize {
+        self.loader.as_ref().map_or(0, AsyncLoader::pending)
+    }
+
+    /// Detaches `entry` from the first module database that stores it.
+    fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.geometry
+            .raw_entry(entry)
+            .or_else(|| self.imagery.raw_entry(entry))
+            .or_else(|| self.audio.raw_entry(entry))
+            .or_else(|| self.fonts.raw_entry(entry))
+            .or_else(|| self.skeletons.raw_entry(entry))
+            .or_else(|| self.animations.raw_entry(entry))
+            .or_else(|| self.shaders.raw_entry(entry))
+            .or_else(|| self.terrain.raw_entry(entry))
+    }
+
+    /// Resolves `entry` on the calling thread for [`DB::request_async`].
+    fn fetch_host_asset(&mut self, entry: DatabaseEntry<'_>) -> Result<HostAsset, NorenError> {
+        if self.geometry.contains(entry) {
+            return self
+                .geometry
+                .fetch_raw_geometry(entry)
+                .map(HostAsset::Geometry);
+        }
+        if self.imagery.contains(entry) {
+            return match self.imagery.fetch_raw_image(entry) {
+                Ok(image) => Ok(HostAsset::Image(image)),
+                Err(_) => self
+                    .imagery
+                    .fetch_raw_cubemap(entry)
+                    .map(HostAsset::Cubemap),
+            };
+        }
+        if self.audio.contains(entry) {
+            return self.audio.fetch_clip(entry).map(HostAsset::Audio);
+        }
+        if self.fonts.contains(entry) {
+            return self.fonts.fetch_font(entry).map(HostAsset::Font);
+        }
+        if self.skeletons.contains(entry) {
+            return self
+                .skeletons
+                .fetch_skeleton(entry)
+                .map(HostAsset::Skeleton);
+        }
+        if self.animations.contains(entry) {
+            return self
+                .animations
+                .fetch_animation(entry)
+                .map(HostAsset::Animation);
+        }
+        if self.shaders.contains(entry) {
+            return self.shaders.fetch_module(entry).map(HostAsset::Shader);
+        }
+
+        Err(NorenError::LookupFailure())
+    }
+
     /// Enumerates all font entries available in the backing database.
     pub fn enumerate_fonts(&self) -> Vec<String> {
         self.fonts.enumerate_entries()
@@ -369,6 +808,11 @@ impl DB {
         self.audio.fetch_sound_track(entry)
     }
 
+    /// Fetches a sound clip and opens an incremental PCM decoder over it.
+    pub fn fetch_pcm_stream(&mut self, entry: DatabaseEntry<'_>) -> Result<PcmStream, NorenError> {
+        self.audio.fetch_pcm_stream(entry)
+    }
+
     /// Enumerates skeleton assets available in the backing database.
     pub fn enumerate_skeletons(&self) -> Vec<String> {
         self.skeletons.enumerate_entries()
@@ -516,7 +960,7 @@ impl DB {
     /// Enumerates logical texture definitions declared in the model layout.
     pub fn enumerate_textures(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.textures.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -524,7 +968,7 @@ impl DB {
     /// Enumerates texture atlas definitions declared in the model layout.
     pub fn enumerate_atlases(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.atlases.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -532,7 +976,7 @@ impl DB {
     /// Enumerates MSDF font definitions declared in the layout.
     pub fn enumerate_msdf_fonts(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.msdf_fonts.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -540,7 +984,7 @@ impl DB {
     /// Enumerates SDF font definitions declared in the layout.
     pub fn enumerate_sdf_fonts(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.sdf_fonts.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -548,7 +992,7 @@ impl DB {
     /// Enumerates material definitions declared in the model layout.
     pub fn enumerate_materials(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.materials.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -556,7 +1000,7 @@ impl DB {
     /// Enumerates mesh definitions declared in the model layout.
     pub fn enumerate_meshes(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.meshes.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -564,7 +1008,7 @@ impl DB {
     /// Enumerates model definitions declared in the model layout.
     pub fn enumerate_models(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.models.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -572,7 +1016,7 @@ impl DB {
     /// Enumerates graphics shader definitions declared in the model layout.
     pub fn enumerate_shaders(&self) -> Vec<String> {
         self.meta_layout
-            .as_ref()
+            .as_deref()
             .map(|layout| layout.shaders.keys().cloned().collect())
             .unwrap_or_default()
     }
@@ -628,7 +1072,7 @@ impl DB {
         if self.furikake.is_some() {
             let mesh_keys = self
                 .meta_layout
-                .as_ref()
+                .as_deref()
                 .and_then(|layout| layout.models.get(entry))
                 .map(|model| model.meshes.clone())
                 .ok_or_else(NorenError::LookupFailure)?;
@@ -683,7 +1127,7 @@ impl DB {
     ) -> Result<(), NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(NorenError::LookupFailure)?;
         let mesh_def = layout
             .meshes
@@ -758,34 +1202,15 @@ impl DB {
         &mut self,
         entry: DatabaseEntry<'_>,
     ) -> Result<Option<HostRig>, NorenError> {
-        let skeleton_entry = model_rig_entry(entry, "skeletons/");
-        if !self
-            .skeletons
-            .enumerate_entries()
-            .iter()
-            .any(|key| key == &skeleton_entry)
-        {
-            return Ok(None);
-        }
-
-        let skeleton = self.skeletons.fetch_skeleton(&skeleton_entry)?;
-        let animation_prefix = model_rig_entry(entry, "animations/");
-        let animation_prefix_folder = format!("{animation_prefix}/");
-        let mut animations = HashMap::new();
-        for animation_entry in self
-            .animations
-            .enumerate_entries()
-            .into_iter()
-            .filter(|key| key == &animation_prefix || key.starts_with(&animation_prefix_folder))
-        {
-            let animation = self.animations.fetch_animation(&animation_entry)?;
-            animations.insert(animation_entry, animation);
-        }
-
-        Ok(Some(HostRig {
-            skeleton,
-            animations,
-        }))
+        let skeletons = &mut self.skeletons;
+        let animations = &mut self.animations;
+        assemble_host_rig(
+            entry,
+            &skeletons.enumerate_entries(),
+            &animations.enumerate_entries(),
+            |skeleton_entry| skeletons.fetch_skeleton(skeleton_entry),
+            |animation_entry| animations.fetch_animation(animation_entry),
+        )
     }
 
     fn load_device_rigging(
@@ -905,7 +1330,7 @@ impl DB {
     pub fn fetch_texture_atlas(&mut self, entry: &str) -> Result<HostTextureAtlas, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(NorenError::LookupFailure)?;
 
         let atlas_def = layout
@@ -936,7 +1361,7 @@ impl DB {
     ) -> Result<DeviceTextureAtlas, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(NorenError::LookupFailure)?;
 
         let atlas_def = layout
@@ -970,7 +1395,7 @@ impl DB {
     pub fn fetch_msdf_font(&mut self, entry: &str) -> Result<MSDFFont, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(NorenError::LookupFailure)?;
 
         let font_def = layout
@@ -998,7 +1423,7 @@ impl DB {
     pub fn fetch_gpu_msdf_font(&mut self, entry: &str) -> Result<DeviceMSDFFont, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(NorenError::LookupFailure)?;
 
         let font_def = layout
@@ -1032,7 +1457,7 @@ impl DB {
     pub fn fetch_sdf_font(&mut self, entry: &str) -> Result<SDFFont, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(NorenError::LookupFailure)?;
 
         let font_def = layout
@@ -1060,7 +1485,7 @@ impl DB {
     pub fn fetch_gpu_sdf_font(&mut self, entry: &str) -> Result<DeviceSDFFont, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(NorenError::LookupFailure)?;
 
         let font_def = layout
@@ -1098,7 +1523,7 @@ impl DB {
     pub fn fetch_material_layout(&self, entry: &str) -> Result<MaterialLayout, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(|| NorenError::LookupFailure())?;
 
         layout
@@ -1118,18 +1543,17 @@ impl DB {
     ) -> Result<(HostMaterial, Option<dashi::Handle<FurikakeMaterial>>), NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(|| NorenError::LookupFailure())?;
 
-        let (name, textures, material) = build_material_components(
-            layout,
-            &mut self.imagery,
-            entry,
-            &mut |name, image, _furikake_id| HostTexture { name, image },
-            &mut |imagery, tex_entry| imagery.fetch_raw_image(tex_entry),
-            None,
-        )?
-        .ok_or_else(NorenError::LookupFailure)?;
+        let (name, textures, material) =
+            build_material_components(layout, entry, &mut |name, image_entry| {
+                Ok(HostTexture {
+                    name,
+                    image: self.imagery.fetch_raw_image(image_entry)?,
+                })
+            })?
+            .ok_or_else(NorenError::LookupFailure)?;
 
         let furikake_handle = self.ensure_furikake_material(entry).transpose()?;
 
@@ -1150,18 +1574,20 @@ impl DB {
     pub fn fetch_device_material(&mut self, entry: &str) -> Result<DeviceMaterial, NorenError> {
         let layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(|| NorenError::LookupFailure())?;
 
-        let (_name, textures, material) = build_material_components(
-            layout,
-            &mut self.imagery,
-            entry,
-            &mut |_, image, furikake_id| DeviceTexture::new(image, furikake_id),
-            &mut |imagery, tex_entry| imagery.fetch_gpu_image(tex_entry),
-            self.furikake.as_mut(),
-        )?
-        .ok_or_else(NorenError::LookupFailure)?;
+        let (_name, textures, material) =
+            build_material_components(layout, entry, &mut |_, image_entry| {
+                let image = self.imagery.fetch_gpu_image(image_entry)?;
+                let furikake_id = ensure_furikake_texture(
+                    &mut self.imagery,
+                    self.furikake.as_mut(),
+                    image_entry,
+                )?;
+                Ok(DeviceTexture::new(image, furikake_id))
+            })?
+            .ok_or_else(NorenError::LookupFailure)?;
 
         let furikake_handle = self.ensure_furikake_material(entry).transpose()?;
 
@@ -1176,7 +1602,7 @@ impl DB {
         let shader_layout = {
             let layout = self
                 .meta_layout
-                .as_ref()
+                .as_deref()
                 .ok_or_else(|| NorenError::LookupFailure())?;
             layout
                 .shaders
@@ -1215,7 +1641,7 @@ impl DB {
             return Some(Ok(*handle));
         }
 
-        let layout = match self.meta_layout.as_ref() {
+        let layout = match self.meta_layout.as_deref() {
             Some(layout) => layout,
             None => return Some(Err(NorenError::LookupFailure())),
         };
@@ -1333,6 +1759,43 @@ fn model_rig_entry(entry: &str, prefix: &str) -> String {
     format!("{prefix}{suffix}")
 }
 
+/// Gathers the skeleton and animations stored under a model's rig entries.
+///
+/// Returns `None` when no skeleton exists for the model.
+fn assemble_host_rig<FetchSkeleton, FetchAnimation>(
+    entry: DatabaseEntry<'_>,
+    skeleton_entries: &[String],
+    animation_entries: &[String],
+    mut fetch_skeleton: FetchSkeleton,
+    mut fetch_animation: FetchAnimation,
+) -> Result<Option<HostRig>, NorenError>
+where
+    FetchSkeleton: FnMut(DatabaseEntry<'_>) -> Result<Skeleton, NorenError>,
+    FetchAnimation: FnMut(DatabaseEntry<'_>) -> Result<AnimationClip, NorenError>,
+{
+    let skeleton_entry = model_rig_entry(entry, "skeletons/");
+    if !skeleton_entries.iter().any(|key| key == &skeleton_entry) {
+        return Ok(None);
+    }
+
+    let skeleton = fetch_skeleton(skeleton_entry.as_str())?;
+    let animation_prefix = model_rig_entry(entry, "animations/");
+    let animation_prefix_folder = format!("{animation_prefix}/");
+    let mut animations = HashMap::new();
+    for animation_entry in animation_entries
+        .iter()
+        .filter(|key| *key == &animation_prefix || key.starts_with(&animation_prefix_folder))
+    {
+        let animation = fetch_animation(animation_entry.as_str())?;
+        animations.insert(animation_entry.clone(), animation);
+    }
+
+    Ok(Some(HostRig {
+        skeleton,
+        animations,
+    }))
+}
+
 fn to_furikake_joint(joint: &Joint) -> JointTransform {
     let translation = Vec3::from(joint.translation);
     let scale = Vec3::from(joint.scale);
@@ -1631,30 +2094,24 @@ fn vertex_buffer_slot(vertices: &[Vertex]) -> VertexBufferSlot {
     }
 }
 
-fn append_texture_bindings<Texture, Image, MakeTexture, FetchImage>(
+/// Resolves each texture key that names an image, handing `resolve_texture`
+/// the texture name and image entry.
+fn append_texture_bindings<Texture, ResolveTexture>(
     output: &mut Vec<Texture>,
     keys: &[String],
     layout: &MetaLayout,
-    imagery: &mut ImageDB,
-    make_texture: &mut MakeTexture,
-    fetch_image: &mut FetchImage,
-    furikake: Option<&mut FurikakeBindings>,
+    resolve_texture: &mut ResolveTexture,
 ) -> Result<(), NorenError>
 where
-    MakeTexture: FnMut(String, Image, Option<u16>) -> Texture,
-    FetchImage: FnMut(&mut ImageDB, DatabaseEntry<'_>) -> Result<Image, NorenError>,
+    ResolveTexture: FnMut(String, DatabaseEntry<'_>) -> Result<Texture, NorenError>,
 {
-    let mut furikake = furikake;
     for tex_key in keys {
         if let Some(tex_def) = layout.textures.get(tex_key) {
             if tex_def.image.is_empty() {
                 continue;
             }
-            let image = fetch_image(imagery, tex_def.image.as_str())?;
             let name = tex_def.name.clone().unwrap_or_else(|| tex_key.clone());
-            let furikake_id =
-                ensure_furikake_texture(imagery, furikake.as_deref_mut(), tex_def.image.as_str())?;
-            output.push(make_texture(name, image, furikake_id));
+            output.push(resolve_texture(name, tex_def.image.as_str())?);
         }
     }
     Ok(())
@@ -1779,19 +2236,14 @@ fn validate_meta_layout(
     validate_shader_layouts(layout, shader_modules)
 }
 
-fn build_material_components<Texture, Image, MakeTexture, FetchImage>(
+fn build_material_components<Texture, ResolveTexture>(
     layout: &MetaLayout,
-    imagery: &mut ImageDB,
     material_key: &str,
-    make_texture: &mut MakeTexture,
-    fetch_image: &mut FetchImage,
-    furikake: Option<&mut FurikakeBindings>,
+    resolve_texture: &mut ResolveTexture,
 ) -> Result<Option<(String, Vec<Texture>, FurikakeMaterial)>, NorenError>
 where
-    MakeTexture: FnMut(String, Image, Option<u16>) -> Texture,
-    FetchImage: FnMut(&mut ImageDB, DatabaseEntry<'_>) -> Result<Image, NorenError>,
+    ResolveTexture: FnMut(String, DatabaseEntry<'_>) -> Result<Texture, NorenError>,
 {
-    let mut furikake = furikake;
     let Some(material_def) = layout.materials.get(material_key) else {
         return Ok(None);
     };
@@ -1824,12 +2276,9 @@ where
         let id = if let Some(id) = lookup_indices.get(tex_key) {
             *id
         } else {
-            let image = fetch_image(imagery, tex_def.image.as_str())?;
             let name = tex_def.name.clone().unwrap_or_else(|| tex_key.to_string());
-            let furikake_id =
-                ensure_furikake_texture(imagery, furikake.as_deref_mut(), tex_def.image.as_str())?;
             let id = textures.len() as u16;
-            textures.push(make_texture(name, image, furikake_id));
+            textures.push(resolve_texture(name, tex_def.image.as_str())?);
             lookup_indices.insert(tex_key.to_string(), id);
             id
         } as u32;
@@ -1853,6 +2302,67 @@ where
     Ok(Some((name, textures, furikake_material)))
 }
 
+/// Geometry and textures of a mesh entry, plus its material key and components.
+struct MeshComponents<Geometry, Texture> {
+    name: String,
+    geometry: Geometry,
+    textures: Vec<Texture>,
+    material: Option<(String, (String, Vec<Texture>, FurikakeMaterial))>,
+}
+
+/// Resolves a mesh entry of the meta layout, returning `None` for unknown meshes
+/// and meshes without geometry.
+///
+/// A material override that does not exist is an error; a missing layout
+/// material leaves the mesh without one.
+fn resolve_mesh_components<Geometry, Texture, FetchGeometry, ResolveTexture>(
+    layout: &MetaLayout,
+    mesh_key: &str,
+    material_override: Option<&str>,
+    fetch_geometry: &mut FetchGeometry,
+    resolve_texture: &mut ResolveTexture,
+) -> Result<Option<MeshComponents<Geometry, Texture>>, NorenError>
+where
+    FetchGeometry: FnMut(DatabaseEntry<'_>) -> Result<Geometry, NorenError>,
+    ResolveTexture: FnMut(String, DatabaseEntry<'_>) -> Result<Texture, NorenError>,
+{
+    let Some(mesh_def) = layout.meshes.get(mesh_key) else {
+        return Ok(None);
+    };
+
+    if mesh_def.geometry.is_empty() {
+        return Ok(None);
+    }
+
+    let geometry = fetch_geometry(mesh_def.geometry.as_str())?;
+    let name = mesh_def
+        .name
+        .clone()
+        .unwrap_or_else(|| mesh_key.to_string());
+
+    let mut textures = Vec::new();
+    append_texture_bindings(&mut textures, &mesh_def.textures, layout, resolve_texture)?;
+
+    let material_key = material_override.or(mesh_def.material.as_deref());
+    let material = match material_key {
+        Some(material_key) => {
+            match build_material_components(layout, material_key, resolve_texture)? {
+                Some(components) => Some((material_key.to_string(), components)),
+                None if material_override.is_some() => return Err(NorenError::LookupFailure()),
+                None => None,
+            }
+        }
+        None => None,
+    };
+
+    Ok(Some(MeshComponents {
+        name,
+        geometry,
+        textures,
+        material,
+    }))
+}
+
 #[derive(Copy, Clone)]
 enum MaterialTextureSlot {
     BaseColor,
@@ -2024,92 +2534,58 @@ impl DB {
         ) -> Material,
         MakeMesh: FnMut(String, Geometry, Vec<Texture>, Option<Material>) -> Mesh,
     {
-        let (mesh_name, geometry, mesh_textures, material_key, material_components) = {
+        let components = {
             let layout = self
                 .meta_layout
-                .as_ref()
+                .as_deref()
                 .ok_or_else(|| NorenError::LookupFailure())?;
-
-            let mesh_def = match layout.meshes.get(mesh_key) {
-                Some(mesh) => mesh,
-                None => return Ok(None),
-            };
-
-            if mesh_def.geometry.is_empty() {
-                return Ok(None);
-            }
-
-            let geometry = fetch_geometry(&mut self.geometry, mesh_def.geometry.as_str())?;
-            let mesh_name = mesh_def
-                .name
-                .clone()
-                .unwrap_or_else(|| mesh_key.to_string());
-
-            let mut mesh_textures = Vec::new();
-            append_texture_bindings(
-                &mut mesh_textures,
-                &mesh_def.textures,
-                layout,
-                &mut self.imagery,
-                make_texture,
-                fetch_image,
-                if use_furikake {
-                    self.furikake.as_mut()
-                } else {
-                    None
-                },
-            )?;
-
-            let material_key = material_override
-                .or_else(|| mesh_def.material.as_deref())
-                .map(str::to_string);
-            let material_components = if let Some(material_key) = material_key.as_deref() {
-                match build_material_components(
-                    layout,
-                    &mut self.imagery,
-                    material_key,
-                    make_texture,
-                    fetch_image,
-                    if use_furikake {
-                        self.furikake.as_mut()
-                    } else {
-                        None
-                    },
-                )? {
-                    Some((name, textures, material)) => Some((name, textures, material)),
-                    None if material_override.is_some() => return Err(NorenError::LookupFailure()),
-                    None => None,
-                }
+            let geometry_db = &mut self.geometry;
+            let imagery = &mut self.imagery;
+            let mut furikake = if use_furikake {
+                self.furikake.as_mut()
             } else {
                 None
             };
 
-            Ok::<_, NorenError>((
-                mesh_name,
-                geometry,
-                mesh_textures,
-                material_key,
-                material_components,
-            ))?
+            resolve_mesh_components(
+                layout,
+                mesh_key,
+                material_override,
+                &mut |entry| fetch_geometry(geometry_db, entry),
+                &mut |name, image_entry| {
+                    let image = fetch_image(imagery, image_entry)?;
+                    let furikake_id =
+                        ensure_furikake_texture(imagery, furikake.as_deref_mut(), image_entry)?;
+                    Ok(make_texture(name, image, furikake_id))
+                },
+            )?
+        };
+        let Some(MeshComponents {
+            name: mesh_name,
+            geometry,
+            textures: mesh_textures,
+            material,
+        }) = components
+        else {
+            return Ok(None);
         };
 
-        let material = if let (Some(material_key), Some((name, textures, material))) =
-            (material_key.as_deref(), material_components)
-        {
-            let furikake_handle = if use_furikake {
-                self.ensure_furikake_material(material_key).transpose()?
-            } else {
-                None
-            };
-            Some(make_material(
-                name,
-                textures,
-                material,
-                material_key,
-                furikake_handle,
-            ))
-        } else {
-            None
+        let material = match material {
+            Some((material_key, (name, textures, material))) => {
+                let furikake_handle = if use_furikake {
+                    self.ensure_furikake_material(&material_key).transpose()?
+                } else {
+                    None
+                };
+                Some(make_material(
+                    name,
+                    textures,
+                    material,
+                    &material_key,
+                    furikake_handle,
+                ))
+            }
+            None => None,
         };
 
         Ok(Some(make_mesh(
@@ -2157,7 +2633,7 @@ impl DB {
         let (model_name, mesh_keys) = {
             let layout = self
                 .meta_layout
-                .as_ref()
+                .as_deref()
                 .ok_or_else(|| NorenError::LookupFailure())?;
 
             let model = layout
@@ -2323,7 +2799,7 @@ impl DB {
     ) -> Result<GraphicsPipelineInputs, NorenError> {
         let shader_layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(|| NorenError::LookupFailure())?
             .shaders
             .get(shader_key)
@@ -2405,7 +2881,7 @@ impl DB {
     ) -> Result<ComputePipelineInputs, NorenError> {
         let shader_layout = self
             .meta_layout
-            .as_ref()
+            .as_deref()
             .ok_or_else(|| NorenError::LookupFailure())?
             .compute_shaders
             .get(shader_key)
@@ -2714,10 +3190,7 @@ mod tests {
             format: dashi::Format::RGBA8,
             mip_levels: 1,
         };
-        let host_image = HostImage {
-            info: image_info,
-            data: vec![255, 255, 255, 255],
-        };
+        let host_image = HostImage::new(image_info, vec![255, 255, 255, 255]);
         let mut img_rdb = RDBFile::new();
         img_rdb.add(IMAGE_ENTRY, &host_image)?;
         img_rdb.save(base_dir.join("imagery.rdb"))?;
@@ -2740,6 +3213,8 @@ mod tests {
             base_dir: base_dir.to_str().expect("base dir to str"),
             layout_file: None,
             pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[],
         };
 
         let mut db = DB::new(&db_info)?;
@@ -3013,10 +3488,7 @@ mod tests {
             format: dashi::Format::RGBA8,
             mip_levels: 1,
         };
-        let host_image = HostImage {
-            info: image_info,
-            data: vec![255, 255, 255, 255],
-        };
+        let host_image = HostImage::new(image_info, vec![255, 255, 255, 255]);
         let mut img_rdb = RDBFile::new();
         img_rdb.add(IMAGE_ENTRY, &host_image)?;
         img_rdb.save(base.join("imagery.rdb"))?;
@@ -3030,6 +3502,8 @@ mod tests {
             base_dir: base.to_str().unwrap(),
             layout_file: None,
             pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[],
         };
 
         let mut db = DB::new_with_ctx(&db_info, Some(&mut ctx))?;
@@ -3099,10 +3573,7 @@ mod tests {
             format: dashi::Format::RGBA8,
             mip_levels: 1,
         };
-        let host_image = HostImage {
-            info: image_info,
-            data: vec![255, 255, 255, 255],
-        };
+        let host_image = HostImage::new(image_info, vec![255, 255, 255, 255]);
         let mut img_rdb = RDBFile::new();
         img_rdb.add(IMAGE_ENTRY, &host_image)?;
         img_rdb.save(base.join("imagery.rdb"))?;
@@ -3116,6 +3587,8 @@ mod tests {
             base_dir: base.to_str().unwrap(),
             layout_file: None,
             pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[],
         };
 
         let mut db = DB::new_with_ctx(&db_info, Some(&mut ctx))?;
@@ -3131,4 +3604,181 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn request_async_hands_back_host_assets() -> Result<(), NorenError> {
+        let tmp = tempdir().unwrap();
+        let base = tmp.path();
+
+        let mut audio = RDBFile::new();
+        let clip = AudioClip::new("audio/custom".into(), AudioFormat::default(), vec![4, 5]);
+        audio.add("audio/custom", &clip)?;
+        audio.save(base.join("audio.rdb"))?;
+
+        let db_info = DBInfo {
+            base_dir: base.to_str().unwrap(),
+            layout_file: None,
+            pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[],
+        };
+        let mut db = DB::new(&db_info)?;
+        assert!(db.poll_completed().is_empty());
+
+        let custom = db.request_async("audio/custom");
+        let skeleton = db.request_async(defaults::DEFAULT_SKELETON_ENTRY);
+        let missing = db.request_async("audio/missing");
+        assert_eq!(db.pending_async(), 3);
+
+        let mut completed = HashMap::new();
+        while db.pending_async() > 0 {
+            for done in db.poll_completed() {
+                completed.insert(done.ticket, done);
+            }
+            std::thread::yield_now();
+        }
+
+        match &completed[&custom].result {
+            Ok(HostAsset::Audio(clip)) => assert_eq!(clip.data, vec![4, 5]),
+            _ => panic!("expected the stored audio clip"),
+        }
+        assert!(matches!(
+            completed[&skeleton].result,
+            Ok(HostAsset::Skeleton(_))
+        ));
+        assert_eq!(completed[&missing].entry, "audio/missing");
+        assert!(matches!(
+            completed[&missing].result,
+            Err(NorenError::LookupFailure())
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn request_async_resolves_mapped_entries() -> Result<(), NorenError> {
+        let tmp = tempdir().unwrap();
+        let base = tmp.path();
+
+        let geom = HostGeometry {
+            vertices: vec![sample_vertex(0.0), sample_vertex(1.0), sample_vertex(2.0)],
+            indices: Some(vec![0, 1, 2]),
+            ..Default::default()
+        }
+        .with_counts();
+        let mut geom_rdb = RDBFile::new();
+        add_mapped_geometry(&mut geom_rdb, GEOMETRY_ENTRY, &geom)?;
+        geom_rdb.save(base.join("geometry.rdb"))?;
+
+        let image_info = ImageInfo {
+            name: IMAGE_ENTRY.to_string(),
+            dim: [1, 1, 1],
+            layers: 1,
+            format: dashi::Format::RGBA8,
+            mip_levels: 1,
+        };
+        let host_image = HostImage::new(image_info, vec![1, 2, 3, 4]);
+        let mut img_rdb = RDBFile::new();
+        add_mapped_image(&mut img_rdb, IMAGE_ENTRY, &host_image)?;
+        img_rdb.save(base.join("imagery.rdb"))?;
+
+        let db_info = DBInfo {
+            base_dir: base.to_str().unwrap(),
+            layout_file: None,
+            pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[],
+        };
+        let mut db = DB::new(&db_info)?;
+
+        let geometry = db.request_async(GEOMETRY_ENTRY);
+        let image = db.request_async(IMAGE_ENTRY);
+
+        let mut completed = HashMap::new();
+        while db.pending_async() > 0 {
+            for done in db.poll_completed() {
+                completed.insert(done.ticket, done);
+            }
+            std::thread::yield_now();
+        }
+
+        match &completed[&geometry].result {
+            Ok(HostAsset::Geometry(loaded)) => {
+                assert_eq!(loaded.vertices.len(), 3);
+                assert_eq!(loaded.indices, geom.indices);
+                assert_eq!(loaded.vertex_count, 3);
+            }
+            _ => panic!("expected the mapped geometry"),
+        }
+        match &completed[&image].result {
+            Ok(HostAsset::Image(loaded)) => assert_eq!(loaded.data, vec![1, 2, 3, 4]),
+            _ => panic!("expected the mapped image"),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn reload_changed_remaps_modified_files() -> Result<(), NorenError> {
+        let tmp = tempdir().unwrap();
+        let base = tmp.path();
+        let save_audio = |clips: &[(&str, Vec<u8>)]| -> Result<(), NorenError> {
+            let mut audio = RDBFile::new();
+            for (name, data) in clips {
+                let clip = AudioClip::new(name.to_string(), AudioFormat::default(), data.clone());
+                audio.add(name, &clip)?;
+            }
+            // Replace the file instead of truncating the mapped one.
+            audio.save(base.join("audio.staged"))?;
+            std::fs::rename(base.join("audio.staged"), base.join("audio.rdb"))?;
+            Ok(())
+        };
+        save_audio(&[("audio/custom", vec![1]), ("audio/other", vec![2])])?;
+
+        let db_info = DBInfo {
+            base_dir: base.to_str().unwrap(),
+            layout_file: None,
+            pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[],
+        };
+        let mut db = DB::new(&db_info)?;
+        assert!(db.reload_changed()?.is_empty());
+        let font_defaults = db.font().shared_defaults();
+
+        save_audio(&[
+            ("audio/custom", vec![1, 2, 3]),
+            ("audio/other", vec![2]),
+            ("audio/extra", vec![4]),
+        ])?;
+        let mut textures = TextureLayoutFile::default();
+        textures.textures.insert(
+            "texture/reloaded".into(),
+            TextureLayout {
+                image: defaults::DEFAULT_IMAGE_ENTRY.into(),
+                name: None,
+            },
+        );
+        std::fs::write(
+            base.join("textures.json"),
+            serde_json::to_vec(&textures).unwrap(),
+        )?;
+
+        assert_eq!(
+            db.reload_changed()?,
+            vec!["audio/custom", "audio/extra", "texture/reloaded"]
+        );
+        assert_eq!(db.fetch_sound_clip("audio/custom")?.data, vec![1, 2, 3]);
+        assert!(
+            db.enumerate_textures()
+                .contains(&"texture/reloaded".to_string())
+        );
+        assert!(
+            Arc::ptr_eq(&font_defaults, &db.font().shared_defaults()),
+            "unchanged modules are not rebuilt"
+        );
+        assert!(db.reload_changed()?.is_empty());
+
+        Ok(())
+    }
 }
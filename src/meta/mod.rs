@@ -5,6 +5,7 @@ pub mod meshes;
 pub mod models;
 pub mod names;
 pub mod shaders;
+pub mod text;
 pub mod textures;
 
 pub use atlases::*;
@@ -14,4 +15,5 @@ pub use meshes::*;
 pub use models::*;
 pub use names::*;
 pub use shaders::*;
+pub use text::*;
 pub use textures::*;
@@ -0,0 +1,370 @@
+use crate::parsing::{
+    FontBounds, FontGlyph, FontKerning, FontMetrics, MsdfFontLayout, SdfFontLayout,
+};
+
+/// Glyph data shared by SDF and MSDF font layouts.
+pub trait TextFont {
+    fn metrics(&self) -> &FontMetrics;
+    fn glyphs(&self) -> &[FontGlyph];
+    fn kerning(&self) -> &[FontKerning];
+}
+
+impl TextFont for MsdfFontLayout {
+    fn metrics(&self) -> &FontMetrics {
+        &self.metrics
+    }
+
+    fn glyphs(&self) -> &[FontGlyph] {
+        &self.glyphs
+    }
+
+    fn kerning(&self) -> &[FontKerning] {
+        &self.kerning
+    }
+}
+
+impl TextFont for SdfFontLayout {
+    fn metrics(&self) -> &FontMetrics {
+        &self.metrics
+    }
+
+    fn glyphs(&self) -> &[FontGlyph] {
+        &self.glyphs
+    }
+
+    fn kerning(&self) -> &[FontKerning] {
+        &self.kerning
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+/// A glyph placed by [`layout_text`].
+#[derive(Debug, Clone)]
+pub struct GlyphQuad {
+    /// Code point of the drawn glyph, which differs from the text for
+    /// characters that fell back to U+FFFD or `?`.
+    pub unicode: u32,
+    /// Byte offset of the character in the laid out text.
+    pub byte: usize,
+    pub line: usize,
+    /// Top-left corner of the quad, with y pointing down from the top of the text.
+    pub position: [f32; 2],
+    pub size: [f32; 2],
+    /// Glyph rectangle in atlas pixels.
+    pub atlas: FontBounds,
+}
+
+/// One line of text produced by [`layout_text`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLine {
+    /// Byte range of the line in the laid out text, without the line break.
+    pub start: usize,
+    pub end: usize,
+    /// Horizontal offset applied by alignment.
+    pub x: f32,
+    /// Baseline position, with y pointing down from the top of the text.
+    pub baseline: f32,
+    /// Width of the line's advances, excluding trailing whitespace.
+    pub width: f32,
+}
+
+/// Positioned glyph quads for a block of text.
+///
+/// All values are in pixels at the font layout's `size`; scale them by
+/// `target_size / size` to draw at another size.
+#[derive(Debug, Clone, Default)]
+pub struct TextLayout {
+    pub quads: Vec<GlyphQuad>,
+    pub lines: Vec<TextLine>,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Lays out left-aligned text, wrapping lines longer than `max_width`.
+pub fn layout_text(font: &impl TextFont, text: &str, max_width: Option<f32>) -> TextLayout {
+    layout_text_aligned(font, text, max_width, TextAlign::Left)
+}
+
+/// Lays out text with the given alignment.
+///
+/// Lines break at `\n` and, when `max_width` is set, at the last space that
+/// keeps the line within it; words wider than `max_width` break between
+/// characters. Lines align within `max_width`, or within the widest line
+/// when there is none. Characters missing from the font fall back to U+FFFD
+/// or `?`.
+pub fn layout_text_aligned(
+    font: &impl TextFont,
+    text: &str,
+    max_width: Option<f32>,
+    align: TextAlign,
+) -> TextLayout {
+    let glyphs = font.glyphs();
+    let kerning = font.kerning();
+    let fallback = find_glyph(glyphs, 0xFFFD).or_else(|| find_glyph(glyphs, '?' as u32));
+    let max_width = max_width.unwrap_or(f32::INFINITY);
+
+    // Pen positions per character, relative to the start of its line.
+    let mut placed: Vec<Placed<'_>> = Vec::new();
+    let mut lines = Vec::new();
+    let mut line = LineBuilder::new(0, 0);
+
+    for (byte, ch) in text.char_indices() {
+        if ch == '\n' {
+            lines.push(line.finish(&placed, byte));
+            line = LineBuilder::new(byte + 1, placed.len());
+            continue;
+        }
+
+        let Some(glyph) = find_glyph(glyphs, ch as u32).or(fallback) else {
+            continue;
+        };
+        let whitespace = ch.is_whitespace();
+        let mut x = line.pen(&placed, kerning, glyph);
+        while !whitespace && x + glyph.advance > max_width && line.first < placed.len() {
+            match line.last_space.take() {
+                Some(space) => {
+                    // Move the word after the last space onto a new line.
+                    let (start, shift) = placed
+                        .get(space + 1)
+                        .map_or((byte, 0.0), |word| (word.byte, word.x));
+                    lines.push(line.finish(&placed[..=space], placed[space].byte));
+                    line = LineBuilder::new(start, space + 1);
+                    for moved in &mut placed[space + 1..] {
+                        moved.x -= shift;
+                        moved.line = lines.len();
+                    }
+                }
+                None => {
+                    lines.push(line.finish(&placed, byte));
+                    line = LineBuilder::new(byte, placed.len());
+                }
+            }
+            x = line.pen(&placed, kerning, glyph);
+        }
+
+        if whitespace {
+            line.last_space = Some(placed.len());
+        }
+        placed.push(Placed {
+            byte,
+            glyph,
+            x,
+            line: lines.len(),
+            whitespace,
+        });
+    }
+    lines.push(line.finish(&placed, text.len()));
+
+    let metrics = font.metrics();
+    let widest = lines.iter().fold(0.0f32, |acc, line| acc.max(line.width));
+    let block = if max_width.is_finite() {
+        max_width
+    } else {
+        widest
+    };
+    for (idx, line) in lines.iter_mut().enumerate() {
+        line.baseline = metrics.ascender + metrics.line_height * idx as f32;
+        line.x = match align {
+            TextAlign::Left => 0.0,
+            TextAlign::Center => (block - line.width) * 0.5,
+            TextAlign::Right => block - line.width,
+        };
+    }
+
+    let quads = placed
+        .iter()
+        .filter_map(|placed| {
+            let plane = placed.glyph.plane_bounds.as_ref()?;
+            let atlas = placed.glyph.atlas_bounds.clone()?;
+            let line = &lines[placed.line];
+            Some(GlyphQuad {
+                unicode: placed.glyph.unicode,
+                byte: placed.byte,
+                line: placed.line,
+                position: [line.x + placed.x + plane.left, line.baseline - plane.top],
+                size: [plane.right - plane.left, plane.top - plane.bottom],
+                atlas,
+            })
+        })
+        .collect();
+
+    TextLayout {
+        quads,
+        width: widest,
+        height: metrics.line_height * lines.len() as f32,
+        lines,
+    }
+}
+
+struct Placed<'a> {
+    byte: usize,
+    glyph: &'a FontGlyph,
+    /// Pen position relative to the start of the line.
+    x: f32,
+    line: usize,
+    whitespace: bool,
+}
+
+struct LineBuilder {
+    start: usize,
+    /// Index of the line's first character in the placed list.
+    first: usize,
+    last_space: Option<usize>,
+}
+
+impl LineBuilder {
+    fn new(start: usize, first: usize) -> Self {
+        Self {
+            start,
+            first,
+            last_space: None,
+        }
+    }
+
+    /// Returns where `glyph` goes after the line's last character, kerned
+    /// against it.
+    fn pen(&self, placed: &[Placed<'_>], kerning: &[FontKerning], glyph: &FontGlyph) -> f32 {
+        match placed[self.first..].last() {
+            Some(last) => {
+                last.x
+                    + last.glyph.advance
+                    + find_kerning(kerning, last.glyph.unicode, glyph.unicode)
+            }
+            None => 0.0,
+        }
+    }
+
+    /// Closes the line, whose characters are `placed[self.first..]`.
+    fn finish(&self, placed: &[Placed<'_>], end: usize) -> TextLine {
+        let width = placed[self.first..]
+            .iter()
+            .rev()
+            .find(|placed| !placed.whitespace)
+            .map_or(0.0, |last| last.x + last.glyph.advance);
+        TextLine {
+            start: self.start,
+            end,
+            x: 0.0,
+            baseline: 0.0,
+            width,
+        }
+    }
+}
+
+/// Finds a glyph by code point; layouts written by `dbgen` keep glyphs sorted.
+fn find_glyph(glyphs: &[FontGlyph], unicode: u32) -> Option<&FontGlyph> {
+    match glyphs.binary_search_by_key(&unicode, |glyph| glyph.unicode) {
+        Ok(idx) => Some(&glyphs[idx]),
+        Err(_) => glyphs.iter().find(|glyph| glyph.unicode == unicode),
+    }
+}
+
+fn find_kerning(kerning: &[FontKerning], left: u32, right: u32) -> f32 {
+    let found = match kerning.binary_search_by_key(&(left, right), |pair| (pair.left, pair.right)) {
+        Ok(idx) => Some(&kerning[idx]),
+        Err(_) => kerning
+            .iter()
+            .find(|pair| pair.left == left && pair.right == right),
+    };
+    found.map_or(0.0, |pair| pair.advance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextAlign, layout_text, layout_text_aligned};
+    use crate::parsing::{FontBounds, FontGlyph, FontKerning, FontMetrics, MsdfFontLayout};
+
+    fn glyph(ch: char, advance: f32) -> FontGlyph {
+        FontGlyph {
+            unicode: ch as u32,
+            advance,
+            plane_bounds: (ch != ' ').then(|| FontBounds {
+                left: 1.0,
+                bottom: -2.0,
+                right: 9.0,
+                top: 12.0,
+            }),
+            atlas_bounds: (ch != ' ').then(|| FontBounds {
+                left: ch as u32 as f32,
+                ..Default::default()
+            }),
+        }
+    }
+
+    fn font() -> MsdfFontLayout {
+        MsdfFontLayout {
+            size: 16.0,
+            metrics: FontMetrics {
+                line_height: 20.0,
+                ascender: 16.0,
+                ..Default::default()
+            },
+            glyphs: vec![
+                glyph(' ', 5.0),
+                glyph('?', 10.0),
+                glyph('A', 10.0),
+                glyph('V', 10.0),
+            ],
+            kerning: vec![FontKerning {
+                left: 'A' as u32,
+                right: 'V' as u32,
+                advance: -2.0,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn places_kerned_glyphs_on_baselines() {
+        let layout = layout_text(&font(), "AV A\nz", None);
+        let positions: Vec<[f32; 2]> = layout.quads.iter().map(|quad| quad.position).collect();
+        assert_eq!(
+            positions,
+            vec![[1.0, 4.0], [9.0, 4.0], [24.0, 4.0], [1.0, 24.0]]
+        );
+        assert_eq!(layout.quads[0].size, [8.0, 14.0]);
+        assert_eq!(layout.quads[2].byte, 3);
+        // Missing characters draw the fallback glyph.
+        assert_eq!(layout.quads[3].unicode, '?' as u32);
+        assert_eq!(layout.quads[3].atlas.left, '?' as u32 as f32);
+
+        assert_eq!(layout.lines.len(), 2);
+        assert_eq!((layout.lines[0].start, layout.lines[0].end), (0, 4));
+        assert_eq!(layout.lines[1].baseline, 36.0);
+        assert_eq!(layout.width, 33.0);
+        assert_eq!(layout.height, 40.0);
+    }
+
+    #[test]
+    fn wraps_words_and_aligns_lines() {
+        let font = font();
+        let layout = layout_text(&font, "AA AA AA", Some(45.0));
+        let ranges: Vec<(usize, usize)> = layout
+            .lines
+            .iter()
+            .map(|line| (line.start, line.end))
+            .collect();
+        assert_eq!(ranges, vec![(0, 5), (6, 8)]);
+        assert_eq!(layout.lines[0].width, 45.0);
+        assert_eq!(layout.lines[1].width, 20.0);
+        assert_eq!(layout.quads[4].position, [1.0, 24.0]);
+        assert_eq!(layout.quads[4].line, 1);
+
+        // Words wider than the line break between characters.
+        let layout = layout_text(&font, "AAAAA", Some(25.0));
+        assert_eq!(layout.lines.len(), 3);
+        assert!(layout.lines.iter().all(|line| line.width <= 25.0));
+
+        let layout = layout_text_aligned(&font, "AA AA AA", Some(45.0), TextAlign::Right);
+        assert_eq!(layout.lines[1].x, 25.0);
+        let layout = layout_text_aligned(&font, "AA\nA", None, TextAlign::Center);
+        assert_eq!(layout.lines[1].x, 5.0);
+    }
+}
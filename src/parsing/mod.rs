@@ -251,6 +251,18 @@ pub struct FontGlyph {
     pub atlas_bounds: Option<FontBounds>,
 }
 
+/// Advance adjustment between two adjacent characters.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct FontKerning {
+    #[serde(default)]
+    pub left: u32,
+    #[serde(default)]
+    pub right: u32,
+    /// Added to the left glyph's advance, in pixels at the layout's `size`.
+    #[serde(default)]
+    pub advance: f32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct SdfFontLayout {
     /// Atlas image entry for the SDF font.
@@ -273,6 +285,9 @@ pub struct SdfFontLayout {
     /// Glyph metadata keyed by glyph entry.
     #[serde(default)]
     pub glyphs: Vec<FontGlyph>,
+    /// Kerning pairs sorted by `left` then `right`.
+    #[serde(default)]
+    pub kerning: Vec<FontKerning>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -300,6 +315,9 @@ pub struct MsdfFontLayout {
     /// Glyph metadata keyed by glyph entry.
     #[serde(default)]
     pub glyphs: Vec<FontGlyph>,
+    /// Kerning pairs sorted by `left` then `right`.
+    #[serde(default)]
+    pub kerning: Vec<FontKerning>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -414,6 +432,62 @@ impl MetaLayout {
             && self.shaders.is_empty()
             && self.compute_shaders.is_empty()
     }
+
+    /// Merges `patch` into this layout; its entries replace ones with the same name.
+    pub fn overlay(&mut self, patch: MetaLayout) {
+        self.textures.extend(patch.textures);
+        self.atlases.extend(patch.atlases);
+        self.msdf_fonts.extend(patch.msdf_fonts);
+        self.sdf_fonts.extend(patch.sdf_fonts);
+        self.materials.extend(patch.materials);
+        self.meshes.extend(patch.meshes);
+        self.models.extend(patch.models);
+        self.shaders.extend(patch.shaders);
+        self.compute_shaders.extend(patch.compute_shaders);
+    }
+
+    /// Returns the names of layouts that differ from `previous`, in name order.
+    pub fn changed_entries(&self, previous: &MetaLayout) -> Vec<String> {
+        let mut changed = Vec::new();
+        diff_layouts(&self.textures, &previous.textures, &mut changed);
+        diff_layouts(&self.atlases, &previous.atlases, &mut changed);
+        diff_layouts(&self.msdf_fonts, &previous.msdf_fonts, &mut changed);
+        diff_layouts(&self.sdf_fonts, &previous.sdf_fonts, &mut changed);
+        diff_layouts(&self.materials, &previous.materials, &mut changed);
+        diff_layouts(&self.meshes, &previous.meshes, &mut changed);
+        diff_layouts(&self.models, &previous.models, &mut changed);
+        diff_layouts(&self.shaders, &previous.shaders, &mut changed);
+        diff_layouts(
+            &self.compute_shaders,
+            &previous.compute_shaders,
+            &mut changed,
+        );
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
+
+/// Collects keys present in only one map or whose layouts serialize differently.
+fn diff_layouts<T: Serialize>(
+    current: &HashMap<String, T>,
+    previous: &HashMap<String, T>,
+    changed: &mut Vec<String>,
+) {
+    for (name, layout) in current {
+        let same = previous.get(name).is_some_and(|prev| {
+            serde_json::to_value(prev).ok() == serde_json::to_value(layout).ok()
+        });
+        if !same {
+            changed.push(name.clone());
+        }
+    }
+    changed.extend(
+        previous
+            .keys()
+            .filter(|name| !current.contains_key(*name))
+            .cloned(),
+    );
 }
 
 impl Default for DatabaseLayoutFile {
@@ -438,3 +512,33 @@ impl Default for DatabaseLayoutFile {
         }
     }
 }
+
+impl DatabaseLayoutFile {
+    /// Returns the RDB module file paths named by the layout.
+    pub fn module_files(&self) -> [&str; 8] {
+        [
+            &self.geometry,
+            &self.imagery,
+            &self.audio,
+            &self.fonts,
+            &self.skeletons,
+            &self.animations,
+            &self.terrain,
+            &self.shaders,
+        ]
+    }
+
+    /// Returns the JSON metadata file paths named by the layout.
+    pub fn metadata_files(&self) -> [&str; 8] {
+        [
+            &self.msdf_fonts,
+            &self.sdf_fonts,
+            &self.textures,
+            &self.atlases,
+            &self.materials,
+            &self.meshes,
+            &self.models,
+            &self.shader_layouts,
+        ]
+    }
+}
@@ -1,10 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
-use tracing::info;
 
-use super::DatabaseEntry;
-use crate::{RDBView, defaults::default_animations, utils::NorenError};
+use super::{DatabaseEntry, fetch_or_default};
+use crate::{RDBLayers, RDBRawEntry, RDBView, defaults::default_animations, utils::NorenError};
 
 #[repr(C)]
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -106,21 +105,21 @@ pub struct AnimationChannel {
 
 #[derive(Default)]
 pub struct AnimationDB {
-    data: Option<RDBView>,
-    defaults: HashMap<String, AnimationClip>,
+    data: RDBLayers,
+    defaults: Arc<HashMap<String, AnimationClip>>,
 }
 
 impl AnimationDB {
     /// Loads animation clips from the given `.rdb` file path, if present.
     pub fn new(module_path: &str) -> Self {
-        let data = match RDBView::load(module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
-        };
+        Self::from_layers(RDBView::load(module_path).ok().into())
+    }
 
+    /// Creates an animation database backed by already opened RDB layers.
+    pub fn from_layers(data: RDBLayers) -> Self {
         Self {
             data,
-            defaults: default_animations().into_iter().collect(),
+            defaults: Arc::new(default_animations().into_iter().collect()),
         }
     }
 
@@ -129,29 +128,32 @@ impl AnimationDB {
         &mut self,
         entry: DatabaseEntry<'_>,
     ) -> Result<AnimationClip, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(animation) = rdb.fetch::<AnimationClip>(entry) {
-                info!(resource = "animation", entry = %entry, source = "rdb");
-                return Ok(animation);
-            }
-        }
+        fetch_or_default(&self.data, &self.defaults, "animation", entry)
+    }
 
-        if let Some(animation) = self.defaults.get(entry) {
-            info!(resource = "animation", entry = %entry, source = "default");
-            return Ok(animation.clone());
-        }
+    /// Returns whether the entry exists in the backing database or the built-in defaults.
+    pub fn contains(&self, entry: DatabaseEntry<'_>) -> bool {
+        self.data.contains(entry) || self.defaults.contains_key(entry)
+    }
+
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
+
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
+    }
 
-        Err(NorenError::DataFailure())
+    pub(crate) fn shared_defaults(&self) -> Arc<HashMap<String, AnimationClip>> {
+        Arc::clone(&self.defaults)
     }
 
     /// Lists animation entries available in the backing database.
     pub fn enumerate_entries(&self) -> Vec<String> {
         let mut entries: Vec<String> = self.defaults.keys().cloned().collect();
-        if let Some(rdb) = &self.data {
-            for meta in rdb.entries() {
-                if !entries.contains(&meta.name) {
-                    entries.push(meta.name);
-                }
+        for meta in self.data.entries() {
+            if !entries.contains(&meta.name) {
+                entries.push(meta.name);
             }
         }
 
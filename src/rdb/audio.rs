@@ -1,9 +1,12 @@
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tracing::info;
+use std::{collections::HashMap, sync::Arc};
 
-use super::DatabaseEntry;
-use crate::{RDBView, defaults::default_sounds, utils::NorenError};
+use super::{DatabaseEntry, PcmBuffer, PcmStream, enumerate_with_defaults, fetch_or_default};
+use crate::{
+    RDBLayers, RDBRawEntry, RDBView,
+    defaults::default_sounds,
+    utils::{NorenError, RdbErr, rdbfile::from_bytes},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -21,25 +24,123 @@ impl Default for AudioFormat {
     }
 }
 
-#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+impl AudioFormat {
+    /// Returns the usual file extension for the format, if known.
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            AudioFormat::Ogg => Some("ogg"),
+            AudioFormat::Wav => Some("wav"),
+            AudioFormat::Mp3 => Some("mp3"),
+            AudioFormat::Flac => Some("flac"),
+            AudioFormat::Unknown => None,
+        }
+    }
+}
+
+/// A looping region of a clip, in sample frames from the start of the clip.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioLoop {
+    pub start: u64,
+    /// Exclusive end frame; `None` loops at the end of the clip.
+    #[serde(default)]
+    pub end: Option<u64>,
+}
+
+/// A named marker in a clip, in sample frames from the start of the clip.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AudioCue {
+    pub name: String,
+    pub frame: u64,
+}
+
+/// A sound clip as stored under the `AUD2` tag.
+///
+/// Change the layout by taking a new tag rather than appending fields here.
+/// Clips written before stable tags are read by [`upgrade_audio_clip`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AudioClip {
     pub name: String,
     #[serde(default)]
     pub format: AudioFormat,
     #[serde(default)]
     pub data: Vec<u8>,
+    /// Playback length in seconds, measured when the database was built.
+    pub duration_secs: f32,
+    /// Region to repeat while the clip loops, if it loops.
+    pub looping: Option<AudioLoop>,
+    /// Default linear gain applied when the clip plays.
+    pub volume: f32,
+    /// Mixer bus or category the clip plays on, such as `music` or `sfx`.
+    pub category: Option<String>,
+    pub cues: Vec<AudioCue>,
+}
+
+impl Default for AudioClip {
+    fn default() -> Self {
+        Self::new(String::new(), AudioFormat::default(), Vec::new())
+    }
 }
 
 impl AudioClip {
     pub fn new(name: String, format: AudioFormat, data: Vec<u8>) -> Self {
-        Self { name, format, data }
+        Self {
+            name,
+            format,
+            data,
+            duration_secs: 0.0,
+            looping: None,
+            volume: 1.0,
+            category: None,
+            cues: Vec::new(),
+        }
+    }
+
+    /// Measures the playback length of the encoded data in seconds.
+    pub fn measure_duration(&self) -> Result<f32, NorenError> {
+        PcmStream::new(self.clone())?.measure_duration()
+    }
+
+    /// Returns the clip with `duration_secs` filled in from its encoded data.
+    pub fn with_measured_duration(mut self) -> Result<Self, NorenError> {
+        self.duration_secs = self.measure_duration()?;
+        Ok(self)
     }
+
+    /// Looks up a cue marker by name.
+    pub fn cue(&self, name: &str) -> Option<&AudioCue> {
+        self.cues.iter().find(|cue| cue.name == name)
+    }
+}
+
+/// Clip layout written under the type-name hash, before clips had a stable tag.
+#[derive(Deserialize)]
+struct UntaggedAudioClip {
+    name: String,
+    format: AudioFormat,
+    data: Vec<u8>,
+}
+
+/// Reads clips written under the type-name hash.
+///
+/// Those clips predate durations and playback settings, so they load with the
+/// defaults from [`AudioClip::new`].
+pub(crate) fn upgrade_audio_clip(
+    name: &str,
+    type_tag: u32,
+    bytes: &[u8],
+) -> Result<AudioClip, RdbErr> {
+    let clip: UntaggedAudioClip = from_bytes(name, type_tag, bytes)?;
+    Ok(AudioClip::new(clip.name, clip.format, clip.data))
 }
 
 #[derive(Debug, Clone, Default)]
 pub struct SoundTrack {
     pub name: String,
     pub format: AudioFormat,
+    pub looping: Option<AudioLoop>,
+    pub volume: f32,
+    pub category: Option<String>,
+    pub cues: Vec<AudioCue>,
     data: Vec<u8>,
     cursor: usize,
 }
@@ -49,6 +150,10 @@ impl SoundTrack {
         Self {
             name: clip.name,
             format: clip.format,
+            looping: clip.looping,
+            volume: clip.volume,
+            category: clip.category,
+            cues: clip.cues,
             data: clip.data,
             cursor: 0,
         }
@@ -76,42 +181,32 @@ impl SoundTrack {
 
 #[derive(Default)]
 pub struct AudioDB {
-    data: Option<RDBView>,
-    defaults: HashMap<String, AudioClip>,
+    data: RDBLayers,
+    defaults: Arc<HashMap<String, AudioClip>>,
 }
 
 impl AudioDB {
     /// Loads audio clips from the given `.rdb` file path, if it exists.
     pub fn new(module_path: &str) -> Self {
-        let data = match RDBView::load(module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
-        };
+        Self::from_layers(RDBView::load(module_path).ok().into())
+    }
 
+    /// Creates an audio database backed by already opened RDB layers.
+    pub fn from_layers(data: RDBLayers) -> Self {
         Self {
             data,
-            defaults: default_sounds()
-                .into_iter()
-                .map(|clip| (clip.name.clone(), clip))
-                .collect(),
+            defaults: Arc::new(
+                default_sounds()
+                    .into_iter()
+                    .map(|clip| (clip.name.clone(), clip))
+                    .collect(),
+            ),
         }
     }
 
     /// Fetches an audio clip by entry name.
     pub fn fetch_clip(&mut self, entry: DatabaseEntry<'_>) -> Result<AudioClip, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(clip) = rdb.fetch::<AudioClip>(entry) {
-                info!(resource = "audio", entry = %entry, source = "rdb");
-                return Ok(clip);
-            }
-        }
-
-        if let Some(clip) = self.defaults.get(entry) {
-            info!(resource = "audio", entry = %entry, source = "default");
-            return Ok(clip.clone());
-        }
-
-        Err(NorenError::DataFailure())
+        fetch_or_default(&self.data, &self.defaults, "audio", entry)
     }
 
     /// Fetches a fully loaded sound clip by entry name.
@@ -128,32 +223,63 @@ impl AudioDB {
         Ok(SoundTrack::new(clip))
     }
 
+    /// Fetches a clip and opens an incremental PCM decoder over it.
+    pub fn fetch_pcm_stream(&mut self, entry: DatabaseEntry<'_>) -> Result<PcmStream, NorenError> {
+        PcmStream::new(self.fetch_clip(entry)?)
+    }
+
+    /// Fetches a clip and decodes it to PCM in full.
+    pub fn fetch_pcm(&mut self, entry: DatabaseEntry<'_>) -> Result<PcmBuffer, NorenError> {
+        self.fetch_pcm_stream(entry)?.decode_all()
+    }
+
+    /// Returns whether the entry exists in the backing database or the built-in defaults.
+    pub fn contains(&self, entry: DatabaseEntry<'_>) -> bool {
+        self.data.contains(entry) || self.defaults.contains_key(entry)
+    }
+
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
+
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
+    }
+
+    pub(crate) fn shared_defaults(&self) -> Arc<HashMap<String, AudioClip>> {
+        Arc::clone(&self.defaults)
+    }
+
     /// Lists all audio clip entries available in the backing database.
     pub fn enumerate_entries(&self) -> Vec<String> {
-        let mut entries: Vec<String> = self
-            .data
-            .as_ref()
-            .map(|rdb| rdb.entries().into_iter().map(|meta| meta.name).collect())
-            .unwrap_or_default();
-        for entry in self.defaults.keys() {
-            if !entries.iter().any(|existing| existing == entry) {
-                entries.push(entry.clone());
-            }
-        }
-        entries
+        enumerate_with_defaults(&self.data, &self.defaults)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{AudioClip, AudioDB, AudioFormat};
-    use crate::utils::rdbfile::RDBFile;
+    use super::{AudioClip, AudioCue, AudioDB, AudioFormat, AudioLoop};
+    use crate::utils::{
+        RdbErr,
+        rdbfile::{RDBFile, decode_payload, fourcc, legacy_type_tag_for},
+    };
+    use serde::Serialize;
 
     const ENTRY: &str = "audio/test";
 
     #[test]
     fn fetch_audio_clip() {
-        let clip = AudioClip::new(ENTRY.to_string(), AudioFormat::Wav, vec![0, 1, 2, 3]);
+        let mut clip = AudioClip::new(ENTRY.to_string(), AudioFormat::Wav, vec![0, 1, 2, 3]);
+        clip.looping = Some(AudioLoop {
+            start: 1,
+            end: Some(3),
+        });
+        clip.volume = 0.5;
+        clip.category = Some("music".into());
+        clip.cues = vec![AudioCue {
+            name: "drop".into(),
+            frame: 2,
+        }];
         let mut file = RDBFile::new();
         file.add(ENTRY, &clip).expect("add clip");
 
@@ -164,6 +290,11 @@ mod tests {
         let loaded = db.fetch_clip(ENTRY).expect("load audio clip");
 
         assert_eq!(loaded, clip);
+        assert_eq!(loaded.cue("drop").map(|cue| cue.frame), Some(2));
+
+        let track = db.fetch_sound_track(ENTRY).expect("load sound track");
+        assert_eq!(track.looping, clip.looping);
+        assert_eq!(track.category.as_deref(), Some("music"));
     }
 
     #[test]
@@ -174,6 +305,43 @@ mod tests {
                 .fetch_sound_clip(entry)
                 .unwrap_or_else(|_| panic!("load default sound {entry}"));
             assert_eq!(clip.name, entry);
+            assert!(clip.duration_secs > 0.0);
+            let measured = clip.measure_duration().expect("measure default sound");
+            assert!((measured - clip.duration_secs).abs() < 1e-6);
+
+            let pcm = db.fetch_pcm(entry).expect("decode default sound");
+            assert!(pcm.channels > 0);
+            assert!((pcm.duration_secs() - clip.duration_secs).abs() < 1e-3);
         }
     }
+
+    #[test]
+    fn clips_written_before_stable_tags_still_load() {
+        #[derive(Serialize)]
+        struct UntaggedClip {
+            name: String,
+            format: AudioFormat,
+            data: Vec<u8>,
+        }
+
+        let bytes = bincode::serialize(&UntaggedClip {
+            name: ENTRY.to_string(),
+            format: AudioFormat::Ogg,
+            data: vec![9, 8, 7],
+        })
+        .expect("serialize old clip");
+
+        let loaded: AudioClip = decode_payload(ENTRY, legacy_type_tag_for::<AudioClip>(), &bytes)
+            .expect("load old clip");
+        assert_eq!(
+            loaded,
+            AudioClip::new(ENTRY.to_string(), AudioFormat::Ogg, vec![9, 8, 7])
+        );
+
+        // Tags retired before release are not read as older layouts.
+        assert!(matches!(
+            decode_payload::<AudioClip>(ENTRY, fourcc(b"AUD1"), &bytes),
+            Err(RdbErr::TypeMismatch { .. })
+        ));
+    }
 }
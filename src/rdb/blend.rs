@@ -0,0 +1,504 @@
+use std::{collections::HashMap, sync::Arc};
+
+use glam::{Quat, Vec3};
+
+use super::{
+    AnimationClip, AnimationDB, AnimationWrap, DatabaseEntry, JointPose, Pose, Skeleton, SkeletonDB,
+};
+use crate::utils::NorenError;
+
+/// Per-joint weights that limit a blend or layer to part of a skeleton.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JointMask {
+    weights: Vec<f32>,
+}
+
+impl JointMask {
+    /// A mask that lets every joint through.
+    pub fn all(skeleton: &Skeleton) -> Self {
+        Self {
+            weights: vec![1.0; skeleton.joints.len()],
+        }
+    }
+
+    /// A mask that blocks every joint.
+    pub fn none(skeleton: &Skeleton) -> Self {
+        Self {
+            weights: vec![0.0; skeleton.joints.len()],
+        }
+    }
+
+    /// A mask covering the named joint and its descendants, e.g. an upper body
+    /// rooted at the spine. Returns `None` if no joint has that name.
+    pub fn subtree(skeleton: &Skeleton, joint: &str) -> Option<Self> {
+        Self::none(skeleton).with_subtree(skeleton, joint, 1.0)
+    }
+
+    /// Sets the weight of the named joint and its descendants.
+    pub fn with_subtree(mut self, skeleton: &Skeleton, joint: &str, weight: f32) -> Option<Self> {
+        let root = joint_index(skeleton, joint)?;
+        for idx in 0..skeleton.joints.len() {
+            if is_descendant(skeleton, idx, root) {
+                self.set(idx, weight);
+            }
+        }
+        Some(self)
+    }
+
+    pub fn set(&mut self, joint: usize, weight: f32) {
+        if let Some(slot) = self.weights.get_mut(joint) {
+            *slot = weight.clamp(0.0, 1.0);
+        }
+    }
+
+    /// Returns the weight of a joint; joints outside the mask weigh zero.
+    pub fn weight(&self, joint: usize) -> f32 {
+        self.weights.get(joint).copied().unwrap_or(0.0)
+    }
+}
+
+fn joint_index(skeleton: &Skeleton, name: &str) -> Option<usize> {
+    skeleton
+        .joints
+        .iter()
+        .position(|joint| joint.name.as_deref() == Some(name))
+}
+
+/// Returns whether `joint` is `root` or lies below it.
+fn is_descendant(skeleton: &Skeleton, joint: usize, root: usize) -> bool {
+    let mut current = Some(joint);
+    for _ in 0..=skeleton.joints.len() {
+        match current {
+            Some(idx) if idx == root => return true,
+            Some(idx) => current = skeleton.joints.get(idx).and_then(|joint| joint.parent),
+            None => return false,
+        }
+    }
+    false
+}
+
+impl Pose {
+    /// Crossfades towards `other`; a `weight` of 1 gives `other`.
+    ///
+    /// Both poses must belong to `skeleton`. Masked-out joints keep this pose.
+    pub fn blend(
+        &self,
+        other: &Pose,
+        weight: f32,
+        mask: Option<&JointMask>,
+        skeleton: &Skeleton,
+    ) -> Pose {
+        let local = self
+            .local
+            .iter()
+            .zip(&other.local)
+            .enumerate()
+            .map(|(idx, (from, to))| from.lerp(to, weight * mask_weight(mask, idx)))
+            .collect();
+        Pose::from_local(skeleton, local)
+    }
+
+    /// Adds the difference between `additive` and `reference` on top of this pose.
+    ///
+    /// `reference` is usually the additive clip's first frame or the rest pose.
+    /// Masked-out joints keep this pose.
+    pub fn layer_additive(
+        &self,
+        additive: &Pose,
+        reference: &Pose,
+        weight: f32,
+        mask: Option<&JointMask>,
+        skeleton: &Skeleton,
+    ) -> Pose {
+        let local = self
+            .local
+            .iter()
+            .zip(additive.local.iter().zip(&reference.local))
+            .enumerate()
+            .map(|(idx, (base, (layer, reference)))| {
+                let weight = weight * mask_weight(mask, idx);
+                let rotation = reference.rotation.inverse() * layer.rotation;
+                let scale = layer.scale / reference.scale;
+                JointPose {
+                    translation: base.translation
+                        + (layer.translation - reference.translation) * weight,
+                    rotation: (base.rotation * Quat::IDENTITY.slerp(rotation, weight)).normalize(),
+                    scale: if scale.is_finite() {
+                        base.scale * Vec3::ONE.lerp(scale, weight)
+                    } else {
+                        base.scale
+                    },
+                }
+            })
+            .collect();
+        Pose::from_local(skeleton, local)
+    }
+}
+
+fn mask_weight(mask: Option<&JointMask>, joint: usize) -> f32 {
+    mask.map_or(1.0, |mask| mask.weight(joint))
+}
+
+/// Maps the joints of the skeleton a clip was authored for onto another
+/// skeleton by joint name.
+///
+/// Rotation and scale channels carry over unchanged, so both skeletons should
+/// share rest orientations. Translations keep the target's proportions by
+/// adding the difference between the two rest translations.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RetargetMap {
+    /// Target joint per source joint.
+    targets: Vec<Option<usize>>,
+    /// Target minus source rest translation per source joint.
+    offsets: Vec<Vec3>,
+}
+
+impl RetargetMap {
+    /// Matches the joints of `source` to the joints of `target` with the same name.
+    pub fn new(source: &Skeleton, target: &Skeleton) -> Self {
+        let by_name: HashMap<&str, usize> = target
+            .joints
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, joint)| Some((joint.name.as_deref()?, idx)))
+            .collect();
+
+        let targets: Vec<Option<usize>> = source
+            .joints
+            .iter()
+            .map(|joint| by_name.get(joint.name.as_deref()?).copied())
+            .collect();
+        let offsets = source
+            .joints
+            .iter()
+            .zip(&targets)
+            .map(|(joint, target_idx)| match target_idx {
+                Some(idx) => {
+                    Vec3::from(target.joints[*idx].translation) - Vec3::from(joint.translation)
+                }
+                None => Vec3::ZERO,
+            })
+            .collect();
+
+        Self { targets, offsets }
+    }
+
+    /// Returns the target joint driven by a source joint, if one matched.
+    pub fn target_of(&self, source_joint: usize) -> Option<usize> {
+        self.targets.get(source_joint).copied().flatten()
+    }
+
+    /// Returns how many source joints found a target.
+    pub fn matched(&self) -> usize {
+        self.targets.iter().flatten().count()
+    }
+}
+
+impl AnimationClip {
+    /// Samples a clip authored for another skeleton onto `skeleton` through `map`.
+    ///
+    /// Target joints without a matching source joint keep their rest transform.
+    pub fn sample_retargeted(
+        &self,
+        time: f32,
+        skeleton: &Skeleton,
+        map: &RetargetMap,
+        wrap: AnimationWrap,
+    ) -> Pose {
+        let mut local: Vec<JointPose> = skeleton.joints.iter().map(JointPose::from_joint).collect();
+        self.apply_channels(self.wrap_time(time, wrap), &mut local, |joint| {
+            Some((map.target_of(joint)?, map.offsets[joint]))
+        });
+        Pose::from_local(skeleton, local)
+    }
+}
+
+/// Samples clips from an [`AnimationDB`] onto skeletons from a [`SkeletonDB`],
+/// retargeting clips whose authoring skeleton differs.
+///
+/// Fetched clips and skeletons are kept, and retarget maps are cached per
+/// (clip, skeleton) pair.
+#[derive(Default)]
+pub struct PoseBlender {
+    clips: HashMap<String, Arc<AnimationClip>>,
+    skeletons: HashMap<String, Arc<Skeleton>>,
+    clip_skeletons: HashMap<String, String>,
+    retargets: HashMap<(String, String), Arc<RetargetMap>>,
+}
+
+impl PoseBlender {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the skeleton `clip` was authored for. Clips without one are
+    /// sampled directly onto whichever skeleton they are played on.
+    pub fn set_clip_skeleton(&mut self, clip: DatabaseEntry<'_>, skeleton: DatabaseEntry<'_>) {
+        self.clip_skeletons
+            .insert(clip.to_string(), skeleton.to_string());
+        self.retargets.retain(|(cached, _), _| cached != clip);
+    }
+
+    /// Fetches a clip, reusing it on later calls.
+    pub fn clip(
+        &mut self,
+        animations: &mut AnimationDB,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<Arc<AnimationClip>, NorenError> {
+        if let Some(clip) = self.clips.get(entry) {
+            return Ok(Arc::clone(clip));
+        }
+        let clip = Arc::new(animations.fetch_animation(entry)?);
+        self.clips.insert(entry.to_string(), Arc::clone(&clip));
+        Ok(clip)
+    }
+
+    /// Fetches a skeleton, reusing it on later calls.
+    pub fn skeleton(
+        &mut self,
+        skeletons: &mut SkeletonDB,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<Arc<Skeleton>, NorenError> {
+        if let Some(skeleton) = self.skeletons.get(entry) {
+            return Ok(Arc::clone(skeleton));
+        }
+        let skeleton = Arc::new(skeletons.fetch_skeleton(entry)?);
+        self.skeletons
+            .insert(entry.to_string(), Arc::clone(&skeleton));
+        Ok(skeleton)
+    }
+
+    /// Returns the map retargeting `clip` onto `skeleton`, building it on first use.
+    ///
+    /// Fails with [`NorenError::LookupFailure`] if no authoring skeleton was
+    /// recorded for the clip.
+    pub fn retarget_map(
+        &mut self,
+        skeletons: &mut SkeletonDB,
+        clip: DatabaseEntry<'_>,
+        skeleton: DatabaseEntry<'_>,
+    ) -> Result<Arc<RetargetMap>, NorenError> {
+        let key = (clip.to_string(), skeleton.to_string());
+        if let Some(map) = self.retargets.get(&key) {
+            return Ok(Arc::clone(map));
+        }
+
+        let source = self
+            .clip_skeletons
+            .get(clip)
+            .cloned()
+            .ok_or_else(NorenError::LookupFailure)?;
+        let source = self.skeleton(skeletons, &source)?;
+        let target = self.skeleton(skeletons, skeleton)?;
+        let map = Arc::new(RetargetMap::new(&source, &target));
+        self.retargets.insert(key, Arc::clone(&map));
+        Ok(map)
+    }
+
+    /// Samples `clip` at `time` onto `skeleton`, retargeting when the clip was
+    /// authored for a different skeleton.
+    pub fn sample(
+        &mut self,
+        animations: &mut AnimationDB,
+        skeletons: &mut SkeletonDB,
+        clip: DatabaseEntry<'_>,
+        skeleton: DatabaseEntry<'_>,
+        time: f32,
+        wrap: AnimationWrap,
+    ) -> Result<Pose, NorenError> {
+        let animation = self.clip(animations, clip)?;
+        let target = self.skeleton(skeletons, skeleton)?;
+        let retarget = self
+            .clip_skeletons
+            .get(clip)
+            .is_some_and(|source| source != skeleton);
+        if !retarget {
+            return Ok(animation.sample_with(time, &target, wrap));
+        }
+
+        let map = self.retarget_map(skeletons, clip, skeleton)?;
+        Ok(animation.sample_retargeted(time, &target, &map, wrap))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::{Quat, Vec3};
+
+    use super::{JointMask, PoseBlender, RetargetMap};
+    use crate::{
+        RDBFile,
+        rdb::{
+            AnimationChannel, AnimationClip, AnimationDB, AnimationOutput, AnimationSampler,
+            AnimationTargetPath, AnimationWrap, Joint, JointPose, Pose, Skeleton, SkeletonDB,
+        },
+    };
+
+    fn joint(name: &str, parent: Option<usize>, translation: [f32; 3]) -> Joint {
+        Joint {
+            name: Some(name.to_string()),
+            parent,
+            translation,
+            ..Default::default()
+        }
+    }
+
+    fn chain() -> Skeleton {
+        Skeleton::new(
+            "chain",
+            vec![
+                joint("hips", None, [0.0, 1.0, 0.0]),
+                joint("spine", Some(0), [0.0, 0.5, 0.0]),
+                joint("head", Some(1), [0.0, 0.5, 0.0]),
+            ],
+            Some(0),
+        )
+    }
+
+    fn turned(skeleton: &Skeleton, joint: usize, degrees: f32) -> Pose {
+        let mut local: Vec<JointPose> = skeleton.joints.iter().map(JointPose::from_joint).collect();
+        local[joint].rotation = Quat::from_rotation_z(degrees.to_radians());
+        Pose::from_local(skeleton, local)
+    }
+
+    fn angle(pose: &Pose, joint: usize) -> f32 {
+        let (axis, angle) = pose.local[joint].rotation.to_axis_angle();
+        (angle * axis.z.signum()).to_degrees()
+    }
+
+    /// A clip turning joint `target` from 0 to 90 degrees about Z over one second.
+    fn turn_clip(target: usize) -> AnimationClip {
+        AnimationClip::new(
+            "turn",
+            1.0,
+            vec![AnimationSampler {
+                input: vec![0.0, 1.0],
+                output: AnimationOutput::Rotations(vec![
+                    [0.0, 0.0, 0.0, 1.0],
+                    Quat::from_rotation_z(90f32.to_radians()).to_array(),
+                ]),
+                ..Default::default()
+            }],
+            vec![AnimationChannel {
+                sampler_index: 0,
+                target_node: target,
+                target_path: AnimationTargetPath::Rotation,
+            }],
+        )
+    }
+
+    #[test]
+    fn masked_crossfade_and_additive_layer() {
+        let skeleton = chain();
+        let upper = JointMask::subtree(&skeleton, "spine").unwrap();
+        assert_eq!(upper.weight(0), 0.0);
+        assert_eq!(upper.weight(2), 1.0);
+        assert!(JointMask::subtree(&skeleton, "tail").is_none());
+
+        let from = turned(&skeleton, 0, 0.0);
+        let to = turned(&skeleton, 0, 90.0);
+        let full = from.blend(&to, 0.5, None, &skeleton);
+        assert!((angle(&full, 0) - 45.0).abs() < 1e-3);
+        let masked = from.blend(&to, 0.5, Some(&upper), &skeleton);
+        assert!(angle(&masked, 0).abs() < 1e-3);
+
+        let base = turned(&skeleton, 1, 30.0);
+        let rest = Pose::rest(&skeleton);
+        let wave = turned(&skeleton, 1, 60.0);
+        let layered = base.layer_additive(&wave, &rest, 0.5, Some(&upper), &skeleton);
+        assert!((angle(&layered, 1) - 60.0).abs() < 1e-3);
+        assert!(angle(&layered, 0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn retargets_by_joint_name() {
+        let source = chain();
+        let target = Skeleton::new(
+            "tall",
+            vec![
+                joint("root", None, [0.0, 0.0, 0.0]),
+                joint("hips", Some(0), [0.0, 2.0, 0.0]),
+                joint("spine", Some(1), [0.0, 1.0, 0.0]),
+            ],
+            Some(0),
+        );
+        let map = RetargetMap::new(&source, &target);
+        assert_eq!(map.target_of(1), Some(2));
+        assert_eq!(map.target_of(2), None);
+        assert_eq!(map.matched(), 2);
+
+        let pose = turn_clip(1).sample_retargeted(1.0, &target, &map, AnimationWrap::Clamp);
+        assert!((angle(&pose, 2) - 90.0).abs() < 1e-3);
+        assert!(angle(&pose, 1).abs() < 1e-3);
+        assert_eq!(pose.local[2].translation, Vec3::new(0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn blender_caches_clips_and_retarget_maps() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut reversed = chain();
+        reversed.joints.reverse();
+        for (idx, joint) in reversed.joints.iter_mut().enumerate() {
+            joint.parent = (idx < 2).then_some(idx + 1);
+        }
+
+        let mut skeleton_file = RDBFile::new();
+        skeleton_file.add("skeletons/chain", &chain()).unwrap();
+        skeleton_file.add("skeletons/reversed", &reversed).unwrap();
+        skeleton_file
+            .save(tmp.path().join("skeletons.rdb"))
+            .unwrap();
+        let mut animation_file = RDBFile::new();
+        animation_file
+            .add("animations/turn", &turn_clip(2))
+            .unwrap();
+        animation_file
+            .save(tmp.path().join("animations.rdb"))
+            .unwrap();
+
+        let mut skeletons = SkeletonDB::new(tmp.path().join("skeletons.rdb").to_str().unwrap());
+        let mut animations = AnimationDB::new(tmp.path().join("animations.rdb").to_str().unwrap());
+        let mut blender = PoseBlender::new();
+        blender.set_clip_skeleton("animations/turn", "skeletons/chain");
+
+        let direct = blender
+            .sample(
+                &mut animations,
+                &mut skeletons,
+                "animations/turn",
+                "skeletons/chain",
+                1.0,
+                AnimationWrap::Clamp,
+            )
+            .unwrap();
+        assert!((angle(&direct, 2) - 90.0).abs() < 1e-3);
+
+        let retargeted = blender
+            .sample(
+                &mut animations,
+                &mut skeletons,
+                "animations/turn",
+                "skeletons/reversed",
+                1.0,
+                AnimationWrap::Clamp,
+            )
+            .unwrap();
+        assert!((angle(&retargeted, 0) - 90.0).abs() < 1e-3);
+        assert!(angle(&retargeted, 2).abs() < 1e-3);
+
+        let first = blender
+            .retarget_map(&mut skeletons, "animations/turn", "skeletons/reversed")
+            .unwrap();
+        let second = blender
+            .retarget_map(&mut skeletons, "animations/turn", "skeletons/reversed")
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert!(
+            blender
+                .retarget_map(&mut skeletons, "animations/other", "skeletons/chain")
+                .is_err()
+        );
+    }
+}
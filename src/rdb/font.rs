@@ -1,12 +1,11 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use fontdue::{Font, FontSettings};
 use serde::{Deserialize, Serialize};
-use tracing::info;
 
-use crate::{RDBView, defaults::default_fonts, utils::NorenError};
+use crate::{RDBLayers, RDBRawEntry, RDBView, defaults::default_fonts, utils::NorenError};
 
-use super::DatabaseEntry;
+use super::{DatabaseEntry, enumerate_with_defaults, fetch_or_default};
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct FontInfo {
@@ -62,71 +61,71 @@ pub struct LoadedFont {
     pub font: Font,
 }
 
+impl LoadedFont {
+    /// Parses the font file stored in a host font.
+    pub fn from_host(host: HostFont) -> Result<Self, NorenError> {
+        let settings = FontSettings {
+            collection_index: host.info.collection_index,
+            ..Default::default()
+        };
+        let font = Font::from_bytes(host.data, settings).map_err(|_| NorenError::DataFailure())?;
+        Ok(Self {
+            name: host.info.name,
+            font,
+        })
+    }
+}
+
 #[derive(Default)]
 pub struct FontDB {
-    data: Option<RDBView>,
-    defaults: HashMap<String, HostFont>,
+    data: RDBLayers,
+    defaults: Arc<HashMap<String, HostFont>>,
 }
 
 impl FontDB {
     /// Loads fonts from the given `.rdb` file path, if it exists.
     pub fn new(module_path: &str) -> Self {
-        let data = match RDBView::load(module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
-        };
+        Self::from_layers(RDBView::load(module_path).ok().into())
+    }
 
+    /// Creates a font database backed by already opened RDB layers.
+    pub fn from_layers(data: RDBLayers) -> Self {
         Self {
             data,
-            defaults: default_fonts()
-                .into_iter()
-                .map(|font| (font.info.name.clone(), font))
-                .collect(),
+            defaults: Arc::new(
+                default_fonts()
+                    .into_iter()
+                    .map(|font| (font.info.name.clone(), font))
+                    .collect(),
+            ),
         }
     }
 
-    fn build_font(&self, host: HostFont) -> Result<LoadedFont, NorenError> {
-        let settings = FontSettings {
-            collection_index: host.info.collection_index,
-            ..Default::default()
-        };
-        let font = Font::from_bytes(host.data, settings).map_err(|_| NorenError::DataFailure())?;
-        Ok(LoadedFont {
-            name: host.info.name,
-            font,
-        })
-    }
-
     /// Fetches a font by entry name.
     pub fn fetch_font(&mut self, entry: DatabaseEntry<'_>) -> Result<LoadedFont, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(font) = rdb.fetch::<HostFont>(entry) {
-                info!(resource = "font", entry = %entry, source = "rdb");
-                return self.build_font(font);
-            }
-        }
+        LoadedFont::from_host(fetch_or_default(&self.data, &self.defaults, "font", entry)?)
+    }
 
-        if let Some(font) = self.defaults.get(entry) {
-            info!(resource = "font", entry = %entry, source = "default");
-            return self.build_font(font.clone());
-        }
+    /// Returns whether the entry exists in the backing database or the built-in defaults.
+    pub fn contains(&self, entry: DatabaseEntry<'_>) -> bool {
+        self.data.contains(entry) || self.defaults.contains_key(entry)
+    }
+
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
 
-        Err(NorenError::DataFailure())
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
+    }
+
+    pub(crate) fn shared_defaults(&self) -> Arc<HashMap<String, HostFont>> {
+        Arc::clone(&self.defaults)
     }
 
     /// Lists all font entries available in the backing database.
     pub fn enumerate_entries(&self) -> Vec<String> {
-        let mut entries: Vec<String> = self
-            .data
-            .as_ref()
-            .map(|rdb| rdb.entries().into_iter().map(|meta| meta.name).collect())
-            .unwrap_or_default();
-        for entry in self.defaults.keys() {
-            if !entries.iter().any(|existing| existing == entry) {
-                entries.push(entry.clone());
-            }
-        }
-        entries
+        enumerate_with_defaults(&self.data, &self.defaults)
     }
 }
 
@@ -1,6 +1,7 @@
 use std::{
     collections::{HashMap, HashSet},
     ptr::NonNull,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -8,8 +9,11 @@ use dashi::{Buffer, BufferInfo, BufferUsage, BufferView, Context, Handle, Memory
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use super::{DatabaseEntry, primitives::Vertex};
-use crate::{DataCache, RDBView, defaults::default_primitives, error::NorenError};
+use super::{DatabaseEntry, fetch_or_default, primitives::Vertex};
+use crate::{
+    AssetHandle, CacheEntry, CacheStats, DataCache, EntryId, HandleReleases, RDBFile, RDBLayers,
+    RDBRawEntry, RDBView, RangeAllocator, RdbErr, defaults::default_primitives, error::NorenError,
+};
 
 #[cfg(test)]
 const UNLOAD_DELAY: Duration = Duration::from_secs(0);
@@ -65,10 +69,16 @@ pub struct DeviceGeometry {
 
 pub struct GeometryDBBuilder {
     ctx: Option<*mut Context>,
-    module_path: String,
+    module: GeometryModule,
     pooled_uploads: bool,
 }
 
+/// Where a [`GeometryDBBuilder`] reads its geometry entries from.
+enum GeometryModule {
+    Path(String),
+    Layers(RDBLayers),
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 struct VertexKey {
     position: [u32; 3],
@@ -129,6 +139,14 @@ impl GeometryLayer {
         self.populate_counts();
         self
     }
+
+    /// Borrows the layer's vertex and index data.
+    pub fn mapped(&self) -> MappedGeometryLayer<'_> {
+        MappedGeometryLayer {
+            vertices: &self.vertices,
+            indices: self.indices.as_deref(),
+        }
+    }
 }
 
 impl HostGeometry {
@@ -149,11 +167,200 @@ impl HostGeometry {
     }
 }
 
+/// Borrowed vertex and index data for one geometry layer.
+#[derive(Clone, Copy, Debug)]
+pub struct MappedGeometryLayer<'a> {
+    pub vertices: &'a [Vertex],
+    pub indices: Option<&'a [u32]>,
+}
+
+impl MappedGeometryLayer<'_> {
+    pub fn vertex_count(&self) -> u32 {
+        count_from_len(self.vertices.len())
+    }
+
+    pub fn index_count(&self) -> Option<u32> {
+        self.indices.map(|indices| count_from_len(indices.len()))
+    }
+
+    fn to_layer(&self) -> GeometryLayer {
+        GeometryLayer {
+            vertices: self.vertices.to_vec(),
+            indices: self.indices.map(<[u32]>::to_vec),
+            ..Default::default()
+        }
+        .with_counts()
+    }
+}
+
+/// Geometry whose vertex and index slices are borrowed rather than owned.
+///
+/// When read through [`GeometryDB::fetch_mapped_geometry`] the slices point
+/// straight into the memory-mapped RDB file.
+#[derive(Clone, Debug)]
+pub struct MappedGeometry<'a> {
+    pub base: MappedGeometryLayer<'a>,
+    pub lods: Vec<MappedGeometryLayer<'a>>,
+}
+
+impl MappedGeometry<'_> {
+    /// Copies the borrowed slices into owned host geometry.
+    pub fn to_host(&self) -> HostGeometry {
+        let base = self.base.to_layer();
+        HostGeometry {
+            vertices: base.vertices,
+            indices: base.indices,
+            vertex_count: base.vertex_count,
+            index_count: base.index_count,
+            lods: self
+                .lods
+                .iter()
+                .map(MappedGeometryLayer::to_layer)
+                .collect(),
+        }
+    }
+}
+
+impl HostGeometry {
+    /// Borrows the geometry's vertex and index data.
+    pub fn mapped(&self) -> MappedGeometry<'_> {
+        MappedGeometry {
+            base: MappedGeometryLayer {
+                vertices: &self.vertices,
+                indices: self.indices.as_deref(),
+            },
+            lods: self.lods.iter().map(GeometryLayer::mapped).collect(),
+        }
+    }
+}
+
+/// Entry name of the raw vertex slice stored for `entry` or one of its LODs.
+pub fn mapped_vertices_entry(entry: &str, lod: Option<usize>) -> String {
+    match lod {
+        Some(lod) => format!("{entry}#lod{lod}#vertices"),
+        None => format!("{entry}#vertices"),
+    }
+}
+
+/// Entry name of the raw index slice stored for `entry` or one of its LODs.
+pub fn mapped_indices_entry(entry: &str, lod: Option<usize>) -> String {
+    match lod {
+        Some(lod) => format!("{entry}#lod{lod}#indices"),
+        None => format!("{entry}#indices"),
+    }
+}
+
+/// Writes `geometry` so its vertices and indices can be borrowed from the mapped file.
+///
+/// The bincode entry keeps only the layer structure; vertex and index data are
+/// stored next to it as aligned POD slices.
+pub fn add_mapped_geometry(
+    rdb: &mut RDBFile,
+    entry: &str,
+    geometry: &HostGeometry,
+) -> Result<(), RdbErr> {
+    let strip = |vertices: &[Vertex], indices: &Option<Vec<u32>>| GeometryLayer {
+        vertices: Vec::new(),
+        indices: indices.as_ref().map(|_| Vec::new()),
+        vertex_count: count_from_len(vertices.len()),
+        index_count: indices
+            .as_ref()
+            .map(|indices| count_from_len(indices.len())),
+    };
+    let base = strip(&geometry.vertices, &geometry.indices);
+    let layout = HostGeometry {
+        vertices: base.vertices,
+        indices: base.indices,
+        vertex_count: base.vertex_count,
+        index_count: base.index_count,
+        lods: geometry
+            .lods
+            .iter()
+            .map(|lod| strip(&lod.vertices, &lod.indices))
+            .collect(),
+    };
+    rdb.add(entry, &layout)?;
+
+    let layers = std::iter::once((None, &geometry.vertices, &geometry.indices)).chain(
+        geometry
+            .lods
+            .iter()
+            .enumerate()
+            .map(|(idx, lod)| (Some(idx), &lod.vertices, &lod.indices)),
+    );
+    for (lod, vertices, indices) in layers {
+        rdb.add_pod_slice(&mapped_vertices_entry(entry, lod), vertices)?;
+        if let Some(indices) = indices {
+            rdb.add_pod_slice(&mapped_indices_entry(entry, lod), indices)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves the borrowed slices of an entry written by [`add_mapped_geometry`].
+fn load_mapped_geometry<'a>(
+    view: &'a RDBView,
+    entry: &str,
+) -> Result<MappedGeometry<'a>, NorenError> {
+    if !view.contains(&mapped_vertices_entry(entry, None)) {
+        return Err(NorenError::LookupFailure());
+    }
+
+    let layout = view.fetch::<HostGeometry>(entry)?;
+    let layer = move |lod: Option<usize>, indexed: bool| -> Result<_, NorenError> {
+        Ok(MappedGeometryLayer {
+            vertices: view.fetch_pod_slice::<Vertex>(&mapped_vertices_entry(entry, lod))?,
+            indices: if indexed {
+                Some(view.fetch_pod_slice::<u32>(&mapped_indices_entry(entry, lod))?)
+            } else {
+                None
+            },
+        })
+    };
+
+    Ok(MappedGeometry {
+        base: layer(None, layout.indices.is_some())?,
+        lods: layout
+            .lods
+            .iter()
+            .enumerate()
+            .map(|(idx, lod)| layer(Some(idx), lod.indices.is_some()))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// Reads host geometry from `data`, preferring mapped entries, then the built-in defaults.
+pub(crate) fn fetch_host_geometry(
+    data: &RDBLayers,
+    defaults: &HashMap<String, HostGeometry>,
+    entry: DatabaseEntry<'_>,
+) -> Result<HostGeometry, NorenError> {
+    if let Some(Ok(mapped)) = data
+        .find(entry)
+        .map(|view| load_mapped_geometry(view, entry))
+    {
+        info!(resource = "geometry", entry = %entry, source = "rdb-mapped");
+        return Ok(mapped.to_host());
+    }
+
+    fetch_or_default(data, defaults, "geometry", entry).map(HostGeometry::with_counts)
+}
+
 impl GeometryDBBuilder {
     pub fn new(ctx: Option<*mut Context>, module_path: &str) -> Self {
         Self {
             ctx,
-            module_path: module_path.to_string(),
+            module: GeometryModule::Path(module_path.to_string()),
+            pooled_uploads: false,
+        }
+    }
+
+    /// Starts a builder backed by already opened RDB layers.
+    pub fn from_layers(ctx: Option<*mut Context>, data: RDBLayers) -> Self {
+        Self {
+            ctx,
+            module: GeometryModule::Layers(data),
             pooled_uploads: false,
         }
     }
@@ -164,19 +371,21 @@ impl GeometryDBBuilder {
     }
 
     pub fn build(self) -> GeometryDB {
-        let data = match RDBView::load(&self.module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
+        let data = match self.module {
+            GeometryModule::Path(module_path) => RDBView::load(&module_path).ok().into(),
+            GeometryModule::Layers(data) => data,
         };
 
         GeometryDB {
             data,
             ctx: self.ctx.and_then(NonNull::new),
             cache: Default::default(),
-            defaults: default_primitives().into_iter().collect(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
             pooled_uploads: self.pooled_uploads,
             vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
             index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
         }
     }
 }
@@ -217,6 +426,25 @@ impl GeometryBufferRef {
             _ => {}
         }
     }
+
+    fn pooled_slice(&self) -> Option<&GeometryBufferSlice> {
+        match self {
+            GeometryBufferRef::Slice(slice) => Some(slice),
+            _ => None,
+        }
+    }
+
+    fn relocate(&mut self, buffer: Handle<Buffer>, moves: &HashMap<u32, u32>) {
+        let GeometryBufferRef::Slice(slice) = self else {
+            return;
+        };
+        if slice.buffer != buffer {
+            return;
+        }
+        if let Some(&offset) = moves.get(&slice.offset) {
+            slice.offset = offset;
+        }
+    }
 }
 
 impl DeviceGeometryLayer {
@@ -251,23 +479,50 @@ impl DeviceGeometry {
             lod.replace_handles(old, new);
         }
     }
+
+    /// Returns the GPU bytes held by the vertices and indices of every layer.
+    pub fn byte_size(&self) -> u64 {
+        self.layers()
+            .map(|layer| {
+                let vertices = u64::from(layer.vertex_count) * std::mem::size_of::<Vertex>() as u64;
+                let indices = u64::from(layer.index_count.unwrap_or(0)) * 4;
+                vertices + indices
+            })
+            .sum()
+    }
+
+    fn layers(&self) -> impl Iterator<Item = &DeviceGeometryLayer> {
+        std::iter::once(&self.base).chain(&self.lods)
+    }
+
+    fn layers_mut(&mut self) -> impl Iterator<Item = &mut DeviceGeometryLayer> {
+        std::iter::once(&mut self.base).chain(&mut self.lods)
+    }
 }
 
 pub struct GeometryDB {
     cache: DataCache<DeviceGeometry>,
     ctx: Option<NonNull<Context>>,
-    data: Option<RDBView>,
-    defaults: HashMap<String, HostGeometry>,
+    data: RDBLayers,
+    defaults: Arc<HashMap<String, HostGeometry>>,
     pooled_uploads: bool,
     vertex_pool: GeometryUploadPool,
     index_pool: GeometryUploadPool,
+    /// Bytes of cached geometry kept before unreferenced entries are evicted.
+    memory_budget: Option<u64>,
+    /// References released by dropped [`AssetHandle`]s.
+    releases: HandleReleases,
 }
 
-#[derive(Default)]
+/// Alignment of pooled slices; covers `u32` indices and `f32` vertex fields.
+const POOL_ALIGNMENT: u32 = 4;
+
 struct GeometryUploadPool {
     buffer: Handle<Buffer>,
     capacity: u32,
+    /// CPU copy of the buffer contents, sized to the allocator's capacity.
     data: Vec<u8>,
+    allocator: RangeAllocator,
     usage: BufferUsage,
     debug_name: String,
 }
@@ -278,6 +533,7 @@ impl GeometryUploadPool {
             buffer: Handle::default(),
             capacity: 0,
             data: Vec::new(),
+            allocator: RangeAllocator::new(0, POOL_ALIGNMENT),
             usage,
             debug_name: debug_name.to_string(),
         }
@@ -292,8 +548,19 @@ impl GeometryUploadPool {
         ctx: &mut Context,
         bytes: &[u8],
     ) -> Result<(GeometryBufferSlice, Option<Handle<Buffer>>), NorenError> {
-        let offset = self.data.len() as u32;
-        self.data.extend_from_slice(bytes);
+        let size = bytes.len() as u32;
+        let offset = match self.allocator.allocate(size) {
+            Some(offset) => offset,
+            None => {
+                let grown = (self.allocator.capacity() + size).next_power_of_two();
+                self.allocator.grow(grown);
+                self.data.resize(self.allocator.capacity() as usize, 0);
+                self.allocator
+                    .allocate(size)
+                    .ok_or(NorenError::UploadFailure())?
+            }
+        };
+        self.data[offset as usize..(offset + size) as usize].copy_from_slice(bytes);
 
         let replaced = self.ensure_capacity(ctx)?;
         Self::write_range(self.buffer, ctx, offset, bytes)?;
@@ -302,12 +569,49 @@ impl GeometryUploadPool {
             GeometryBufferSlice {
                 buffer: self.buffer,
                 offset,
-                size: bytes.len() as u32,
+                size,
             },
             replaced,
         ))
     }
 
+    /// Returns a slice's range to the pool once nothing references it.
+    fn release(&mut self, slice: &GeometryBufferSlice) {
+        if slice.buffer.valid() && slice.buffer == self.buffer {
+            self.allocator.free(slice.offset, slice.size);
+        }
+    }
+
+    /// Packs `live` slices to the front of the pool and uploads the result.
+    ///
+    /// Returns a map from old to new offsets.
+    fn defragment(
+        &mut self,
+        ctx: &mut Context,
+        live: &[GeometryBufferSlice],
+    ) -> Result<HashMap<u32, u32>, NorenError> {
+        let ranges: Vec<(u32, u32)> = live
+            .iter()
+            .filter(|slice| slice.buffer == self.buffer)
+            .map(|slice| (slice.offset, slice.size))
+            .collect();
+        let moves = self.allocator.compact(&ranges);
+
+        // Moves are in offset order and only ever shift data down, so copying
+        // in place never overwrites a range that has yet to move.
+        for &(from, to, size) in &moves {
+            self.data
+                .copy_within(from as usize..(from + size) as usize, to as usize);
+        }
+        let used = self.allocator.used_bytes() as usize;
+        self.data[used..].fill(0);
+        if self.buffer.valid() {
+            Self::write_range(self.buffer, ctx, 0, &self.data[..used])?;
+        }
+
+        Ok(moves.into_iter().map(|(from, to, _)| (from, to)).collect())
+    }
+
     fn ensure_capacity(&mut self, ctx: &mut Context) -> Result<Option<Handle<Buffer>>, NorenError> {
         let needed = self.data.len() as u32;
         if self.buffer.valid() && needed <= self.capacity {
@@ -416,73 +720,85 @@ impl GeometryDB {
         }
 
         let geom = geom.with_counts();
+        self.enter_gpu_layers(entry, &geom.mapped())
+    }
+
+    /// Uploads geometry straight from the mapped file when it was written with
+    /// [`add_mapped_geometry`], returning `None` for other entries.
+    fn enter_gpu_mapped_geometry(
+        &mut self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<Option<DeviceGeometry>, NorenError> {
+        // The borrowed slices point into the views, so hold them while uploading.
+        let layers = self.data.clone();
+
+        match layers
+            .find(entry)
+            .map(|view| load_mapped_geometry(view, entry))
+        {
+            Some(Ok(mapped)) => {
+                info!(resource = "geometry", entry = %entry, source = "rdb-mapped");
+                self.enter_gpu_layers(entry, &mapped).map(Some)
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn enter_gpu_layers(
+        &mut self,
+        entry: DatabaseEntry<'_>,
+        geom: &MappedGeometry<'_>,
+    ) -> Result<DeviceGeometry, NorenError> {
+        self.cache.check_key(entry)?;
 
         let device_geom = if cfg!(test) {
-            let lods = geom
-                .lods
-                .into_iter()
-                .map(|lod| DeviceGeometryLayer {
-                    vertices: Default::default(),
-                    indices: Default::default(),
-                    vertex_count: lod.vertex_count,
-                    index_count: lod.index_count,
-                    furikake_vertex_id: None,
-                    furikake_index_id: None,
-                })
-                .collect();
+            let layer = |layer: &MappedGeometryLayer<'_>| DeviceGeometryLayer {
+                vertices: Default::default(),
+                indices: Default::default(),
+                vertex_count: layer.vertex_count(),
+                index_count: layer.index_count(),
+                furikake_vertex_id: None,
+                furikake_index_id: None,
+            };
 
             DeviceGeometry {
-                base: DeviceGeometryLayer {
-                    vertices: Default::default(),
-                    indices: Default::default(),
-                    vertex_count: geom.vertex_count,
-                    index_count: geom.index_count,
-                    furikake_vertex_id: None,
-                    furikake_index_id: None,
-                },
-                vertex_count: geom.vertex_count,
-                index_count: geom.index_count,
-                lods,
+                base: layer(&geom.base),
+                vertex_count: geom.base.vertex_count(),
+                index_count: geom.base.index_count(),
+                lods: geom.lods.iter().map(layer).collect(),
             }
         } else {
-            let HostGeometry {
-                vertices,
-                indices,
-                vertex_count,
-                index_count,
-                lods,
-            } = geom;
             let ctx = unsafe { self.ctx.unwrap().as_mut() };
 
-            let base_layer = GeometryLayer {
-                vertices,
-                indices,
-                vertex_count,
-                index_count,
-            };
-
-            let base = self.upload_layer(ctx, entry, &base_layer)?;
+            let base = self.upload_layer(ctx, entry, &geom.base)?;
 
-            let lods = lods
-                .into_iter()
+            let lods = geom
+                .lods
+                .iter()
                 .enumerate()
                 .map(|(idx, layer)| {
                     let debug_name = format!("{entry}::lod{idx}");
-                    self.upload_layer(ctx, &debug_name, &layer)
+                    self.upload_layer(ctx, &debug_name, layer)
                 })
                 .collect::<Result<Vec<_>, _>>()?;
 
             DeviceGeometry {
-                vertex_count: base_layer.vertex_count,
-                index_count: base_layer.index_count,
+                vertex_count: geom.base.vertex_count(),
+                index_count: geom.base.index_count(),
                 base,
                 lods,
             }
         };
 
-        let cache_entry = self.cache.insert_or_increment(entry, || device_geom);
+        let bytes = device_geom.byte_size();
+        let device_geom = self
+            .cache
+            .insert_or_increment(entry, bytes, || device_geom)?
+            .payload
+            .clone();
+        self.trim_to_budget();
 
-        Ok(cache_entry.payload.clone())
+        Ok(device_geom)
     }
 
     /// Returns whether the requested geometry entry is already cached on the GPU.
@@ -495,19 +811,7 @@ impl GeometryDB {
         &mut self,
         entry: DatabaseEntry<'_>,
     ) -> Result<HostGeometry, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(geometry) = rdb.fetch::<HostGeometry>(entry) {
-                info!(resource = "geometry", entry = %entry, source = "rdb");
-                return Ok(geometry.with_counts());
-            }
-        }
-
-        if let Some(geometry) = self.defaults.get(entry) {
-            info!(resource = "geometry", entry = %entry, source = "default");
-            return Ok(geometry.clone().with_counts());
-        }
-
-        Err(NorenError::DataFailure())
+        fetch_host_geometry(&self.data, &self.defaults, entry)
     }
 
     /// Ensures the geometry is loaded on the GPU and increments its reference count.
@@ -516,48 +820,175 @@ impl GeometryDB {
         entry: DatabaseEntry<'_>,
     ) -> Result<DeviceGeometry, NorenError> {
         if !self.is_loaded(&entry) {
+            if let Some(device_geom) = self.enter_gpu_mapped_geometry(entry)? {
+                return Ok(device_geom);
+            }
             let host_geom = self.fetch_raw_geometry(entry)?;
             return self.enter_gpu_geometry(entry, host_geom);
         }
 
         let cache_entry = self
             .cache
-            .insert_or_increment(entry, || unreachable!("entry should already be loaded"));
+            .insert_or_increment(entry, 0, || unreachable!("entry should already be loaded"))?;
 
         Ok(cache_entry.payload.clone())
     }
 
+    /// Like [`GeometryDB::fetch_gpu_geometry`], but the reference is released
+    /// when the last clone of the returned handle drops.
+    pub fn fetch_gpu_geometry_handle(
+        &mut self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<AssetHandle<DeviceGeometry>, NorenError> {
+        self.process_releases();
+        let geometry = self.fetch_gpu_geometry(entry)?;
+        Ok(self.releases.handle(EntryId::new(entry), geometry))
+    }
+
+    /// Takes another handle to geometry that is already cached, without
+    /// hashing its name.
+    pub fn fetch_gpu_geometry_by_id(
+        &mut self,
+        id: EntryId,
+    ) -> Result<AssetHandle<DeviceGeometry>, NorenError> {
+        self.process_releases();
+        let cached = self.cache.get_mut(id).ok_or(NorenError::LookupFailure())?;
+        cached.acquire();
+        let geometry = cached.payload.clone();
+        Ok(self.releases.handle(id, geometry))
+    }
+
+    /// Drops the references of handles released since the last call.
+    fn process_releases(&mut self) {
+        let released = self.releases.drain();
+        if released.is_empty() {
+            return;
+        }
+
+        let unload_at = Instant::now() + UNLOAD_DELAY;
+        for id in released {
+            self.cache.decrement(id, unload_at);
+        }
+        self.release_stale();
+        self.trim_to_budget();
+    }
+
+    /// Borrows vertex and index data for an entry written with [`add_mapped_geometry`].
+    ///
+    /// The returned slices point straight into the memory-mapped database file.
+    pub fn fetch_mapped_geometry(
+        &self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<MappedGeometry<'_>, NorenError> {
+        let view = self.data.find(entry).ok_or(NorenError::DataFailure())?;
+        load_mapped_geometry(view, entry)
+    }
+
+    /// Returns whether the entry exists in the backing database or the built-in defaults.
+    pub fn contains(&self, entry: DatabaseEntry<'_>) -> bool {
+        self.data.contains(entry) || self.defaults.contains_key(entry)
+    }
+
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
+
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
+    }
+
+    pub(crate) fn shared_defaults(&self) -> Arc<HashMap<String, HostGeometry>> {
+        Arc::clone(&self.defaults)
+    }
+
     /// Lists all geometry entries available in the backing database.
     pub fn enumerate_entries(&self) -> Vec<String> {
         self.data
-            .as_ref()
-            .map(|rdb| rdb.entries().into_iter().map(|meta| meta.name).collect())
-            .unwrap_or_default()
+            .entries()
+            .into_iter()
+            .filter(|meta| !meta.pod)
+            .map(|meta| meta.name)
+            .collect()
     }
 
     /// Decrements a geometry reference, scheduling it for unloading after a delay.
     pub fn unref_entry(&mut self, entry: DatabaseEntry<'_>) -> Result<(), NorenError> {
         let unload_at = Instant::now() + UNLOAD_DELAY;
         match self.cache.decrement(entry, unload_at) {
-            Some(_) => Ok(()),
+            Some(_) => {
+                self.release_stale();
+                self.trim_to_budget();
+                Ok(())
+            }
             None => Err(NorenError::LookupFailure()),
         }
     }
 
+    /// Caps the bytes of cached geometry; `None` removes the cap.
+    ///
+    /// Whenever the cap is exceeded, unreferenced entries are evicted least
+    /// recently used first, without waiting for their unload delay.
+    pub fn set_memory_budget(&mut self, budget: Option<u64>) {
+        self.memory_budget = budget;
+        self.trim_to_budget();
+    }
+
+    /// Reports the bytes held by cached geometry.
+    pub fn memory_stats(&self) -> CacheStats {
+        self.cache.stats(self.memory_budget)
+    }
+
+    fn trim_to_budget(&mut self) {
+        if let Some(budget) = self.memory_budget {
+            let evicted = self.cache.evict_to(budget);
+            self.release_entries(evicted);
+        }
+    }
+
     // Checks whether any geometry needs to be unloaded, and does so.
     /// Removes expired geometry buffers from the GPU and cache.
     pub fn unload_pulse(&mut self) {
+        self.process_releases();
         let expired = self.cache.drain_expired(Instant::now());
+        self.release_entries(expired);
+    }
 
-        if cfg!(test) {
-            drop(expired);
-            return;
-        }
+    /// Swaps in remapped database files and evicts cached GPU copies of `changed` entries.
+    ///
+    /// Changed entries that are still referenced keep their buffers until the
+    /// last reference is released; the next fetch after that reloads them.
+    pub(crate) fn reload_layers(&mut self, data: RDBLayers, changed: &[String]) {
+        self.data = data;
+        let retired = changed
+            .iter()
+            .filter_map(|entry| self.cache.retire(entry))
+            .collect();
+        self.release_entries(retired);
+    }
 
+    /// Frees stale geometry whose last reference was just released.
+    fn release_stale(&mut self) {
+        let stale = self.cache.drain_stale();
+        self.release_entries(stale);
+    }
+
+    /// Returns pooled slices and destroys the dedicated buffers of evicted entries.
+    fn release_entries(&mut self, expired: Vec<(String, CacheEntry<DeviceGeometry>)>) {
         if expired.is_empty() {
             return;
         }
 
+        for (_key, entry) in &expired {
+            for layer in entry.payload.layers() {
+                if let Some(slice) = layer.vertices.pooled_slice() {
+                    self.vertex_pool.release(slice);
+                }
+                if let Some(slice) = layer.indices.pooled_slice() {
+                    self.index_pool.release(slice);
+                }
+            }
+        }
+
         let Ok(ctx) = self.ctx_mut() else {
             return;
         };
@@ -578,6 +1009,42 @@ impl GeometryDB {
             }
         }
     }
+
+    /// Packs live pooled slices to the front of the shared vertex and index
+    /// buffers and rewrites the offsets of cached geometry.
+    ///
+    /// The buffers are rewritten in place, so no submitted GPU work may still
+    /// read them. [`DeviceGeometry`] fetched before the call keeps its old
+    /// offsets and should be fetched again.
+    pub fn defragment_pool(&mut self) -> Result<(), NorenError> {
+        if !self.pooled_uploads {
+            return Ok(());
+        }
+
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        self.cache.for_each_payload_mut(|geometry| {
+            for layer in geometry.layers() {
+                vertices.extend(layer.vertices.pooled_slice().copied());
+                indices.extend(layer.indices.pooled_slice().copied());
+            }
+        });
+
+        let mut ctx = self.ctx.ok_or(NorenError::DashiContext())?;
+        let ctx = unsafe { ctx.as_mut() };
+        let vertex_moves = self.vertex_pool.defragment(ctx, &vertices)?;
+        let index_moves = self.index_pool.defragment(ctx, &indices)?;
+
+        let vertex_buffer = self.vertex_pool.buffer_handle();
+        let index_buffer = self.index_pool.buffer_handle();
+        self.cache.for_each_payload_mut(|geometry| {
+            for layer in geometry.layers_mut() {
+                layer.vertices.relocate(vertex_buffer, &vertex_moves);
+                layer.indices.relocate(index_buffer, &index_moves);
+            }
+        });
+        Ok(())
+    }
 }
 
 impl GeometryDB {
@@ -585,7 +1052,7 @@ impl GeometryDB {
         &mut self,
         ctx: &mut Context,
         debug_name: &str,
-        layer: &GeometryLayer,
+        layer: &MappedGeometryLayer<'_>,
     ) -> Result<DeviceGeometryLayer, NorenError> {
         if self.pooled_uploads {
             self.upload_layer_pooled(ctx, debug_name, layer)
@@ -597,18 +1064,9 @@ impl GeometryDB {
     fn upload_layer_dedicated(
         ctx: &mut Context,
         debug_name: &str,
-        layer: &GeometryLayer,
+        layer: &MappedGeometryLayer<'_>,
     ) -> Result<DeviceGeometryLayer, NorenError> {
-        debug_assert_eq!(layer.vertex_count, count_from_len(layer.vertices.len()));
-        debug_assert_eq!(
-            layer.index_count,
-            layer
-                .indices
-                .as_ref()
-                .map(|indices| count_from_len(indices.len()))
-        );
-
-        let vertex_bytes = bytemuck::cast_slice(&layer.vertices);
+        let vertex_bytes = bytemuck::cast_slice(layer.vertices);
 
         let vertex_buffer = ctx
             .make_buffer(&BufferInfo {
@@ -620,7 +1078,7 @@ impl GeometryDB {
             })
             .map_err(|_| NorenError::UploadFailure())?;
 
-        let index_handle = if let Some(indices) = &layer.indices {
+        let index_handle = if let Some(indices) = layer.indices {
             if indices.is_empty() {
                 GeometryBufferRef::None
             } else {
@@ -645,8 +1103,8 @@ impl GeometryDB {
         Ok(DeviceGeometryLayer {
             vertices: GeometryBufferRef::Dedicated(vertex_buffer),
             indices: index_handle,
-            vertex_count: layer.vertex_count,
-            index_count: layer.index_count,
+            vertex_count: layer.vertex_count(),
+            index_count: layer.index_count(),
             furikake_vertex_id: None,
             furikake_index_id: None,
         })
@@ -656,16 +1114,16 @@ impl GeometryDB {
         &mut self,
         ctx: &mut Context,
         _debug_name: &str,
-        layer: &GeometryLayer,
+        layer: &MappedGeometryLayer<'_>,
     ) -> Result<DeviceGeometryLayer, NorenError> {
-        let vertex_bytes = bytemuck::cast_slice(&layer.vertices);
+        let vertex_bytes = bytemuck::cast_slice(layer.vertices);
         let (vertex_slice, replaced_vertex) = self.vertex_pool.append(ctx, vertex_bytes)?;
 
         if let Some(old) = replaced_vertex {
             self.update_cached_buffer_handle(old, self.vertex_pool.buffer_handle());
         }
 
-        let index_buffer = if let Some(indices) = &layer.indices {
+        let index_buffer = if let Some(indices) = layer.indices {
             if indices.is_empty() {
                 GeometryBufferRef::None
             } else {
@@ -683,8 +1141,8 @@ impl GeometryDB {
         Ok(DeviceGeometryLayer {
             vertices: GeometryBufferRef::Slice(vertex_slice),
             indices: index_buffer,
-            vertex_count: layer.vertex_count,
-            index_count: layer.index_count,
+            vertex_count: layer.vertex_count(),
+            index_count: layer.index_count(),
             furikake_vertex_id: None,
             furikake_index_id: None,
         })
@@ -751,11 +1209,13 @@ mod tests {
         let mut db = GeometryDB {
             cache: DataCache::default(),
             ctx: None,
-            data: None,
-            defaults: default_primitives().into_iter().collect(),
+            data: RDBLayers::default(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
             pooled_uploads: false,
             vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
             index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
         };
 
         let device = db.enter_gpu_geometry("geom/lod_mesh", host_geom.clone())?;
@@ -811,6 +1271,55 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn mapped_geometry_borrows_from_file() -> Result<(), NorenError> {
+        let tmp = tempdir().expect("create temp dir");
+        let entry = "geom/mapped";
+        let host_geom = HostGeometry {
+            vertices: vec![sample_vertex(0.0), sample_vertex(1.0), sample_vertex(2.0)],
+            indices: Some(vec![0, 1, 2]),
+            lods: vec![GeometryLayer {
+                vertices: vec![sample_vertex(3.0)],
+                indices: None,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+        .with_counts();
+
+        let mut file = RDBFile::new();
+        add_mapped_geometry(&mut file, entry, &host_geom)?;
+        let path = tmp.path().join("mapped_geometry.rdb");
+        file.save(&path)?;
+
+        let mut db = GeometryDB::new(None, path.to_str().unwrap());
+        assert_eq!(db.enumerate_entries(), vec![entry.to_string()]);
+
+        let mapped = db.fetch_mapped_geometry(entry)?;
+        assert_eq!(mapped.base.vertices, host_geom.vertices.as_slice());
+        assert_eq!(mapped.base.indices, Some(&[0u32, 1, 2][..]));
+        assert_eq!(mapped.lods.len(), 1);
+        assert_eq!(
+            mapped.lods[0].vertices,
+            host_geom.lods[0].vertices.as_slice()
+        );
+        assert_eq!(mapped.lods[0].indices, None);
+
+        let loaded = db.fetch_raw_geometry(entry)?;
+        assert_eq!(loaded.vertices, host_geom.vertices);
+        assert_eq!(loaded.indices, host_geom.indices);
+        assert_eq!(loaded.vertex_count, 3);
+        assert_eq!(loaded.lods[0].vertex_count, 1);
+
+        let device = db.fetch_gpu_geometry(entry)?;
+        assert_eq!(device.vertex_count, 3);
+        assert_eq!(device.index_count, Some(3));
+        assert_eq!(device.lods.len(), 1);
+        assert!(db.fetch_mapped_geometry("geom/missing").is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn pooled_uploads_share_buffers_and_offsets() -> Result<(), NorenError> {
         let mut ctx =
@@ -818,11 +1327,13 @@ mod tests {
         let mut db = GeometryDB {
             cache: DataCache::default(),
             ctx: None,
-            data: None,
-            defaults: default_primitives().into_iter().collect(),
+            data: RDBLayers::default(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
             pooled_uploads: true,
             vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
             index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
         };
 
         let mesh_a = GeometryLayer {
@@ -839,8 +1350,8 @@ mod tests {
         }
         .with_counts();
 
-        let mut layer_a = db.upload_layer_pooled(&mut ctx, "mesh/a", &mesh_a)?;
-        let layer_b = db.upload_layer_pooled(&mut ctx, "mesh/b", &mesh_b)?;
+        let mut layer_a = db.upload_layer_pooled(&mut ctx, "mesh/a", &mesh_a.mapped())?;
+        let layer_b = db.upload_layer_pooled(&mut ctx, "mesh/b", &mesh_b.mapped())?;
 
         if let GeometryBufferRef::Slice(slice) = layer_a.vertices.clone() {
             if slice.buffer != db.vertex_pool.buffer_handle() {
@@ -901,11 +1412,13 @@ mod tests {
         let mut db = GeometryDB {
             cache: DataCache::default(),
             ctx: None,
-            data: Some(view),
-            defaults: default_primitives().into_iter().collect(),
+            data: Some(view).into(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
             pooled_uploads: false,
             vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
             index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
         };
 
         // First fetch should load from disk and cache
@@ -936,11 +1449,13 @@ mod tests {
         let mut db = GeometryDB {
             cache: DataCache::default(),
             ctx: None,
-            data: None,
-            defaults: default_primitives().into_iter().collect(),
+            data: RDBLayers::default(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
             pooled_uploads: false,
             vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
             index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
         };
 
         let geometry = db.fetch_raw_geometry(DEFAULT_GEOMETRY_ENTRIES[0])?;
@@ -948,4 +1463,109 @@ mod tests {
         assert!(!geometry.vertices.is_empty());
         Ok(())
     }
+
+    #[test]
+    fn memory_budget_evicts_least_recently_released_geometry() -> Result<(), NorenError> {
+        let mut db = GeometryDB {
+            cache: DataCache::default(),
+            ctx: None,
+            data: RDBLayers::default(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
+            pooled_uploads: false,
+            vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
+            index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
+        };
+        let [first, second] = [DEFAULT_GEOMETRY_ENTRIES[0], DEFAULT_GEOMETRY_ENTRIES[1]];
+
+        let first_bytes = db.fetch_gpu_geometry(first)?.byte_size();
+        let second_bytes = db.fetch_gpu_geometry(second)?.byte_size();
+        assert!(first_bytes > 0 && second_bytes > 0);
+        assert_eq!(db.memory_stats().resident_bytes, first_bytes + second_bytes);
+
+        db.set_memory_budget(Some(first_bytes.max(second_bytes)));
+        assert_eq!(db.memory_stats().entries, 2, "referenced entries stay");
+
+        db.unref_entry(first)?;
+        assert!(!db.is_loaded(&first));
+        db.unref_entry(second)?;
+        assert!(db.is_loaded(&second));
+        assert_eq!(db.memory_stats().resident_bytes, second_bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn dropping_the_last_handle_releases_geometry() -> Result<(), NorenError> {
+        let mut db = GeometryDB {
+            cache: DataCache::default(),
+            ctx: None,
+            data: RDBLayers::default(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
+            pooled_uploads: false,
+            vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
+            index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
+        };
+        let entry = DEFAULT_GEOMETRY_ENTRIES[0];
+
+        let handle = db.fetch_gpu_geometry_handle(entry)?;
+        assert_eq!(handle.id(), EntryId::new(entry));
+        let by_id = db.fetch_gpu_geometry_by_id(handle.id())?;
+        assert_eq!(by_id.vertex_count, handle.vertex_count);
+        assert_eq!(db.cache.get(entry).map(|cached| cached.refcount), Some(2));
+
+        let clone = handle.clone();
+        drop(handle);
+        drop(by_id);
+        db.unload_pulse();
+        assert_eq!(db.cache.get(entry).map(|cached| cached.refcount), Some(1));
+
+        drop(clone);
+        db.unload_pulse();
+        assert!(!db.is_loaded(&entry));
+        assert!(matches!(
+            db.fetch_gpu_geometry_by_id(EntryId::new(entry)),
+            Err(NorenError::LookupFailure())
+        ));
+        Ok(())
+    }
+    #[test]
+    fn reload_keeps_referenced_geometry_until_released() -> Result<(), NorenError> {
+        let mut db = GeometryDB {
+            cache: DataCache::default(),
+            ctx: None,
+            data: RDBLayers::default(),
+            defaults: Arc::new(default_primitives().into_iter().collect()),
+            pooled_uploads: false,
+            vertex_pool: GeometryUploadPool::new(BufferUsage::VERTEX, "geometry::vertices"),
+            index_pool: GeometryUploadPool::new(BufferUsage::INDEX, "geometry::indices"),
+            memory_budget: None,
+            releases: HandleReleases::default(),
+        };
+        let [live, idle] = [DEFAULT_GEOMETRY_ENTRIES[0], DEFAULT_GEOMETRY_ENTRIES[1]];
+
+        let handle = db.fetch_gpu_geometry_handle(live)?;
+        db.fetch_gpu_geometry(idle)?;
+        db.unref_entry(idle)?;
+
+        db.reload_layers(RDBLayers::default(), &[live.to_string(), idle.to_string()]);
+        assert!(
+            db.is_loaded(&live),
+            "referenced geometry survives the reload"
+        );
+        assert!(!db.is_loaded(&idle));
+        assert!(db.cache.get(live).is_some_and(|cached| cached.stale));
+
+        drop(handle);
+        db.unload_pulse();
+        assert!(!db.is_loaded(&live));
+        assert_eq!(db.memory_stats().resident_bytes, 0);
+
+        let reloaded = db.fetch_gpu_geometry(live)?;
+        assert!(reloaded.vertex_count > 0);
+        assert!(db.cache.get(live).is_some_and(|cached| !cached.stale));
+        Ok(())
+    }
 }
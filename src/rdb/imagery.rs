@@ -1,20 +1,25 @@
 use std::{
     collections::HashMap,
     ptr::NonNull,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
-use dashi::{Context, Handle, Image};
+use dashi::{
+    Buffer, BufferInfo, BufferUsage, CommandQueueInfo2, Context, Handle, Image, MemoryVisibility,
+    SubmitInfo, SubresourceRange, driver::command::CopyBufferImage, gpu::CommandStream,
+};
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use crate::{
-    DataCache, RDBView,
+    AssetHandle, CacheEntry, CacheStats, DataCache, EntryId, HandleReleases, RDBFile, RDBLayers,
+    RDBRawEntry, RDBView, RdbErr,
     defaults::{default_cubemaps, default_images},
-    utils::NorenError,
+    utils::{NorenError, rdbfile::from_bytes},
 };
 
-use super::DatabaseEntry;
+use super::{DatabaseEntry, fetch_or_default};
 
 #[cfg(test)]
 const UNLOAD_DELAY: Duration = Duration::from_secs(0);
@@ -83,19 +88,129 @@ impl ImageInfo {
             mip_levels: self.mip_levels,
         }
     }
+
+    /// Returns the block compression of the image format, if any.
+    pub fn block_format(&self) -> Option<BlockFormat> {
+        BlockFormat::from_format(self.format)
+    }
+}
+
+/// A texture format stored as 4x4 compressed blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockFormat {
+    /// RGB with optional 1-bit alpha, 8 bytes per block.
+    Bc1,
+    /// RGB with interpolated alpha, 16 bytes per block.
+    Bc3,
+    /// One channel, 8 bytes per block.
+    Bc4,
+    /// Two channels, such as tangent-space normals, 16 bytes per block.
+    Bc5,
+    /// High quality RGBA, 16 bytes per block.
+    Bc7,
+}
+
+impl BlockFormat {
+    /// Returns the block format backing `format`.
+    pub fn from_format(format: dashi::Format) -> Option<Self> {
+        match format {
+            dashi::Format::BC1 => Some(Self::Bc1),
+            dashi::Format::BC3 => Some(Self::Bc3),
+            dashi::Format::BC4 => Some(Self::Bc4),
+            dashi::Format::BC5 => Some(Self::Bc5),
+            dashi::Format::BC7 => Some(Self::Bc7),
+            _ => None,
+        }
+    }
+
+    /// Returns the dashi format for this block format.
+    pub fn format(self) -> dashi::Format {
+        match self {
+            Self::Bc1 => dashi::Format::BC1,
+            Self::Bc3 => dashi::Format::BC3,
+            Self::Bc4 => dashi::Format::BC4,
+            Self::Bc5 => dashi::Format::BC5,
+            Self::Bc7 => dashi::Format::BC7,
+        }
+    }
+
+    /// Returns the size of one 4x4 block in bytes.
+    pub fn block_bytes(self) -> usize {
+        match self {
+            Self::Bc1 | Self::Bc4 => 8,
+            Self::Bc3 | Self::Bc5 | Self::Bc7 => 16,
+        }
+    }
+
+    /// Returns the compressed size of a `width` x `height` level.
+    pub fn level_bytes(self, width: u32, height: u32) -> usize {
+        let blocks = width.div_ceil(4).max(1) * height.div_ceil(4).max(1);
+        blocks as usize * self.block_bytes()
+    }
 }
 
 #[repr(C)]
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct HostImage {
     pub info: ImageInfo,
+    /// Pixels of every mip level, largest first.
     pub data: Vec<u8>,
+    /// Byte offset of each mip level in `data`; empty when `data` holds only
+    /// level 0.
+    pub mip_offsets: Vec<u64>,
+}
+
+/// Image and cubemap layout written before mip chains were stored.
+#[derive(Deserialize)]
+struct SingleLevelImage {
+    info: ImageInfo,
+    data: Vec<u8>,
+}
+
+/// Decodes a payload stored under an older image or cubemap tag, all of which
+/// hold a single level.
+fn upgrade_image_layout<T>(
+    name: &str,
+    type_tag: u32,
+    bytes: &[u8],
+    single_level: fn(ImageInfo, Vec<u8>) -> T,
+) -> Result<T, RdbErr> {
+    let image: SingleLevelImage = from_bytes(name, type_tag, bytes)?;
+    Ok(single_level(image.info, image.data))
+}
+
+/// Reads images written under the `IMG0` tag or the type-name hash.
+pub(crate) fn upgrade_host_image(
+    name: &str,
+    type_tag: u32,
+    bytes: &[u8],
+) -> Result<HostImage, RdbErr> {
+    upgrade_image_layout(name, type_tag, bytes, HostImage::new)
+}
+
+/// Reads cubemaps written under the `CUBE` tag or the type-name hash.
+pub(crate) fn upgrade_host_cubemap(
+    name: &str,
+    type_tag: u32,
+    bytes: &[u8],
+) -> Result<HostCubemap, RdbErr> {
+    upgrade_image_layout(name, type_tag, bytes, HostCubemap::new)
 }
 
 impl HostImage {
-    /// Creates a new host-side image with metadata and pixel data.
+    /// Creates a new host-side image with metadata and level 0 pixel data.
     pub fn new(info: ImageInfo, data: Vec<u8>) -> Self {
-        Self { info, data }
+        Self::with_mips(info, data, Vec::new())
+    }
+
+    /// Creates a host-side image whose `data` packs the levels starting at
+    /// `mip_offsets`.
+    pub fn with_mips(info: ImageInfo, data: Vec<u8>, mip_offsets: Vec<u64>) -> Self {
+        Self {
+            info,
+            data,
+            mip_offsets,
+        }
     }
 
     /// Returns the image metadata.
@@ -107,6 +222,203 @@ impl HostImage {
     pub fn data(&self) -> &[u8] {
         &self.data
     }
+
+    /// Returns the number of mip levels stored in `data`.
+    pub fn mip_count(&self) -> usize {
+        self.mip_offsets.len().max(1)
+    }
+
+    /// Returns the pixels of one mip level.
+    pub fn mip_level(&self, level: usize) -> Option<&[u8]> {
+        mip_level(&self.data, &self.mip_offsets, level)
+    }
+}
+
+/// Slices one level out of pixels packed at `offsets`.
+fn mip_level<'a>(data: &'a [u8], offsets: &[u64], level: usize) -> Option<&'a [u8]> {
+    if offsets.is_empty() {
+        return (level == 0).then_some(data);
+    }
+    let start = *offsets.get(level)? as usize;
+    let end = offsets
+        .get(level + 1)
+        .map_or(data.len(), |end| *end as usize);
+    data.get(start..end)
+}
+
+/// Image metadata with pixel data borrowed from the memory-mapped database file.
+#[derive(Clone, Debug)]
+pub struct MappedImage<'a> {
+    pub info: ImageInfo,
+    pub data: &'a [u8],
+    pub mip_offsets: Vec<u64>,
+}
+
+impl MappedImage<'_> {
+    /// Returns the pixels of one mip level.
+    pub fn mip_level(&self, level: usize) -> Option<&[u8]> {
+        mip_level(self.data, &self.mip_offsets, level)
+    }
+}
+
+/// Entry name of the raw pixel slice stored for `entry`.
+pub fn mapped_pixels_entry(entry: &str) -> String {
+    format!("{entry}#pixels")
+}
+
+/// Writes `image` so its pixels can be borrowed from the mapped file.
+///
+/// The bincode entry keeps only the image metadata; pixels are stored next
+/// to it as an aligned POD slice.
+pub fn add_mapped_image(rdb: &mut RDBFile, entry: &str, image: &HostImage) -> Result<(), RdbErr> {
+    let metadata = HostImage::with_mips(image.info.clone(), Vec::new(), image.mip_offsets.clone());
+    rdb.add(entry, &metadata)?;
+    rdb.add_pod_slice(&mapped_pixels_entry(entry), &image.data)
+}
+
+/// Resolves the borrowed pixels of an entry written by [`add_mapped_image`].
+fn load_mapped_image<'a>(view: &'a RDBView, entry: &str) -> Result<MappedImage<'a>, NorenError> {
+    let pixels_entry = mapped_pixels_entry(entry);
+    if !view.contains(&pixels_entry) {
+        return Err(NorenError::LookupFailure());
+    }
+
+    let metadata = view.fetch::<HostImage>(entry)?;
+    let data = view.fetch_pod_slice::<u8>(&pixels_entry)?;
+    Ok(MappedImage {
+        info: metadata.info,
+        data,
+        mip_offsets: metadata.mip_offsets,
+    })
+}
+
+/// Reads a host image from `data`, preferring mapped entries, then the built-in defaults.
+pub(crate) fn fetch_host_image(
+    data: &RDBLayers,
+    defaults: &HashMap<String, HostImage>,
+    entry: DatabaseEntry<'_>,
+) -> Result<HostImage, NorenError> {
+    if let Some(Ok(image)) = data.find(entry).map(|view| load_mapped_image(view, entry)) {
+        info!(resource = "image", entry = %entry, source = "rdb-mapped");
+        return Ok(HostImage::with_mips(
+            image.info,
+            image.data.to_vec(),
+            image.mip_offsets,
+        ));
+    }
+
+    fetch_or_default(data, defaults, "image", entry)
+}
+
+/// Creates a GPU image from level 0 and copies any further mip levels into it.
+fn upload_image(
+    ctx: &mut Context,
+    entry: DatabaseEntry<'_>,
+    info: &ImageInfo,
+    data: &[u8],
+    mip_offsets: &[u64],
+) -> Result<DeviceImage, NorenError> {
+    let base = mip_level(data, mip_offsets, 0).ok_or(NorenError::DataFailure())?;
+    let gpu_info = info.gpu();
+    let mut dashi_info = info.dashi();
+    dashi_info.debug_name = entry;
+    dashi_info.initial_data = Some(base);
+
+    let img = ctx
+        .make_image(&dashi_info)
+        .map_err(|_| NorenError::UploadFailure())?;
+
+    if let Err(err) = upload_mip_levels(ctx, entry, img, info, data, mip_offsets) {
+        ctx.destroy_image(img);
+        return Err(err);
+    }
+
+    Ok(DeviceImage {
+        img,
+        info: gpu_info,
+    })
+}
+
+/// Copies the levels after level 0 into `img` through one staging buffer.
+fn upload_mip_levels(
+    ctx: &mut Context,
+    entry: DatabaseEntry<'_>,
+    img: Handle<Image>,
+    info: &ImageInfo,
+    data: &[u8],
+    mip_offsets: &[u64],
+) -> Result<(), NorenError> {
+    let levels = mip_offsets.len().min(info.mip_levels as usize);
+    if levels < 2 {
+        return Ok(());
+    }
+
+    let first = mip_offsets[1] as usize;
+    let tail = data.get(first..).ok_or(NorenError::DataFailure())?;
+    let byte_size = u32::try_from(tail.len()).map_err(|_| NorenError::UploadFailure())?;
+    let staging = ctx
+        .make_buffer(&BufferInfo {
+            debug_name: entry,
+            byte_size,
+            visibility: MemoryVisibility::CpuAndGpu,
+            usage: BufferUsage::ALL,
+            initial_data: Some(tail),
+        })
+        .map_err(|_| NorenError::UploadFailure())?;
+
+    let copied = copy_mip_levels(ctx, entry, staging, img, info, &mip_offsets[..levels]);
+    ctx.destroy_buffer(staging);
+    copied
+}
+
+/// Records and waits on one copy per level from `staging`, which starts at level 1.
+fn copy_mip_levels(
+    ctx: &mut Context,
+    entry: DatabaseEntry<'_>,
+    staging: Handle<Buffer>,
+    img: Handle<Image>,
+    info: &ImageInfo,
+    mip_offsets: &[u64],
+) -> Result<(), NorenError> {
+    // Offsets come from the file; check them before recording any copy.
+    let src_offsets = mip_offsets[1..]
+        .iter()
+        .map(|offset| {
+            offset
+                .checked_sub(mip_offsets[1])
+                .and_then(|offset| u32::try_from(offset).ok())
+                .ok_or(NorenError::DataFailure())
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut ring = ctx
+        .make_command_ring(&CommandQueueInfo2 {
+            debug_name: entry,
+            ..Default::default()
+        })
+        .map_err(|_| NorenError::UploadFailure())?;
+    let mut recorded = Ok(());
+    ring.record(|list| {
+        let mut stream = CommandStream::new().begin();
+        for (level, &src_offset) in (1u32..).zip(&src_offsets) {
+            stream = stream.copy_buffer_to_image(&CopyBufferImage {
+                src: staging,
+                dst: img,
+                range: SubresourceRange::new(level, 1, 0, info.layers),
+                src_offset,
+            });
+        }
+        recorded = stream
+            .end()
+            .append(list)
+            .map(|_| ())
+            .map_err(|_| NorenError::UploadFailure());
+    })
+    .map_err(|_| NorenError::UploadFailure())?;
+    recorded?;
+    ring.submit(&SubmitInfo::default())
+        .map_err(|_| NorenError::UploadFailure())?;
+    ring.wait_all().map_err(|_| NorenError::UploadFailure())
 }
 
 #[repr(C)]
@@ -114,8 +426,12 @@ impl HostImage {
 pub struct HostCubemap {
     /// Cubemap metadata. `layers` must be exactly 6.
     pub info: ImageInfo,
-    /// Raw pixel data stored as 6 consecutive image layers in face order.
+    /// Raw pixel data stored as 6 consecutive image layers in face order,
+    /// repeated for every mip level, largest first.
     pub data: Vec<u8>,
+    /// Byte offset of each mip level in `data`; empty when `data` holds only
+    /// level 0.
+    pub mip_offsets: Vec<u64>,
 }
 
 impl HostCubemap {
@@ -123,7 +439,17 @@ impl HostCubemap {
     ///
     /// The data is expected to be 6 consecutive faces matching the cubemap metadata.
     pub fn new(info: ImageInfo, data: Vec<u8>) -> Self {
-        Self { info, data }
+        Self::with_mips(info, data, Vec::new())
+    }
+
+    /// Creates a host-side cubemap whose `data` packs all 6 faces of each level
+    /// starting at `mip_offsets`.
+    pub fn with_mips(info: ImageInfo, data: Vec<u8>, mip_offsets: Vec<u64>) -> Self {
+        Self {
+            info,
+            data,
+            mip_offsets,
+        }
     }
 
     /// Creates a new host-side cubemap with packed per-face data.
@@ -143,7 +469,7 @@ impl HostCubemap {
             data.extend_from_slice(&face);
         }
 
-        Ok(Self { info, data })
+        Ok(Self::new(info, data))
     }
 
     /// Returns the cubemap metadata.
@@ -175,26 +501,32 @@ pub struct ImageDB {
     cache: DataCache<DeviceImage>,
     cubemap_cache: DataCache<DeviceCubemap>,
     ctx: Option<NonNull<Context>>,
-    data: Option<RDBView>,
-    defaults: HashMap<String, HostImage>,
-    cubemap_defaults: HashMap<String, HostCubemap>,
+    data: RDBLayers,
+    defaults: Arc<HashMap<String, HostImage>>,
+    cubemap_defaults: Arc<HashMap<String, HostCubemap>>,
+    /// Bytes of cached images and cubemaps kept before unreferenced ones are evicted.
+    memory_budget: Option<u64>,
+    /// Image references released by dropped [`AssetHandle`]s.
+    releases: HandleReleases,
 }
 
 impl ImageDB {
     /// Creates an image database helper for the provided GPU context and backing module.
     pub fn new(ctx: Option<*mut Context>, module_path: &str) -> Self {
-        let data = match RDBView::load(module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
-        };
+        Self::from_layers(ctx, RDBView::load(module_path).ok().into())
+    }
 
+    /// Creates an image database backed by already opened RDB layers.
+    pub fn from_layers(ctx: Option<*mut Context>, data: RDBLayers) -> Self {
         Self {
             data,
             ctx: ctx.and_then(NonNull::new),
             cache: Default::default(),
             cubemap_cache: Default::default(),
-            defaults: default_images().into_iter().collect(),
-            cubemap_defaults: default_cubemaps().into_iter().collect(),
+            defaults: Arc::new(default_images().into_iter().collect()),
+            cubemap_defaults: Arc::new(default_cubemaps().into_iter().collect()),
+            memory_budget: None,
+            releases: HandleReleases::default(),
         }
     }
 
@@ -217,21 +549,33 @@ impl ImageDB {
     ) -> Result<DeviceImage, NorenError> {
         let ctx: &mut Context = self.ctx_mut()?;
 
-        let HostImage { info, data } = image;
-
-        let gpu_info = info.gpu();
-        let mut dashi_info = info.dashi();
-        dashi_info.debug_name = entry;
-        dashi_info.initial_data = Some(&data);
+        upload_image(ctx, entry, &image.info, &image.data, &image.mip_offsets)
+    }
 
-        let img = ctx
-            .make_image(&dashi_info)
-            .map_err(|_| NorenError::UploadFailure())?;
+    /// Uploads pixels straight from the mapped file when the entry was written
+    /// with [`add_mapped_image`], returning `None` for other entries.
+    ///
+    /// The uploaded byte count is returned alongside the image.
+    fn enter_gpu_mapped_image(
+        &mut self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<Option<(DeviceImage, u64)>, NorenError> {
+        let Some(view) = self.data.find(entry) else {
+            return Ok(None);
+        };
+        let Ok(image) = load_mapped_image(view, entry) else {
+            return Ok(None);
+        };
 
-        Ok(DeviceImage {
-            img,
-            info: gpu_info,
-        })
+        info!(resource = "image", entry = %entry, source = "rdb-mapped");
+        let ctx = self
+            .ctx
+            .as_mut()
+            .map(|ctx| unsafe { ctx.as_mut() })
+            .ok_or(NorenError::DashiContext())?;
+        let bytes = image.data.len() as u64;
+        upload_image(ctx, entry, &image.info, image.data, &image.mip_offsets)
+            .map(|device| Some((device, bytes)))
     }
 
     /// Uploads a host cubemap to the GPU and returns its cube view and metadata.
@@ -242,21 +586,31 @@ impl ImageDB {
     ) -> Result<DeviceCubemap, NorenError> {
         let ctx: &mut Context = self.ctx_mut()?;
 
-        let HostCubemap { info, data } = cubemap;
+        let HostCubemap {
+            info,
+            data,
+            mip_offsets,
+        } = cubemap;
 
         if info.layers != 6 {
             return Err(NorenError::DataFailure());
         }
 
+        let base = mip_level(&data, &mip_offsets, 0).ok_or(NorenError::DataFailure())?;
         let gpu_info = info.gpu();
         let mut dashi_info = info.dashi_cube();
         dashi_info.debug_name = entry;
-        dashi_info.initial_data = Some(&data);
+        dashi_info.initial_data = Some(base);
 
         let img = ctx
             .make_image(&dashi_info)
             .map_err(|_| NorenError::UploadFailure())?;
 
+        if let Err(err) = upload_mip_levels(ctx, entry, img, &info, &data, &mip_offsets) {
+            ctx.destroy_image(img);
+            return Err(err);
+        }
+
         let view = dashi::ImageView {
             img,
             range: dashi::SubresourceRange::new(0, info.mip_levels, 0, 6),
@@ -280,22 +634,20 @@ impl ImageDB {
         self.cubemap_cache.get(*entry).is_some()
     }
 
+    /// Borrows metadata and pixels for an entry written with [`add_mapped_image`].
+    ///
+    /// The returned pixel slice points straight into the memory-mapped database file.
+    pub fn fetch_mapped_image(
+        &self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<MappedImage<'_>, NorenError> {
+        let view = self.data.find(entry).ok_or(NorenError::DataFailure())?;
+        load_mapped_image(view, entry)
+    }
+
     /// Retrieves host image data from the backing database file.
     pub fn fetch_raw_image(&mut self, entry: DatabaseEntry<'_>) -> Result<HostImage, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(image) = rdb.fetch::<HostImage>(entry) {
-                info!(resource = "image", entry = %entry, source = "rdb");
-                return Ok(image);
-            }
-        }
-
-        if let Some(image) = self.defaults.get(entry) {
-            tracing::info!("Fetching default {}", entry);
-            info!(resource = "image", entry = %entry, source = "default");
-            return Ok(image.clone());
-        }
-
-        Err(NorenError::DataFailure())
+        fetch_host_image(&self.data, &self.defaults, entry)
     }
 
     /// Retrieves host cubemap data from the backing database file.
@@ -303,56 +655,92 @@ impl ImageDB {
         &mut self,
         entry: DatabaseEntry<'_>,
     ) -> Result<HostCubemap, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(cubemap) = rdb.fetch::<HostCubemap>(entry) {
-                info!(resource = "cubemap", entry = %entry, source = "rdb");
-                return Ok(cubemap);
-            }
-        }
-
-        if let Some(cubemap) = self.cubemap_defaults.get(entry) {
-            tracing::info!("Fetching default {}", entry);
-            info!(resource = "cubemap", entry = %entry, source = "default");
-            return Ok(cubemap.clone());
-        }
-
-        Err(NorenError::DataFailure())
+        fetch_or_default(&self.data, &self.cubemap_defaults, "cubemap", entry)
     }
 
     /// Loads an image into GPU memory if needed and bumps its reference count.
     pub fn fetch_gpu_image(&mut self, entry: DatabaseEntry<'_>) -> Result<DeviceImage, NorenError> {
         if let Some(item) = self.cache.get_mut(entry) {
-            item.refcount += 1;
-            item.clear_unload();
+            item.acquire();
             return Ok(item.payload.clone());
         }
-
-        let host_image = self.fetch_raw_image(entry)?;
-        let device_image = self.enter_gpu_image(entry, host_image)?;
+        self.cache.check_key(entry)?;
+
+        let (device_image, bytes) = match self.enter_gpu_mapped_image(entry)? {
+            Some(mapped) => mapped,
+            None => {
+                let host_image = self.fetch_raw_image(entry)?;
+                let bytes = host_image.data.len() as u64;
+                (self.enter_gpu_image(entry, host_image)?, bytes)
+            }
+        };
 
         let cached_image = device_image.clone();
-        self.cache.insert_or_increment(entry, || cached_image);
+        self.cache
+            .insert_or_increment(entry, bytes, || cached_image)?;
+        self.trim_to_budget();
 
         Ok(device_image)
     }
 
+    /// Like [`ImageDB::fetch_gpu_image`], but the reference is released when
+    /// the last clone of the returned handle drops.
+    pub fn fetch_gpu_image_handle(
+        &mut self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<AssetHandle<DeviceImage>, NorenError> {
+        self.process_releases();
+        let image = self.fetch_gpu_image(entry)?;
+        Ok(self.releases.handle(EntryId::new(entry), image))
+    }
+
+    /// Takes another handle to an image that is already cached, without
+    /// hashing its name.
+    pub fn fetch_gpu_image_by_id(
+        &mut self,
+        id: EntryId,
+    ) -> Result<AssetHandle<DeviceImage>, NorenError> {
+        self.process_releases();
+        let cached = self.cache.get_mut(id).ok_or(NorenError::LookupFailure())?;
+        cached.acquire();
+        let image = cached.payload.clone();
+        Ok(self.releases.handle(id, image))
+    }
+
+    /// Drops the references of handles released since the last call.
+    fn process_releases(&mut self) {
+        let released = self.releases.drain();
+        if released.is_empty() {
+            return;
+        }
+
+        let unload_at = Instant::now() + UNLOAD_DELAY;
+        for id in released {
+            self.cache.decrement(id, unload_at);
+        }
+        self.release_stale();
+        self.trim_to_budget();
+    }
+
     /// Loads a cubemap into GPU memory if needed and bumps its reference count.
     pub fn fetch_gpu_cubemap(
         &mut self,
         entry: DatabaseEntry<'_>,
     ) -> Result<DeviceCubemap, NorenError> {
         if let Some(entry) = self.cubemap_cache.get_mut(entry) {
-            entry.refcount += 1;
-            entry.clear_unload();
+            entry.acquire();
             return Ok(entry.payload.clone());
         }
+        self.cubemap_cache.check_key(entry)?;
 
         let host_cubemap = self.fetch_raw_cubemap(entry)?;
+        let bytes = host_cubemap.data.len() as u64;
         let device_cubemap = self.enter_gpu_cubemap(entry, host_cubemap)?;
 
         let cached_cubemap = device_cubemap.clone();
         self.cubemap_cache
-            .insert_or_increment(entry, || cached_cubemap);
+            .insert_or_increment(entry, bytes, || cached_cubemap)?;
+        self.trim_to_budget();
 
         Ok(device_cubemap)
     }
@@ -364,7 +752,11 @@ impl ImageDB {
     pub fn unref_entry(&mut self, entry: DatabaseEntry<'_>) -> Result<(), NorenError> {
         let unload_at = Instant::now() + UNLOAD_DELAY;
         match self.cache.decrement(entry, unload_at) {
-            Some(_) => Ok(()),
+            Some(_) => {
+                self.release_stale();
+                self.trim_to_budget();
+                Ok(())
+            }
             None => Err(NorenError::LookupFailure()),
         }
     }
@@ -376,16 +768,110 @@ impl ImageDB {
     pub fn unref_cubemap(&mut self, entry: DatabaseEntry<'_>) -> Result<(), NorenError> {
         let unload_at = Instant::now() + UNLOAD_DELAY;
         match self.cubemap_cache.decrement(entry, unload_at) {
-            Some(_) => Ok(()),
+            Some(_) => {
+                self.release_stale();
+                self.trim_to_budget();
+                Ok(())
+            }
             None => Err(NorenError::LookupFailure()),
         }
     }
 
+    /// Caps the bytes of cached images and cubemaps; `None` removes the cap.
+    ///
+    /// Whenever the cap is exceeded, unreferenced entries are evicted least
+    /// recently used first, without waiting for their unload delay.
+    pub fn set_memory_budget(&mut self, budget: Option<u64>) {
+        self.memory_budget = budget;
+        self.trim_to_budget();
+    }
+
+    /// Reports the bytes held by cached images and cubemaps together.
+    pub fn memory_stats(&self) -> CacheStats {
+        let images = self.cache.stats(self.memory_budget);
+        let cubemaps = self.cubemap_cache.stats(self.memory_budget);
+        CacheStats {
+            resident_bytes: images.resident_bytes + cubemaps.resident_bytes,
+            entries: images.entries + cubemaps.entries,
+            budget: self.memory_budget,
+        }
+    }
+
+    /// Evicts the least recently used unreferenced image or cubemap until the
+    /// budget is met.
+    fn trim_to_budget(&mut self) {
+        let Some(budget) = self.memory_budget else {
+            return;
+        };
+
+        if self.cache.resident_bytes() + self.cubemap_cache.resident_bytes() <= budget {
+            return;
+        }
+
+        let mut image_candidates = self.cache.eviction_candidates().into_iter().peekable();
+        let mut cubemap_candidates = self
+            .cubemap_cache
+            .eviction_candidates()
+            .into_iter()
+            .peekable();
+        let mut images = Vec::new();
+        let mut cubemaps = Vec::new();
+        while self.cache.resident_bytes() + self.cubemap_cache.resident_bytes() > budget {
+            let take_image = match (image_candidates.peek(), cubemap_candidates.peek()) {
+                (Some((_, used)), Some((_, cubemap_used))) => used <= cubemap_used,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if take_image {
+                if let Some((key, _)) = image_candidates.next() {
+                    images.extend(self.cache.remove(&key).map(|entry| (key, entry)));
+                }
+            } else if let Some((key, _)) = cubemap_candidates.next() {
+                cubemaps.extend(self.cubemap_cache.remove(&key).map(|entry| (key, entry)));
+            }
+        }
+        self.release_entries(images, cubemaps);
+    }
+
     // Checks whether any imagery needs to be unloaded, and does so.
     /// Destroys expired GPU images whose unload delay has elapsed.
     pub fn unload_pulse(&mut self) {
+        self.process_releases();
         let expired = self.cache.drain_expired(Instant::now());
         let expired_cubemaps = self.cubemap_cache.drain_expired(Instant::now());
+        self.release_entries(expired, expired_cubemaps);
+    }
+
+    /// Swaps in remapped database files and evicts cached GPU copies of `changed` entries.
+    ///
+    /// Changed images and cubemaps that are still referenced keep their GPU
+    /// copies until the last reference is released.
+    pub(crate) fn reload_layers(&mut self, data: RDBLayers, changed: &[String]) {
+        self.data = data;
+        let retired = changed
+            .iter()
+            .filter_map(|entry| self.cache.retire(entry))
+            .collect();
+        let retired_cubemaps = changed
+            .iter()
+            .filter_map(|entry| self.cubemap_cache.retire(entry))
+            .collect();
+        self.release_entries(retired, retired_cubemaps);
+    }
+
+    /// Destroys stale images and cubemaps whose last reference was just released.
+    fn release_stale(&mut self) {
+        let stale = self.cache.drain_stale();
+        let stale_cubemaps = self.cubemap_cache.drain_stale();
+        self.release_entries(stale, stale_cubemaps);
+    }
+
+    fn release_entries(
+        &mut self,
+        expired: Vec<(String, CacheEntry<DeviceImage>)>,
+        expired_cubemaps: Vec<(String, CacheEntry<DeviceCubemap>)>,
+    ) {
         if expired.is_empty() && expired_cubemaps.is_empty() {
             return;
         }
@@ -401,13 +887,38 @@ impl ImageDB {
         }
     }
 
+    /// Returns whether the entry exists in the backing database or the built-in defaults.
+    pub fn contains(&self, entry: DatabaseEntry<'_>) -> bool {
+        self.data.contains(entry)
+            || self.defaults.contains_key(entry)
+            || self.cubemap_defaults.contains_key(entry)
+    }
+
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
+
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
+    }
+
+    pub(crate) fn shared_defaults(&self) -> Arc<HashMap<String, HostImage>> {
+        Arc::clone(&self.defaults)
+    }
+
+    pub(crate) fn shared_cubemap_defaults(&self) -> Arc<HashMap<String, HostCubemap>> {
+        Arc::clone(&self.cubemap_defaults)
+    }
+
     /// Lists all imagery entries available in the backing database.
     pub fn enumerate_entries(&self) -> Vec<String> {
         let mut str: Vec<String> = self
             .data
-            .as_ref()
-            .map(|rdb| rdb.entries().into_iter().map(|meta| meta.name).collect())
-            .unwrap_or_default();
+            .entries()
+            .into_iter()
+            .filter(|meta| !meta.pod)
+            .map(|meta| meta.name)
+            .collect();
 
         let defaults: Vec<String> = self.defaults.iter().map(|c| c.0.clone()).collect();
         let cubemap_defaults: Vec<String> =
@@ -438,7 +949,7 @@ mod tests {
 
         let data = vec![255u8; (info.dim[0] * info.dim[1] * 4) as usize];
 
-        HostImage { info, data }
+        HostImage::new(info, data)
     }
 
     fn create_sample_cubemap() -> HostCubemap {
@@ -510,6 +1021,72 @@ mod tests {
         let _ = fs::remove_file(&path);
     }
 
+    #[test]
+    fn mapped_images_borrow_pixels_from_file() {
+        let image = create_sample_image();
+
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "noren_image_mapped_test_{}.rdb",
+            std::process::id()
+        ));
+        let mut file = RDBFile::new();
+        add_mapped_image(&mut file, TEST_ENTRY, &image).expect("add mapped image");
+        file.save(&path).expect("write rdb");
+
+        let mut db = ImageDB::new(None, &path.to_string_lossy());
+        assert!(db.enumerate_entries().contains(&TEST_ENTRY.to_string()));
+        assert!(
+            !db.enumerate_entries()
+                .contains(&mapped_pixels_entry(TEST_ENTRY))
+        );
+
+        let mapped = db.fetch_mapped_image(TEST_ENTRY).expect("borrow pixels");
+        assert_eq!(mapped.info.dim, image.info.dim);
+        assert_eq!(mapped.data, image.data.as_slice());
+
+        let host = db.fetch_raw_image(TEST_ENTRY).expect("copy pixels");
+        assert_eq!(host.data, image.data);
+
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn images_written_before_mip_chains_still_load() {
+        #[derive(Serialize)]
+        struct SingleLevel {
+            info: ImageInfo,
+            data: Vec<u8>,
+        }
+
+        impl crate::RDBType for SingleLevel {
+            const TYPE_TAG: Option<u32> = Some(crate::fourcc(b"IMG0"));
+        }
+
+        let image = create_sample_image();
+        let mut path = std::env::temp_dir();
+        path.push(format!("noren_image_v0_test_{}.rdb", std::process::id()));
+        let mut file = RDBFile::new();
+        file.add(
+            TEST_ENTRY,
+            &SingleLevel {
+                info: image.info.clone(),
+                data: image.data.clone(),
+            },
+        )
+        .expect("add old image");
+        file.save(&path).expect("write rdb");
+
+        let mut db = ImageDB::new(None, &path.to_string_lossy());
+        let loaded = db.fetch_raw_image(TEST_ENTRY).expect("load old image");
+        assert_eq!(loaded.info.dim, image.info.dim);
+        assert_eq!(loaded.data, image.data);
+        assert!(loaded.mip_offsets.is_empty());
+        assert_eq!(loaded.mip_level(0), Some(image.data.as_slice()));
+
+        let _ = fs::remove_file(path);
+    }
+
     #[test]
     fn repeated_fetch_unref_cycle() {
         let mut ctx = match dashi::Context::headless(&Default::default()) {
@@ -585,6 +1162,48 @@ mod tests {
         let _ = fs::remove_file(&path);
     }
 
+    #[test]
+    fn reload_keeps_referenced_images_until_released() {
+        let mut ctx = match dashi::Context::headless(&Default::default()) {
+            Ok(ctx) => ctx,
+            Err(_) => return,
+        };
+
+        let image = create_sample_image();
+
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "noren_image_reload_test_{}.rdb",
+            std::process::id()
+        ));
+        write_sample_rdb(&path, &image);
+
+        let path_string = path.to_string_lossy().to_string();
+
+        let mut db = ImageDB::new(Some(&mut ctx), &path_string);
+
+        let handle = db
+            .fetch_gpu_image_handle(TEST_ENTRY)
+            .expect("load gpu image from rdb");
+        let layers = db.layers().clone();
+        db.reload_layers(layers, &[TEST_ENTRY.to_string()]);
+        assert!(
+            db.is_loaded(&TEST_ENTRY),
+            "referenced image survives the reload"
+        );
+        assert!(handle.img.valid());
+
+        drop(handle);
+        db.unload_pulse();
+        assert!(!db.is_loaded(&TEST_ENTRY));
+
+        db.fetch_gpu_image(TEST_ENTRY)
+            .expect("reload image after release");
+        assert!(db.is_loaded(&TEST_ENTRY));
+
+        let _ = fs::remove_file(&path);
+    }
+
     #[test]
     fn default_image_available_without_file() -> Result<(), NorenError> {
         let mut db = ImageDB::new(None, "./missing-image.rdb");
@@ -1,22 +1,101 @@
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use tracing::info;
+
+use crate::{NorenError, RDBLayers, RDBType, RdbErr};
+
 pub mod animation;
 pub mod audio;
 pub mod bind_table_layout;
+pub mod blend;
 pub mod font;
 pub mod geometry;
 pub mod imagery;
+pub mod pcm;
+pub mod pose;
 pub mod primitives;
 pub mod shader;
 pub mod skeleton;
+pub mod tags;
 pub mod terrain;
 
 pub use animation::*;
 pub use audio::*;
 pub use bind_table_layout::*;
+pub use blend::*;
 pub use font::*;
 pub use geometry::*;
 pub use imagery::*;
+pub use pcm::*;
+pub use pose::*;
 pub use shader::*;
 pub use skeleton::*;
 pub use terrain::*;
 
 pub type DatabaseEntry<'a> = &'a str;
+
+/// Fetches `entry` from the highest-priority layer of a module's backing RDBs.
+///
+/// Decode failures and type mismatches are logged and returned so callers can
+/// fall back to defaults; missing files or entries map to `DataFailure`.
+pub(crate) fn fetch_entry<T: DeserializeOwned + RDBType>(
+    data: &RDBLayers,
+    resource: &str,
+    entry: DatabaseEntry<'_>,
+) -> Result<T, NorenError> {
+    if data.is_empty() {
+        return Err(NorenError::DataFailure());
+    }
+
+    match data.fetch::<T>(entry) {
+        Ok(value) => Ok(value),
+        Err(err @ (RdbErr::Decode { .. } | RdbErr::TypeMismatch { .. })) => {
+            tracing::warn!(
+                resource = %resource,
+                entry = %entry,
+                error = %err,
+                "failed to decode entry"
+            );
+            Err(err.into())
+        }
+        Err(_) => Err(NorenError::DataFailure()),
+    }
+}
+
+/// Fetches `entry` like [`fetch_entry`], falling back to a module's built-in defaults.
+pub(crate) fn fetch_or_default<T: DeserializeOwned + RDBType + Clone>(
+    data: &RDBLayers,
+    defaults: &HashMap<String, T>,
+    resource: &str,
+    entry: DatabaseEntry<'_>,
+) -> Result<T, NorenError> {
+    let rdb_err = match fetch_entry::<T>(data, resource, entry) {
+        Ok(value) => {
+            info!(resource = %resource, entry = %entry, source = "rdb");
+            return Ok(value);
+        }
+        Err(err) => err,
+    };
+
+    if let Some(value) = defaults.get(entry) {
+        info!(resource = %resource, entry = %entry, source = "default");
+        return Ok(value.clone());
+    }
+
+    Err(rdb_err)
+}
+
+/// Lists the entries stored in `data`, followed by the defaults it does not override.
+pub(crate) fn enumerate_with_defaults<T>(
+    data: &RDBLayers,
+    defaults: &HashMap<String, T>,
+) -> Vec<String> {
+    let mut entries: Vec<String> = data.entries().into_iter().map(|meta| meta.name).collect();
+    for entry in defaults.keys() {
+        if !data.contains(entry) {
+            entries.push(entry.clone());
+        }
+    }
+    entries
+}
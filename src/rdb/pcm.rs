@@ -0,0 +1,318 @@
+use std::{io::Cursor, sync::Arc};
+
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{CODEC_TYPE_NULL, Decoder, DecoderOptions},
+    errors::Error as SymphoniaError,
+    formats::{FormatOptions, FormatReader},
+    io::{MediaSourceStream, MediaSourceStreamOptions},
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+use super::{AudioClip, AudioFormat};
+use crate::utils::NorenError;
+
+/// A fully decoded clip as interleaved PCM samples.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PcmBuffer {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Interleaved samples in `[-1.0, 1.0]`.
+    pub samples: Vec<f32>,
+}
+
+impl PcmBuffer {
+    /// Returns the number of sample frames, one sample per channel each.
+    pub fn frames(&self) -> usize {
+        self.samples.len() / self.channels.max(1) as usize
+    }
+
+    /// Returns the playback length in seconds.
+    pub fn duration_secs(&self) -> f32 {
+        frames_to_secs(self.frames() as u64, self.sample_rate)
+    }
+
+    /// Converts the samples to interleaved signed 16-bit PCM.
+    pub fn to_i16(&self) -> Vec<i16> {
+        self.samples.iter().copied().map(sample_to_i16).collect()
+    }
+}
+
+/// Decodes an audio clip into interleaved PCM one packet at a time.
+///
+/// Long music tracks never need to be decoded in full; each call to
+/// [`PcmStream::next_chunk`] decodes only the next packet of the clip.
+pub struct PcmStream {
+    name: String,
+    format: AudioFormat,
+    data: Arc<[u8]>,
+    reader: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    sample_rate: u32,
+    channels: u16,
+    frames: Option<u64>,
+    buffer: Option<SampleBuffer<f32>>,
+}
+
+impl PcmStream {
+    /// Probes the clip's container and prepares a decoder for its first audio track.
+    pub fn new(clip: AudioClip) -> Result<Self, NorenError> {
+        let data: Arc<[u8]> = clip.data.into();
+        let (reader, decoder, track_id) = open(&clip.format, &data)?;
+        let params = decoder.codec_params();
+        let sample_rate = params.sample_rate.unwrap_or_default();
+        let channels = params
+            .channels
+            .map_or(0, |channels| channels.count() as u16);
+        let frames = params.n_frames;
+
+        Ok(Self {
+            name: clip.name,
+            format: clip.format,
+            data,
+            reader,
+            decoder,
+            track_id,
+            sample_rate,
+            channels,
+            frames,
+            buffer: None,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the sample rate in Hz, or zero if the container does not
+    /// declare it and nothing has been decoded yet.
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Returns the channel count, or zero if the container does not declare it
+    /// and nothing has been decoded yet.
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    /// Returns the number of sample frames, if the container declares it.
+    pub fn total_frames(&self) -> Option<u64> {
+        self.frames
+    }
+
+    /// Returns the playback length in seconds, if the container declares it.
+    pub fn duration_secs(&self) -> Option<f32> {
+        self.frames
+            .filter(|_| self.sample_rate > 0)
+            .map(|frames| frames_to_secs(frames, self.sample_rate))
+    }
+
+    /// Decodes the next packet into interleaved `f32` samples.
+    ///
+    /// Returns `None` once the clip is exhausted. Corrupt packets are skipped.
+    pub fn next_chunk(&mut self) -> Result<Option<&[f32]>, NorenError> {
+        loop {
+            let packet = match self.reader.next_packet() {
+                Ok(packet) => packet,
+                Err(SymphoniaError::IoError(err))
+                    if err.kind() == std::io::ErrorKind::UnexpectedEof =>
+                {
+                    return Ok(None);
+                }
+                Err(SymphoniaError::ResetRequired) => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(SymphoniaError::DecodeError(_)) => continue,
+                Err(err) => return Err(err.into()),
+            };
+            let spec = *decoded.spec();
+            self.sample_rate = spec.rate;
+            self.channels = spec.channels.count() as u16;
+
+            let needed = decoded.capacity() * spec.channels.count();
+            if self
+                .buffer
+                .as_ref()
+                .is_none_or(|buffer| buffer.capacity() < needed)
+            {
+                self.buffer = Some(SampleBuffer::new(decoded.capacity() as u64, spec));
+            }
+            let buffer = self.buffer.as_mut().expect("sample buffer allocated");
+            buffer.copy_interleaved_ref(decoded);
+            return Ok(Some(buffer.samples()));
+        }
+    }
+
+    /// Decodes the next packet into interleaved signed 16-bit samples.
+    pub fn next_chunk_i16(&mut self) -> Result<Option<Vec<i16>>, NorenError> {
+        Ok(self
+            .next_chunk()?
+            .map(|samples| samples.iter().copied().map(sample_to_i16).collect()))
+    }
+
+    /// Restarts decoding from the beginning of the clip.
+    pub fn rewind(&mut self) -> Result<(), NorenError> {
+        let (reader, decoder, track_id) = open(&self.format, &self.data)?;
+        self.reader = reader;
+        self.decoder = decoder;
+        self.track_id = track_id;
+        Ok(())
+    }
+
+    /// Decodes the rest of the clip into one buffer.
+    pub fn decode_all(mut self) -> Result<PcmBuffer, NorenError> {
+        let mut samples = Vec::new();
+        while let Some(chunk) = self.next_chunk()? {
+            samples.extend_from_slice(chunk);
+        }
+
+        Ok(PcmBuffer {
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            samples,
+        })
+    }
+
+    /// Returns the number of sample frames in the clip, decoding it once when
+    /// the container does not declare a count. Decoding restarts afterwards.
+    pub fn frame_count(&mut self) -> Result<u64, NorenError> {
+        if let Some(frames) = self.frames {
+            return Ok(frames);
+        }
+
+        self.rewind()?;
+        let mut samples = 0u64;
+        while let Some(chunk) = self.next_chunk()? {
+            samples += chunk.len() as u64;
+        }
+        let frames = samples / u64::from(self.channels.max(1));
+        self.frames = Some(frames);
+        self.rewind()?;
+        Ok(frames)
+    }
+
+    /// Returns the clip length in seconds, decoding it when the container
+    /// does not declare a frame count.
+    pub fn measure_duration(mut self) -> Result<f32, NorenError> {
+        let frames = self.frame_count()?;
+        Ok(frames_to_secs(frames, self.sample_rate))
+    }
+}
+
+fn open(
+    format: &AudioFormat,
+    data: &Arc<[u8]>,
+) -> Result<(Box<dyn FormatReader>, Box<dyn Decoder>, u32), NorenError> {
+    let source = MediaSourceStream::new(
+        Box::new(Cursor::new(Arc::clone(data))),
+        MediaSourceStreamOptions::default(),
+    );
+    let mut hint = Hint::new();
+    if let Some(extension) = format.extension() {
+        hint.with_extension(extension);
+    }
+
+    let probed = symphonia::default::get_probe().format(
+        &hint,
+        source,
+        &FormatOptions::default(),
+        &MetadataOptions::default(),
+    )?;
+    let reader = probed.format;
+    let track = reader
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| NorenError::InvalidAudio("no decodable audio track".into()))?;
+    let decoder =
+        symphonia::default::get_codecs().make(&track.codec_params, &DecoderOptions::default())?;
+    let track_id = track.id;
+
+    Ok((reader, decoder, track_id))
+}
+
+fn frames_to_secs(frames: u64, sample_rate: u32) -> f32 {
+    if sample_rate == 0 {
+        return 0.0;
+    }
+    (frames as f64 / f64::from(sample_rate)) as f32
+}
+
+fn sample_to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * f32::from(i16::MAX)) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PcmStream;
+    use crate::rdb::{AudioClip, AudioFormat};
+
+    /// Builds a 16-bit PCM WAV file holding `frames` frames of a ramp.
+    fn wav(sample_rate: u32, channels: u16, frames: u32) -> Vec<u8> {
+        let data_len = frames * u32::from(channels) * 2;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * u32::from(channels) * 2).to_le_bytes());
+        bytes.extend_from_slice(&(channels * 2).to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+        for frame in 0..frames {
+            for _ in 0..channels {
+                let sample = (frame % 256) as i16 * 64;
+                bytes.extend_from_slice(&sample.to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn streams_wav_clip_as_interleaved_pcm() {
+        let clip = AudioClip::new("audio/ramp".into(), AudioFormat::Wav, wav(8000, 2, 12000));
+        let mut stream = PcmStream::new(clip.clone()).expect("open stream");
+        assert_eq!(stream.sample_rate(), 8000);
+        assert_eq!(stream.channels(), 2);
+        assert_eq!(stream.total_frames(), Some(12000));
+        assert_eq!(stream.duration_secs(), Some(1.5));
+
+        let first = stream
+            .next_chunk()
+            .expect("decode")
+            .expect("chunk")
+            .to_vec();
+        assert!(first.len() < 24000, "whole clip decoded at once");
+        assert_eq!(first[2], first[3]);
+        assert!((first[2] - 64.0 / 32768.0).abs() < 1e-6);
+
+        let chunk = stream.next_chunk_i16().expect("decode").expect("chunk");
+        assert_eq!(chunk.len() % 2, 0);
+
+        stream.rewind().expect("rewind");
+        let pcm = stream.decode_all().expect("decode all");
+        assert_eq!(pcm.frames(), 12000);
+        assert_eq!(pcm.samples[..first.len()], first[..]);
+        assert_eq!(pcm.to_i16()[2], 63);
+        assert_eq!(clip.measure_duration().expect("measure"), 1.5);
+    }
+
+    #[test]
+    fn rejects_undecodable_data() {
+        let clip = AudioClip::new("audio/junk".into(), AudioFormat::Unknown, vec![0; 64]);
+        assert!(PcmStream::new(clip).is_err());
+    }
+}
@@ -0,0 +1,419 @@
+use std::ops::{Add, Mul};
+
+use glam::{Mat4, Quat, Vec3, Vec4};
+
+use super::{
+    AnimationClip, AnimationInterpolation, AnimationOutput, AnimationSampler, AnimationTargetPath,
+    Joint, Skeleton,
+};
+
+/// How sample times outside `[0, duration]` map back onto a clip.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnimationWrap {
+    /// Repeats the clip, so `duration + t` samples like `t`.
+    #[default]
+    Loop,
+    /// Holds the first or last keyframe.
+    Clamp,
+}
+
+/// A joint transform relative to its parent joint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct JointPose {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+impl Default for JointPose {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl JointPose {
+    pub const IDENTITY: Self = Self {
+        translation: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        scale: Vec3::ONE,
+    };
+
+    /// Returns the rest transform stored on a skeleton joint.
+    pub fn from_joint(joint: &Joint) -> Self {
+        let rotation = Quat::from_array(joint.rotation);
+        Self {
+            translation: Vec3::from_array(joint.translation),
+            rotation: if rotation.length_squared() > 0.0 {
+                rotation.normalize()
+            } else {
+                Quat::IDENTITY
+            },
+            scale: Vec3::from_array(joint.scale),
+        }
+    }
+
+    pub fn matrix(&self) -> Mat4 {
+        Mat4::from_scale_rotation_translation(self.scale, self.rotation, self.translation)
+    }
+
+    /// Interpolates towards `other`, slerping the rotation.
+    pub fn lerp(&self, other: &Self, factor: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(other.translation, factor),
+            rotation: self.rotation.slerp(other.rotation, factor).normalize(),
+            scale: self.scale.lerp(other.scale, factor),
+        }
+    }
+}
+
+/// Joint transforms of a skeleton at one point in time.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Pose {
+    /// Transforms relative to each joint's parent.
+    pub local: Vec<JointPose>,
+    /// Transforms in skeleton space.
+    pub global: Vec<Mat4>,
+    /// `global * inverse_bind_matrix` per joint, ready for vertex skinning.
+    pub skinning: Vec<Mat4>,
+}
+
+impl Pose {
+    /// Returns the skeleton's rest pose.
+    pub fn rest(skeleton: &Skeleton) -> Self {
+        Self::from_local(
+            skeleton,
+            skeleton.joints.iter().map(JointPose::from_joint).collect(),
+        )
+    }
+
+    /// Builds global and skinning matrices from local joint transforms.
+    pub fn from_local(skeleton: &Skeleton, local: Vec<JointPose>) -> Self {
+        let joints = &skeleton.joints;
+        let count = joints.len().min(local.len());
+        let parent_of = |idx: usize| joints[idx].parent.filter(|&parent| parent < count);
+
+        let mut global: Vec<Option<Mat4>> = vec![None; count];
+        let mut chain = Vec::new();
+        for idx in 0..count {
+            // Walk up to the nearest resolved ancestor, then resolve downwards.
+            let mut current = Some(idx);
+            while let Some(joint) = current {
+                if global[joint].is_some() || chain.len() > count {
+                    break;
+                }
+                chain.push(joint);
+                current = parent_of(joint);
+            }
+            for joint in chain.drain(..).rev() {
+                let parent = parent_of(joint)
+                    .and_then(|parent| global[parent])
+                    .unwrap_or(Mat4::IDENTITY);
+                global[joint] = Some(parent * local[joint].matrix());
+            }
+        }
+
+        let global: Vec<Mat4> = global
+            .into_iter()
+            .map(|matrix| matrix.unwrap_or(Mat4::IDENTITY))
+            .collect();
+        let skinning = global
+            .iter()
+            .zip(joints)
+            .map(|(global, joint)| *global * Mat4::from_cols_array_2d(&joint.inverse_bind_matrix))
+            .collect();
+
+        Self {
+            local: local.into_iter().take(count).collect(),
+            global,
+            skinning,
+        }
+    }
+
+    pub fn joint_count(&self) -> usize {
+        self.local.len()
+    }
+}
+
+impl AnimationClip {
+    /// Returns the clip length, falling back to the last keyframe time when
+    /// `duration_seconds` is unset.
+    pub fn duration(&self) -> f32 {
+        if self.duration_seconds > 0.0 {
+            return self.duration_seconds;
+        }
+        self.samplers
+            .iter()
+            .filter_map(|sampler| sampler.input.last().copied())
+            .fold(0.0, f32::max)
+    }
+
+    /// Samples the clip at `time` seconds, looping past the end.
+    pub fn sample(&self, time: f32, skeleton: &Skeleton) -> Pose {
+        self.sample_with(time, skeleton, AnimationWrap::Loop)
+    }
+
+    /// Samples the clip at `time` seconds using the given wrap mode.
+    ///
+    /// Joints without a channel keep their rest transform. Morph target
+    /// weight channels are ignored.
+    pub fn sample_with(&self, time: f32, skeleton: &Skeleton, wrap: AnimationWrap) -> Pose {
+        let mut local: Vec<JointPose> = skeleton.joints.iter().map(JointPose::from_joint).collect();
+        self.apply_channels(self.wrap_time(time, wrap), &mut local, |joint| {
+            Some((joint, Vec3::ZERO))
+        });
+        Pose::from_local(skeleton, local)
+    }
+
+    /// Writes the channels sampled at `time` into `local`.
+    ///
+    /// `target` maps a channel's joint to the joint it drives in `local` and an
+    /// offset added to its translations.
+    pub(crate) fn apply_channels(
+        &self,
+        time: f32,
+        local: &mut [JointPose],
+        target: impl Fn(usize) -> Option<(usize, Vec3)>,
+    ) {
+        for channel in &self.channels {
+            let Some(sampler) = self.samplers.get(channel.sampler_index) else {
+                continue;
+            };
+            let Some((joint, offset)) = target(channel.target_node) else {
+                continue;
+            };
+            let Some(pose) = local.get_mut(joint) else {
+                continue;
+            };
+
+            match (&channel.target_path, &sampler.output) {
+                (AnimationTargetPath::Translation, AnimationOutput::Translations(values)) => {
+                    if let Some(value) = interpolate(
+                        sampler,
+                        values.len(),
+                        time,
+                        |idx| Vec3::from(values[idx]),
+                        Vec3::lerp,
+                    ) {
+                        pose.translation = value + offset;
+                    }
+                }
+                (AnimationTargetPath::Rotation, AnimationOutput::Rotations(values)) => {
+                    if let Some(value) = interpolate(
+                        sampler,
+                        values.len(),
+                        time,
+                        |idx| Vec4::from(values[idx]),
+                        slerp,
+                    ) {
+                        let rotation = Quat::from_vec4(value);
+                        if rotation.length_squared() > 0.0 {
+                            pose.rotation = rotation.normalize();
+                        }
+                    }
+                }
+                (AnimationTargetPath::Scale, AnimationOutput::Scales(values)) => {
+                    if let Some(value) = interpolate(
+                        sampler,
+                        values.len(),
+                        time,
+                        |idx| Vec3::from(values[idx]),
+                        Vec3::lerp,
+                    ) {
+                        pose.scale = value;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    pub(crate) fn wrap_time(&self, time: f32, wrap: AnimationWrap) -> f32 {
+        let duration = self.duration();
+        if duration <= 0.0 {
+            return 0.0;
+        }
+        match wrap {
+            AnimationWrap::Loop => time.rem_euclid(duration),
+            AnimationWrap::Clamp => time.clamp(0.0, duration),
+        }
+    }
+}
+
+fn slerp(from: Vec4, to: Vec4, factor: f32) -> Vec4 {
+    Vec4::from(Quat::from_vec4(from).slerp(Quat::from_vec4(to), factor))
+}
+
+/// Interpolates a sampler's output at `time`.
+///
+/// `value` reads output element `idx`; cubic spline outputs store an in-tangent,
+/// value, and out-tangent per keyframe, as in glTF.
+fn interpolate<T>(
+    sampler: &AnimationSampler,
+    len: usize,
+    time: f32,
+    value: impl Fn(usize) -> T,
+    lerp: fn(T, T, f32) -> T,
+) -> Option<T>
+where
+    T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+{
+    let cubic = sampler.interpolation == AnimationInterpolation::CubicSpline;
+    let stride = if cubic { 3 } else { 1 };
+    let keys = sampler.input.len().min(len / stride);
+    if keys == 0 {
+        return None;
+    }
+    let key = |idx: usize| value(idx * stride + usize::from(cubic));
+
+    let input = &sampler.input[..keys];
+    let next = input.partition_point(|&key_time| key_time <= time);
+    if next == 0 {
+        return Some(key(0));
+    }
+    if next == keys {
+        return Some(key(keys - 1));
+    }
+
+    let prev = next - 1;
+    let span = input[next] - input[prev];
+    if span <= 0.0 {
+        return Some(key(next));
+    }
+    let factor = (time - input[prev]) / span;
+
+    Some(match sampler.interpolation {
+        AnimationInterpolation::Step => key(prev),
+        AnimationInterpolation::Linear => lerp(key(prev), key(next), factor),
+        AnimationInterpolation::CubicSpline => {
+            let f2 = factor * factor;
+            let f3 = f2 * factor;
+            let out_tangent = value(prev * 3 + 2) * span;
+            let in_tangent = value(next * 3) * span;
+            key(prev) * (2.0 * f3 - 3.0 * f2 + 1.0)
+                + out_tangent * (f3 - 2.0 * f2 + factor)
+                + key(next) * (-2.0 * f3 + 3.0 * f2)
+                + in_tangent * (f3 - f2)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{Mat4, Quat, Vec3};
+
+    use super::{AnimationWrap, Pose};
+    use crate::{
+        defaults::{DEFAULT_ANIMATION_ENTRY, default_animations, default_skeletons},
+        rdb::{
+            AnimationChannel, AnimationClip, AnimationInterpolation, AnimationOutput,
+            AnimationSampler, AnimationTargetPath, Joint, Skeleton,
+        },
+    };
+
+    fn assert_mat_near(a: &Mat4, b: &Mat4, epsilon: f32) {
+        assert!(a.abs_diff_eq(*b, epsilon), "{a:?} != {b:?}");
+    }
+
+    fn single_joint(interpolation: AnimationInterpolation, output: Vec<[f32; 3]>) -> AnimationClip {
+        AnimationClip::new(
+            "slide",
+            0.0,
+            vec![AnimationSampler {
+                interpolation,
+                input: vec![0.0, 1.0],
+                output: AnimationOutput::Translations(output),
+            }],
+            vec![AnimationChannel {
+                sampler_index: 0,
+                target_node: 0,
+                target_path: AnimationTargetPath::Translation,
+            }],
+        )
+    }
+
+    #[test]
+    fn interpolates_step_linear_and_cubic_keys() {
+        let skeleton = Skeleton::new("one", vec![Joint::default()], Some(0));
+        let x =
+            |clip: &AnimationClip, time: f32| clip.sample(time, &skeleton).local[0].translation.x;
+
+        let linear = single_joint(
+            AnimationInterpolation::Linear,
+            vec![[0.0; 3], [2.0, 0.0, 0.0]],
+        );
+        assert_eq!(linear.duration(), 1.0);
+        assert!((x(&linear, 0.25) - 0.5).abs() < 1e-6);
+
+        let step = single_joint(
+            AnimationInterpolation::Step,
+            vec![[0.0; 3], [2.0, 0.0, 0.0]],
+        );
+        assert_eq!(x(&step, 0.75), 0.0);
+
+        // In-tangent, value, out-tangent per key; only the first key's out-tangent is set.
+        let cubic = single_joint(
+            AnimationInterpolation::CubicSpline,
+            vec![
+                [0.0; 3],
+                [0.0; 3],
+                [1.0, 0.0, 0.0],
+                [0.0; 3],
+                [0.0; 3],
+                [0.0; 3],
+            ],
+        );
+        assert!((x(&cubic, 0.5) - 0.125).abs() < 1e-6);
+    }
+
+    #[test]
+    fn fox_rest_pose_skins_to_identity() {
+        let (_, skeleton) = default_skeletons().remove(0);
+        let pose = Pose::rest(&skeleton);
+        assert_eq!(pose.joint_count(), skeleton.joints.len());
+        for skinning in &pose.skinning {
+            assert_mat_near(skinning, &Mat4::IDENTITY, 1e-3);
+        }
+    }
+
+    #[test]
+    fn fox_clip_samples_keys_and_wraps() {
+        let (_, skeleton) = default_skeletons().remove(0);
+        let clip = default_animations()
+            .into_iter()
+            .find(|(entry, _)| entry == DEFAULT_ANIMATION_ENTRY)
+            .map(|(_, clip)| clip)
+            .expect("default fox animation");
+        let duration = clip.duration();
+        assert!(duration > 0.0);
+
+        let start = clip.sample(0.0, &skeleton);
+        for channel in &clip.channels {
+            let sampler = &clip.samplers[channel.sampler_index];
+            let local = &start.local[channel.target_node];
+            match &sampler.output {
+                AnimationOutput::Rotations(values) => {
+                    let key = Quat::from_array(values[0]).normalize();
+                    assert!(
+                        local.rotation.abs_diff_eq(key, 1e-5)
+                            || local.rotation.abs_diff_eq(-key, 1e-5)
+                    );
+                }
+                AnimationOutput::Translations(values) => {
+                    assert!(local.translation.abs_diff_eq(Vec3::from(values[0]), 1e-5));
+                }
+                _ => {}
+            }
+        }
+
+        let looped = clip.sample(duration + 0.3, &skeleton);
+        let direct = clip.sample(0.3, &skeleton);
+        for (a, b) in looped.skinning.iter().zip(&direct.skinning) {
+            assert_mat_near(a, b, 1e-3);
+        }
+
+        let clamped = clip.sample_with(duration + 1.0, &skeleton, AnimationWrap::Clamp);
+        let end = clip.sample_with(duration, &skeleton, AnimationWrap::Clamp);
+        assert_eq!(clamped, end);
+        assert!(clamped.skinning.iter().all(|matrix| matrix.is_finite()));
+    }
+}
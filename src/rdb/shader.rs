@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use super::DatabaseEntry;
-use crate::{RDBView, utils::NorenError};
+use crate::{RDBLayers, RDBRawEntry, RDBView, utils::NorenError};
 
 const SPIRV_MAGIC_WORD: u32 = 0x0723_0203;
 
@@ -52,48 +52,70 @@ impl Default for ShaderModule {
     }
 }
 
+/// Fetches a shader module from `data`, ensuring it contains SPIR-V data.
+pub(crate) fn fetch_spirv_module(
+    data: &RDBLayers,
+    entry: DatabaseEntry<'_>,
+) -> Result<ShaderModule, NorenError> {
+    if !data.is_empty() {
+        let module = data.fetch::<ShaderModule>(entry)?;
+        if module.is_spirv() {
+            info!(resource = "shader", entry = %entry, source = "rdb");
+            return Ok(module);
+        }
+
+        return Err(NorenError::DataFailure());
+    }
+
+    Err(NorenError::DataFailure())
+}
+
 #[derive(Default)]
 pub struct ShaderDB {
-    data: Option<RDBView>,
+    data: RDBLayers,
 }
 
 impl ShaderDB {
     /// Loads shader modules from the given `.rdb` file path, if it exists.
     pub fn new(module_path: &str) -> Self {
-        let data = match RDBView::load(module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
-        };
+        Self::from_layers(RDBView::load(module_path).ok().into())
+    }
 
+    /// Creates a shader database backed by already opened RDB layers.
+    pub fn from_layers(data: RDBLayers) -> Self {
         Self { data }
     }
 
     /// Returns whether shader module data was loaded successfully.
     pub fn has_data(&self) -> bool {
-        self.data.is_some()
+        !self.data.is_empty()
     }
 
     /// Fetches a shader module by entry name, ensuring it contains SPIR-V data.
     pub fn fetch_module(&mut self, entry: DatabaseEntry<'_>) -> Result<ShaderModule, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            let module = rdb.fetch::<ShaderModule>(entry)?;
-            if module.is_spirv() {
-                info!(resource = "shader", entry = %entry, source = "rdb");
-                return Ok(module);
-            }
-
-            return Err(NorenError::DataFailure());
-        }
+        fetch_spirv_module(&self.data, entry)
+    }
+
+    /// Returns whether the entry exists in the backing database.
+    pub fn contains(&self, entry: DatabaseEntry<'_>) -> bool {
+        self.data.contains(entry)
+    }
+
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
 
-        Err(NorenError::DataFailure())
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
     }
 
     /// Lists all shader modules available in the backing database.
     pub fn enumerate_entries(&self) -> Vec<String> {
         self.data
-            .as_ref()
-            .map(|rdb| rdb.entries().into_iter().map(|meta| meta.name).collect())
-            .unwrap_or_default()
+            .entries()
+            .into_iter()
+            .map(|meta| meta.name)
+            .collect()
     }
 }
 
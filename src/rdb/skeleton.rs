@@ -1,10 +1,9 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
 
 use serde::{Deserialize, Serialize};
-use tracing::info;
 
-use super::DatabaseEntry;
-use crate::{RDBView, defaults::default_skeletons, utils::NorenError};
+use super::{DatabaseEntry, fetch_or_default};
+use crate::{RDBLayers, RDBRawEntry, RDBView, defaults::default_skeletons, utils::NorenError};
 
 #[repr(C)]
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -70,49 +69,52 @@ impl Default for Joint {
 
 #[derive(Default)]
 pub struct SkeletonDB {
-    data: Option<RDBView>,
-    defaults: HashMap<String, Skeleton>,
+    data: RDBLayers,
+    defaults: Arc<HashMap<String, Skeleton>>,
 }
 
 impl SkeletonDB {
     /// Loads skeleton assets from the given `.rdb` file path, if present.
     pub fn new(module_path: &str) -> Self {
-        let data = match RDBView::load(module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
-        };
+        Self::from_layers(RDBView::load(module_path).ok().into())
+    }
 
+    /// Creates a skeleton database backed by already opened RDB layers.
+    pub fn from_layers(data: RDBLayers) -> Self {
         Self {
             data,
-            defaults: default_skeletons().into_iter().collect(),
+            defaults: Arc::new(default_skeletons().into_iter().collect()),
         }
     }
 
     /// Fetches a skeleton asset by entry name.
     pub fn fetch_skeleton(&mut self, entry: DatabaseEntry<'_>) -> Result<Skeleton, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(skeleton) = rdb.fetch::<Skeleton>(entry) {
-                info!(resource = "skeleton", entry = %entry, source = "rdb");
-                return Ok(skeleton);
-            }
-        }
+        fetch_or_default(&self.data, &self.defaults, "skeleton", entry)
+    }
 
-        if let Some(skeleton) = self.defaults.get(entry) {
-            info!(resource = "skeleton", entry = %entry, source = "default");
-            return Ok(skeleton.clone());
-        }
+    /// Returns whether the entry exists in the backing database or the built-in defaults.
+    pub fn contains(&self, entry: DatabaseEntry<'_>) -> bool {
+        self.data.contains(entry) || self.defaults.contains_key(entry)
+    }
+
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
+
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
+    }
 
-        Err(NorenError::DataFailure())
+    pub(crate) fn shared_defaults(&self) -> Arc<HashMap<String, Skeleton>> {
+        Arc::clone(&self.defaults)
     }
 
     /// Lists skeleton entries available in the backing database.
     pub fn enumerate_entries(&self) -> Vec<String> {
         let mut entries: Vec<String> = self.defaults.keys().cloned().collect();
-        if let Some(rdb) = &self.data {
-            for meta in rdb.entries() {
-                if !entries.contains(&meta.name) {
-                    entries.push(meta.name);
-                }
+        for meta in self.data.entries() {
+            if !entries.contains(&meta.name) {
+                entries.push(meta.name);
             }
         }
 
@@ -0,0 +1,146 @@
+use std::any::type_name;
+
+pub use crate::utils::rdbfile::fourcc;
+use crate::utils::{error::RdbErr, rdbfile::RDBType};
+
+use super::{
+    AnimationClip, AudioClip, HostCubemap, HostFont, HostGeometry, HostImage, ShaderModule,
+    Skeleton, TerrainChunk, TerrainChunkArtifact, TerrainChunkState, TerrainGeneratorDefinition,
+    TerrainMutationLayer, TerrainMutationOp, TerrainProjectSettings,
+    audio::upgrade_audio_clip,
+    imagery::{upgrade_host_cubemap, upgrade_host_image},
+    primitives::Vertex,
+};
+
+/// An explicitly assigned on-disk tag for a serialized asset type.
+pub struct StableTypeTag {
+    pub tag: u32,
+    /// Tags the type was written under before its layout changed.
+    pub older_tags: &'static [u32],
+    type_name: fn() -> &'static str,
+}
+
+impl StableTypeTag {
+    const fn of<T: RDBType>(code: &[u8; 4]) -> Self {
+        Self {
+            tag: fourcc(code),
+            older_tags: T::OLDER_TAGS,
+            type_name: type_name::<T>,
+        }
+    }
+
+    /// Returns the Rust type name the tag is assigned to.
+    pub fn type_name(&self) -> &'static str {
+        (self.type_name)()
+    }
+}
+
+/// Assigns each type its [`RDBType::TYPE_TAG`] and lists it in [`STABLE_TYPE_TAGS`].
+///
+/// `upgrading [old tags] with path` reads payloads stored under the old tags,
+/// or under the type-name hash, through the given [`RDBType::upgrade`]
+/// function. The tag list is left out when only the hash needs upgrading.
+macro_rules! stable_type_tags {
+    ($($ty:ty => $code:literal $(upgrading $([$($old:literal),+])? with $upgrade:path)?),* $(,)?) => {
+        $(impl RDBType for $ty {
+            const TYPE_TAG: Option<u32> = Some(fourcc($code));
+            $(
+                const OLDER_TAGS: &'static [u32] = &[$($(fourcc($old)),+)?];
+
+                fn upgrade(name: &str, type_tag: u32, bytes: &[u8]) -> Result<Self, RdbErr> {
+                    $upgrade(name, type_tag, bytes)
+                }
+            )?
+        })*
+
+        /// Tags written for asset types stored in RDB files.
+        ///
+        /// These stay fixed when the Rust types are renamed or moved. Never reuse or
+        /// change a tag once files containing it have shipped.
+        static STABLE_TYPE_TAGS: &[StableTypeTag] = &[
+            $(StableTypeTag::of::<$ty>($code)),*
+        ];
+    };
+}
+
+stable_type_tags! {
+    HostGeometry => b"GEOM",
+    HostImage => b"IMG1" upgrading [b"IMG0"] with upgrade_host_image,
+    HostCubemap => b"CUB1" upgrading [b"CUBE"] with upgrade_host_cubemap,
+    AudioClip => b"AUD2" upgrading with upgrade_audio_clip,
+    HostFont => b"FONT",
+    Skeleton => b"SKEL",
+    AnimationClip => b"ANIM",
+    ShaderModule => b"SHDR",
+    TerrainChunk => b"TCHK",
+    TerrainChunkArtifact => b"TART",
+    TerrainChunkState => b"TSTA",
+    TerrainProjectSettings => b"TPRJ",
+    TerrainGeneratorDefinition => b"TGEN",
+    TerrainMutationLayer => b"TLAY",
+    TerrainMutationOp => b"TMOP",
+    Vertex => b"VERT",
+}
+
+/// Returns every registered stable type tag.
+pub fn stable_type_tags() -> &'static [StableTypeTag] {
+    STABLE_TYPE_TAGS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        RdbErr,
+        utils::rdbfile::{RDBFile, RDBView, legacy_type_tag_for, type_tag_for},
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn stable_tags_are_unique() {
+        let mut tags = HashSet::new();
+        let mut names = HashSet::new();
+        for entry in stable_type_tags() {
+            for &tag in std::iter::once(&entry.tag).chain(entry.older_tags) {
+                assert!(tags.insert(tag), "duplicate tag {tag:#010X}");
+            }
+            assert!(names.insert(entry.type_name()), "duplicate type");
+        }
+    }
+
+    #[test]
+    fn registered_types_use_their_stable_tag() {
+        assert_eq!(type_tag_for::<HostGeometry>(), fourcc(b"GEOM"));
+        assert_eq!(type_tag_for::<HostImage>(), fourcc(b"IMG1"));
+        assert_ne!(
+            type_tag_for::<HostImage>(),
+            legacy_type_tag_for::<HostImage>()
+        );
+        assert_eq!(type_tag_for::<u32>(), legacy_type_tag_for::<u32>());
+    }
+
+    #[test]
+    fn fetch_rejects_mismatched_types() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let path = temp.path().join("tags.rdb");
+
+        let mut rdb = RDBFile::new();
+        rdb.add("geometry/quad", &HostGeometry::default())
+            .expect("add geometry");
+        rdb.save(&path).expect("save");
+
+        let view = RDBView::load(&path).expect("load");
+        assert_eq!(view.entries()[0].type_tag, fourcc(b"GEOM"));
+        let err = view
+            .fetch::<HostImage>("geometry/quad")
+            .expect_err("image fetch from geometry entry");
+        assert!(matches!(
+            err,
+            RdbErr::TypeMismatch { entry, expected, found }
+                if entry == "geometry/quad"
+                    && expected == fourcc(b"IMG1")
+                    && found == fourcc(b"GEOM")
+        ));
+        assert!(view.fetch::<HostGeometry>("geometry/quad").is_ok());
+    }
+}
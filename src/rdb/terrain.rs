@@ -10,8 +10,12 @@ use std::{
 };
 use tracing::info;
 
-use super::DatabaseEntry;
-use crate::{RDBView, error::NorenError};
+use super::{DatabaseEntry, fetch_entry};
+use crate::{
+    RDBLayers, RDBRawEntry, RDBView, RdbErr,
+    error::NorenError,
+    utils::rdbfile::{from_bytes, type_tag_for},
+};
 
 /// RDB schema for terrain data.
 ///
@@ -354,10 +358,13 @@ impl LegacyTerrainMutationOp {
     }
 }
 
-pub(crate) fn deserialize_legacy_mutation_op(bytes: &[u8]) -> TerrainMutationOp {
+pub(crate) fn deserialize_legacy_mutation_op(
+    entry: &str,
+    bytes: &[u8],
+) -> Result<TerrainMutationOp, RdbErr> {
     let legacy: LegacyTerrainMutationOp =
-        bincode::deserialize(bytes).expect("deserialize legacy mutation op");
-    legacy.upgrade()
+        from_bytes(entry, type_tag_for::<TerrainMutationOp>(), bytes)?;
+    Ok(legacy.upgrade())
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -994,7 +1001,7 @@ impl TerrainChunk {
 }
 
 pub struct TerrainDB {
-    data: Option<RDBView>,
+    data: RDBLayers,
     fallback_chunk: Option<TerrainChunk>,
     chunk_artifact_index: TerrainChunkArtifactIndex,
 }
@@ -1052,93 +1059,69 @@ impl TerrainChunkArtifactIndex {
 }
 
 impl TerrainDB {
+    /// Loads terrain data from the given `.rdb` file path, if it exists.
     pub fn new(module_path: &str) -> Self {
-        let data = match RDBView::load(module_path) {
-            Ok(d) => Some(d),
-            Err(_) => None,
-        };
+        Self::with_layers(Some(module_path), RDBView::load(module_path).ok().into())
+    }
+
+    /// Creates a terrain database backed by already opened RDB layers.
+    pub fn from_layers(data: RDBLayers) -> Self {
+        Self::with_layers(None, data)
+    }
+
+    fn with_layers(module_path: Option<&str>, data: RDBLayers) -> Self {
         let mut chunk_artifact_index = TerrainChunkArtifactIndex::default();
 
-        let fallback_chunk = if data.is_none() {
+        let fallback_chunk = if data.is_empty() {
             Some(default_terrain_chunk())
         } else {
-            if let Some(rdb) = data.as_ref() {
-                let entries = rdb.entries();
-                let mut projects = HashSet::new();
-                let mut project_settings = 0;
-                let mut generators = 0;
-                let mut mutation_layers = 0;
-                let mut mutation_ops = 0;
-                let mut chunk_artifacts = 0;
-                let mut chunk_states = 0;
-
-                for entry in &entries {
-                    if let Some(rest) = entry.name.strip_prefix(TERRAIN_PROJECT_PREFIX) {
-                        if let Some(rest) = rest.strip_prefix('/') {
-                            if let Some(project_key) = rest.split('/').next() {
-                                if !project_key.is_empty() {
-                                    projects.insert(project_key.to_string());
-                                }
+            let mut projects = HashSet::new();
+            let project_settings = data
+                .entries_with_prefix(TERRAIN_PROJECT_PREFIX)
+                .into_iter()
+                .inspect(|entry| {
+                    let rest = &entry.name[TERRAIN_PROJECT_PREFIX.len()..];
+                    if let Some(rest) = rest.strip_prefix('/') {
+                        if let Some(project_key) = rest.split('/').next() {
+                            if !project_key.is_empty() {
+                                projects.insert(project_key.to_string());
                             }
                         }
-                        project_settings += 1;
-                    }
-                    if entry
-                        .name
-                        .starts_with(&format!("{}/", TERRAIN_GENERATOR_PREFIX))
-                    {
-                        generators += 1;
-                    }
-                    if entry
-                        .name
-                        .starts_with(&format!("{}/", TERRAIN_MUTATION_LAYER_PREFIX))
-                    {
-                        mutation_layers += 1;
-                    }
-                    if entry
-                        .name
-                        .starts_with(&format!("{}/", TERRAIN_MUTATION_OP_PREFIX))
-                    {
-                        mutation_ops += 1;
-                    }
-                    if entry
-                        .name
-                        .starts_with(&format!("{}/", TERRAIN_CHUNK_ARTIFACT_PREFIX))
-                    {
-                        chunk_artifacts += 1;
-                        if let Some(key) = parse_chunk_artifact_entry(&entry.name) {
-                            chunk_artifact_index.insert(
-                                &key.project_key,
-                                key.chunk_coords,
-                                key.lod,
-                            );
-                        }
-                    }
-                    if entry
-                        .name
-                        .starts_with(&format!("{}/", TERRAIN_CHUNK_STATE_PREFIX))
-                    {
-                        chunk_states += 1;
                     }
+                })
+                .count();
+            let count_prefix = |prefix: &str| data.entries_with_prefix(&format!("{prefix}/")).len();
+            let generators = count_prefix(TERRAIN_GENERATOR_PREFIX);
+            let mutation_layers = count_prefix(TERRAIN_MUTATION_LAYER_PREFIX);
+            let mutation_ops = count_prefix(TERRAIN_MUTATION_OP_PREFIX);
+            let chunk_states = count_prefix(TERRAIN_CHUNK_STATE_PREFIX);
+
+            let artifact_prefix = format!("{}/", TERRAIN_CHUNK_ARTIFACT_PREFIX);
+            let mut chunk_artifacts = 0;
+            for entry in data.entries_with_prefix(&artifact_prefix) {
+                chunk_artifacts += 1;
+                if let Some(key) = parse_chunk_artifact_entry(&entry.name) {
+                    chunk_artifact_index.insert(&key.project_key, key.chunk_coords, key.lod);
                 }
-
-                let mut project_keys = projects.into_iter().collect::<Vec<_>>();
-                project_keys.sort();
-                tracing::info!(
-                    resource = "terrain",
-                    path = module_path,
-                    entries = entries.len(),
-                    projects = project_keys.len(),
-                    project_settings,
-                    generators,
-                    mutation_layers,
-                    mutation_ops,
-                    chunk_artifacts,
-                    chunk_states,
-                    project_keys = ?project_keys,
-                    "Loaded terrain database."
-                );
             }
+
+            let mut project_keys = projects.into_iter().collect::<Vec<_>>();
+            project_keys.sort();
+            tracing::info!(
+                resource = "terrain",
+                path = ?module_path,
+                layers = data.len(),
+                entries = data.entries().len(),
+                projects = project_keys.len(),
+                project_settings,
+                generators,
+                mutation_layers,
+                mutation_ops,
+                chunk_artifacts,
+                chunk_states,
+                project_keys = ?project_keys,
+                "Loaded terrain database."
+            );
             None
         };
 
@@ -1153,10 +1136,11 @@ impl TerrainDB {
         &mut self,
         entry: DatabaseEntry<'_>,
     ) -> Result<TerrainProjectSettings, NorenError> {
-        let Some(rdb) = &mut self.data else {
+        if self.data.is_empty() {
             return Err(NorenError::DataFailure());
-        };
-        rdb.fetch::<TerrainProjectSettings>(entry)
+        }
+        self.data
+            .fetch::<TerrainProjectSettings>(entry)
             .map_err(NorenError::from)
     }
 
@@ -1164,20 +1148,22 @@ impl TerrainDB {
         &mut self,
         entry: DatabaseEntry<'_>,
     ) -> Result<TerrainChunkArtifact, NorenError> {
-        let Some(rdb) = &mut self.data else {
+        if self.data.is_empty() {
             return Err(NorenError::DataFailure());
-        };
-        rdb.fetch::<TerrainChunkArtifact>(entry)
+        }
+        self.data
+            .fetch::<TerrainChunkArtifact>(entry)
             .map_err(NorenError::from)
     }
 
     pub fn fetch_chunk(&mut self, entry: DatabaseEntry<'_>) -> Result<TerrainChunk, NorenError> {
-        if let Some(rdb) = &mut self.data {
-            if let Ok(chunk) = rdb.fetch::<TerrainChunk>(entry) {
+        let rdb_err = match fetch_entry::<TerrainChunk>(&self.data, "terrain", entry) {
+            Ok(chunk) => {
                 info!(resource = "terrain", entry = %entry, source = "rdb");
                 return Ok(chunk);
             }
-        }
+            Err(err) => err,
+        };
 
         if let Some(chunk) = &self.fallback_chunk {
             info!(resource = "terrain", entry = %entry, source = "fallback");
@@ -1188,7 +1174,7 @@ impl TerrainDB {
             "Failed to fetch terrain chunk {} from RDB or fallback",
             entry
         );
-        Err(NorenError::DataFailure())
+        Err(rdb_err)
     }
 
     pub fn fetch_chunks_around(
@@ -1203,7 +1189,7 @@ impl TerrainDB {
         let mut chunks = Vec::with_capacity(coords.len());
         let mut entry =
             String::with_capacity(TERRAIN_CHUNK_ARTIFACT_PREFIX.len() + project_key.len() + 32);
-        let use_artifact_index = self.data.is_some();
+        let use_artifact_index = !self.data.is_empty();
 
         for chunk_coords in coords {
             if use_artifact_index
@@ -1238,7 +1224,7 @@ impl TerrainDB {
         let mut chunks = Vec::with_capacity(coords.len());
         let mut entry =
             String::with_capacity(TERRAIN_CHUNK_ARTIFACT_PREFIX.len() + project_key.len() + 32);
-        let use_artifact_index = self.data.is_some();
+        let use_artifact_index = !self.data.is_empty();
 
         for chunk_coords in coords {
             if use_artifact_index
@@ -1313,7 +1299,7 @@ impl TerrainDB {
         let mut artifacts = Vec::with_capacity(chunk_count_x.saturating_mul(chunk_count_z));
         let mut entry =
             String::with_capacity(TERRAIN_CHUNK_ARTIFACT_PREFIX.len() + project_key.len() + 32);
-        let use_artifact_index = self.data.is_some();
+        let use_artifact_index = !self.data.is_empty();
 
         let world_min_x = settings.world_bounds_min[0];
         let world_min_y = settings.world_bounds_min[1];
@@ -1453,20 +1439,31 @@ impl TerrainDB {
         chunk_id_at(settings, coord)
     }
 
+    pub(crate) fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        self.data.raw_entry(entry)
+    }
+
+    pub(crate) fn layers(&self) -> &RDBLayers {
+        &self.data
+    }
+
     pub fn enumerate_entries(&self) -> Vec<String> {
+        if self.data.is_empty() {
+            return self
+                .fallback_chunk
+                .as_ref()
+                .map(|_| vec![DEFAULT_TERRAIN_CHUNK_ENTRY.to_string()])
+                .unwrap_or_default();
+        }
         self.data
-            .as_ref()
-            .map(|rdb| rdb.entries().into_iter().map(|meta| meta.name).collect())
-            .unwrap_or_else(|| {
-                self.fallback_chunk
-                    .as_ref()
-                    .map(|_| vec![DEFAULT_TERRAIN_CHUNK_ENTRY.to_string()])
-                    .unwrap_or_default()
-            })
+            .entries()
+            .into_iter()
+            .map(|meta| meta.name)
+            .collect()
     }
 
     pub fn has_data(&self) -> bool {
-        self.data.is_some() || self.fallback_chunk.is_some()
+        !self.data.is_empty() || self.fallback_chunk.is_some()
     }
 }
 
@@ -2037,7 +2034,8 @@ mod tests {
         };
 
         let bytes = serialize(&legacy).expect("serialize");
-        let upgraded = deserialize_legacy_mutation_op(&bytes);
+        let upgraded =
+            deserialize_legacy_mutation_op("terrain/mutation_op/legacy", &bytes).expect("upgrade");
         match upgraded.params {
             TerrainMutationParams::MaterialPaint { blend_mode, .. } => {
                 assert_eq!(blend_mode, TerrainMaterialBlendMode::Blend);
@@ -2046,6 +2044,16 @@ mod tests {
         }
     }
 
+    #[test]
+    fn legacy_mutation_op_decode_errors_name_the_entry() {
+        let err = deserialize_legacy_mutation_op("terrain/mutation_op/broken", &[1, 2, 3])
+            .expect_err("garbage should not decode");
+        assert!(matches!(
+            err,
+            RdbErr::Decode { ref entry, .. } if entry == "terrain/mutation_op/broken"
+        ));
+    }
+
     #[test]
     fn terrain_project_round_trip_saves_and_loads_data() -> Result<(), NorenError> {
         let temp = tempdir().expect("temp dir");
@@ -2089,7 +2097,7 @@ mod tests {
         file.add(&chunk_state_entry(project_key, &coord_key), &state)?;
         file.save(&path)?;
 
-        let mut view = RDBView::load(&path)?;
+        let view = RDBView::load(&path)?;
         let settings_back: TerrainProjectSettings =
             view.fetch(&project_settings_entry(project_key))?;
         let generator_back: TerrainGeneratorDefinition = view.fetch(&generator_entry(
@@ -0,0 +1,350 @@
+use std::{collections::HashMap, sync::Arc};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    DB, MeshComponents, RDBLayers, RDBRawEntry, RDBType, assemble_host_rig,
+    build_material_components,
+    meta::{HostMaterial, HostMesh, HostModel, HostTexture},
+    parsing::{MaterialLayout, MetaLayout},
+    rdb::{
+        AnimationClip, AudioClip, DatabaseEntry, HostCubemap, HostFont, HostGeometry, HostImage,
+        LoadedFont, PcmStream, ShaderModule, Skeleton, enumerate_with_defaults, fetch_or_default,
+        geometry::fetch_host_geometry, imagery::fetch_host_image, shader::fetch_spirv_module,
+    },
+    resolve_mesh_components,
+    utils::NorenError,
+};
+
+/// A module's layers and built-in defaults.
+struct HostModule<T> {
+    data: RDBLayers,
+    defaults: Arc<HashMap<String, T>>,
+}
+
+impl<T: DeserializeOwned + RDBType + Clone> HostModule<T> {
+    fn new(data: &RDBLayers, defaults: Arc<HashMap<String, T>>) -> Self {
+        Self {
+            data: data.clone(),
+            defaults,
+        }
+    }
+
+    fn fetch(&self, resource: &str, entry: DatabaseEntry<'_>) -> Result<T, NorenError> {
+        fetch_or_default(&self.data, &self.defaults, resource, entry)
+    }
+
+    fn enumerate_entries(&self) -> Vec<String> {
+        enumerate_with_defaults(&self.data, &self.defaults)
+    }
+}
+
+struct ReaderState {
+    geometry: HostModule<HostGeometry>,
+    images: HostModule<HostImage>,
+    cubemaps: HostModule<HostCubemap>,
+    audio: HostModule<AudioClip>,
+    fonts: HostModule<HostFont>,
+    skeletons: HostModule<Skeleton>,
+    animations: HostModule<AnimationClip>,
+    shaders: RDBLayers,
+    terrain: RDBLayers,
+    meta_layout: Option<Arc<MetaLayout>>,
+}
+
+/// Thread-safe handle to the host-side read paths of a [`DB`].
+///
+/// Clones share the mapped files, built-in defaults, and meta layout, so any
+/// number of threads can fetch host assets at once. GPU uploads and caches stay
+/// with the owning [`DB`]. A reader is a snapshot: files remapped by
+/// [`DB::reload_changed`] show up in readers taken afterwards.
+#[derive(Clone)]
+pub struct DBReader {
+    inner: Arc<ReaderState>,
+}
+
+impl DBReader {
+    pub(crate) fn new(db: &DB) -> Self {
+        let imagery = db.imagery();
+        Self {
+            inner: Arc::new(ReaderState {
+                geometry: HostModule::new(db.geometry().layers(), db.geometry().shared_defaults()),
+                images: HostModule::new(imagery.layers(), imagery.shared_defaults()),
+                cubemaps: HostModule::new(imagery.layers(), imagery.shared_cubemap_defaults()),
+                audio: HostModule::new(db.audio().layers(), db.audio().shared_defaults()),
+                fonts: HostModule::new(db.font().layers(), db.font().shared_defaults()),
+                skeletons: HostModule::new(
+                    db.skeletons().layers(),
+                    db.skeletons().shared_defaults(),
+                ),
+                animations: HostModule::new(
+                    db.animations().layers(),
+                    db.animations().shared_defaults(),
+                ),
+                shaders: db.shaders().layers().clone(),
+                terrain: db.terrain().layers().clone(),
+                meta_layout: db.meta_layout.clone(),
+            }),
+        }
+    }
+
+    fn layout(&self) -> Result<&MetaLayout, NorenError> {
+        self.inner
+            .meta_layout
+            .as_deref()
+            .ok_or_else(NorenError::LookupFailure)
+    }
+
+    /// Retrieves host geometry data directly from the backing database file.
+    pub fn fetch_raw_geometry(&self, entry: DatabaseEntry<'_>) -> Result<HostGeometry, NorenError> {
+        let geometry = &self.inner.geometry;
+        fetch_host_geometry(&geometry.data, &geometry.defaults, entry)
+    }
+
+    /// Retrieves host image data from the backing database file.
+    pub fn fetch_raw_image(&self, entry: DatabaseEntry<'_>) -> Result<HostImage, NorenError> {
+        let images = &self.inner.images;
+        fetch_host_image(&images.data, &images.defaults, entry)
+    }
+
+    /// Retrieves host cubemap data from the backing database file.
+    pub fn fetch_raw_cubemap(&self, entry: DatabaseEntry<'_>) -> Result<HostCubemap, NorenError> {
+        self.inner.cubemaps.fetch("cubemap", entry)
+    }
+
+    /// Fetches a fully loaded sound clip by entry name.
+    pub fn fetch_sound_clip(&self, entry: DatabaseEntry<'_>) -> Result<AudioClip, NorenError> {
+        self.inner.audio.fetch("audio", entry)
+    }
+
+    /// Fetches a sound clip and opens an incremental PCM decoder over it.
+    pub fn fetch_pcm_stream(&self, entry: DatabaseEntry<'_>) -> Result<PcmStream, NorenError> {
+        PcmStream::new(self.fetch_sound_clip(entry)?)
+    }
+
+    /// Fetches a font by entry name.
+    pub fn fetch_font(&self, entry: DatabaseEntry<'_>) -> Result<LoadedFont, NorenError> {
+        LoadedFont::from_host(self.inner.fonts.fetch("font", entry)?)
+    }
+
+    /// Fetches a skeleton asset by entry name.
+    pub fn fetch_skeleton(&self, entry: DatabaseEntry<'_>) -> Result<Skeleton, NorenError> {
+        self.inner.skeletons.fetch("skeleton", entry)
+    }
+
+    /// Fetches an animation clip by entry name.
+    pub fn fetch_animation(&self, entry: DatabaseEntry<'_>) -> Result<AnimationClip, NorenError> {
+        self.inner.animations.fetch("animation", entry)
+    }
+
+    /// Fetches a shader module by entry name, ensuring it contains SPIR-V data.
+    pub fn fetch_shader_module(
+        &self,
+        entry: DatabaseEntry<'_>,
+    ) -> Result<ShaderModule, NorenError> {
+        fetch_spirv_module(&self.inner.shaders, entry)
+    }
+
+    /// Detaches the stored bytes of `entry` from whichever module holds it.
+    ///
+    /// Only the primary payload is returned. Entries written by
+    /// [`add_mapped_geometry`](crate::rdb::add_mapped_geometry) or
+    /// [`add_mapped_image`](crate::rdb::add_mapped_image) keep their vertices,
+    /// indices and pixels in separate slices, so decoding them yields empty
+    /// data; read those through [`DBReader::fetch_raw_geometry`] or
+    /// [`DBReader::fetch_raw_image`] instead.
+    pub fn raw_entry(&self, entry: DatabaseEntry<'_>) -> Option<RDBRawEntry> {
+        let inner = &self.inner;
+        [
+            &inner.geometry.data,
+            &inner.images.data,
+            &inner.audio.data,
+            &inner.fonts.data,
+            &inner.skeletons.data,
+            &inner.animations.data,
+            &inner.shaders,
+            &inner.terrain,
+        ]
+        .into_iter()
+        .find_map(|layers| layers.raw_entry(entry))
+    }
+
+    /// Returns the merged meta layout, if the database declares one.
+    pub fn meta_layout(&self) -> Option<&MetaLayout> {
+        self.inner.meta_layout.as_deref()
+    }
+
+    /// Fetches a material layout without loading textures or images.
+    pub fn fetch_material_layout(&self, entry: &str) -> Result<MaterialLayout, NorenError> {
+        self.layout()?
+            .materials
+            .get(entry)
+            .cloned()
+            .ok_or_else(NorenError::LookupFailure)
+    }
+
+    /// Builds a CPU-side material with host images.
+    pub fn fetch_host_material(&self, entry: &str) -> Result<HostMaterial, NorenError> {
+        let (name, textures, material) =
+            build_material_components(self.layout()?, entry, &mut |name, image_entry| {
+                self.host_texture(name, image_entry)
+            })?
+            .ok_or_else(NorenError::LookupFailure)?;
+
+        Ok(HostMaterial {
+            name,
+            textures,
+            material,
+        })
+    }
+
+    /// Builds a CPU-side model composed of host geometry, textures, and materials.
+    pub fn fetch_model(&self, entry: DatabaseEntry<'_>) -> Result<HostModel, NorenError> {
+        let layout = self.layout()?;
+        let model = layout
+            .models
+            .get(entry)
+            .ok_or_else(NorenError::LookupFailure)?;
+
+        let mut meshes = Vec::new();
+        for mesh_key in &model.meshes {
+            if let Some(mesh) = self.host_mesh(layout, mesh_key, None)? {
+                meshes.push(mesh);
+            }
+        }
+
+        let rig = assemble_host_rig(
+            entry,
+            &self.inner.skeletons.enumerate_entries(),
+            &self.inner.animations.enumerate_entries(),
+            |skeleton_entry| self.fetch_skeleton(skeleton_entry),
+            |animation_entry| self.fetch_animation(animation_entry),
+        )?;
+
+        Ok(HostModel {
+            name: model.name.clone().unwrap_or_else(|| entry.to_string()),
+            meshes,
+            rig,
+        })
+    }
+
+    /// Builds a CPU-side mesh using the provided material entry instead of the layout default.
+    pub fn fetch_mesh_with_material(
+        &self,
+        mesh_entry: DatabaseEntry<'_>,
+        material_entry: &str,
+    ) -> Result<HostMesh, NorenError> {
+        self.host_mesh(self.layout()?, mesh_entry, Some(material_entry))?
+            .ok_or_else(NorenError::LookupFailure)
+    }
+
+    fn host_texture(
+        &self,
+        name: String,
+        image_entry: DatabaseEntry<'_>,
+    ) -> Result<HostTexture, NorenError> {
+        Ok(HostTexture {
+            name,
+            image: self.fetch_raw_image(image_entry)?,
+        })
+    }
+
+    fn host_mesh(
+        &self,
+        layout: &MetaLayout,
+        mesh_key: &str,
+        material_override: Option<&str>,
+    ) -> Result<Option<HostMesh>, NorenError> {
+        let Some(MeshComponents {
+            name,
+            geometry,
+            textures,
+            material,
+        }) = resolve_mesh_components(
+            layout,
+            mesh_key,
+            material_override,
+            &mut |entry| self.fetch_raw_geometry(entry),
+            &mut |name, image_entry| self.host_texture(name, image_entry),
+        )?
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(HostMesh {
+            name,
+            vertex_count: geometry.vertex_count,
+            index_count: geometry.index_count,
+            geometry,
+            textures,
+            material: material.map(|(_, (name, textures, material))| HostMaterial {
+                name,
+                textures,
+                material,
+            }),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DBReader;
+    use crate::{
+        DB, DBInfo, NorenError, RDBFile, defaults,
+        rdb::{AudioClip, AudioFormat},
+    };
+    use tempfile::tempdir;
+
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    #[test]
+    fn readers_fetch_host_assets_across_threads() -> Result<(), NorenError> {
+        assert_send_sync::<DBReader>();
+
+        let tmp = tempdir().unwrap();
+        let mut audio = RDBFile::new();
+        let clip = AudioClip::new("audio/custom".into(), AudioFormat::default(), vec![4, 5]);
+        audio.add("audio/custom", &clip)?;
+        audio.save(tmp.path().join("audio.rdb"))?;
+
+        let db = DB::new(&DBInfo {
+            base_dir: tmp.path().to_str().unwrap(),
+            layout_file: None,
+            pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[],
+        })?;
+        let reader = db.reader();
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let reader = reader.clone();
+                std::thread::spawn(move || {
+                    let model = reader.fetch_model("model/fox")?;
+                    let clip = reader.fetch_sound_clip("audio/custom")?;
+                    Ok::<_, NorenError>((model, clip))
+                })
+            })
+            .collect();
+        for worker in workers {
+            let (model, clip) = worker.join().expect("reader thread")?;
+            assert_eq!(model.meshes.len(), 1);
+            assert!(model.meshes[0].material.is_some());
+            assert!(model.rig.is_some());
+            assert_eq!(clip.data, vec![4, 5]);
+        }
+
+        assert!(reader.raw_entry("audio/custom").is_some());
+        assert!(
+            reader
+                .fetch_material_layout(defaults::DEFAULT_MATERIAL_ENTRY)
+                .is_ok()
+        );
+        assert!(matches!(
+            reader.fetch_model("model/missing"),
+            Err(NorenError::LookupFailure())
+        ));
+
+        Ok(())
+    }
+}
@@ -382,7 +382,7 @@ fn collect_mutation_ops(
             }
             let mut op = if version < MUTATION_OP_BLEND_MODE_VERSION {
                 let bytes = rdb.entry_bytes(&entry.name)?;
-                deserialize_legacy_mutation_op(bytes)
+                deserialize_legacy_mutation_op(&entry.name, &bytes)?
             } else {
                 rdb.fetch::<TerrainMutationOp>(&entry.name)?
             };
@@ -1547,7 +1547,8 @@ mod tests {
         };
 
         let bytes = serialize(&legacy_op).expect("serialize");
-        let upgraded = deserialize_legacy_mutation_op(&bytes);
+        let upgraded =
+            deserialize_legacy_mutation_op("terrain/mutation_op/legacy", &bytes).expect("upgrade");
         let TerrainMutationParams::MaterialPaint { blend_mode, .. } = upgraded.params else {
             panic!("expected material paint params");
         };
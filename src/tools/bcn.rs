@@ -0,0 +1,449 @@
+use crate::rdb::BlockFormat;
+
+/// Interpolation weights of BC7's 4-bit indices, out of 64.
+const BC7_WEIGHTS: [u32; 16] = [0, 4, 9, 13, 17, 21, 26, 30, 34, 38, 43, 47, 51, 55, 60, 64];
+
+/// Compresses RGBA8 `pixels` into 4x4 blocks, row by row.
+///
+/// Edge blocks of sizes that are not a multiple of 4 repeat the last row and
+/// column. BC4 reads red and BC5 reads red and green.
+pub fn encode_blocks(format: BlockFormat, pixels: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let mut out = Vec::with_capacity(format.level_bytes(width, height));
+    for by in 0..height.div_ceil(4).max(1) {
+        for bx in 0..width.div_ceil(4).max(1) {
+            let texels = read_block(pixels, width, height, bx * 4, by * 4);
+            match format {
+                BlockFormat::Bc1 => out.extend(encode_bc1(&texels, true)),
+                BlockFormat::Bc3 => {
+                    out.extend(encode_bc4(&texels.map(|texel| texel[3])));
+                    out.extend(encode_bc1(&texels, false));
+                }
+                BlockFormat::Bc4 => out.extend(encode_bc4(&texels.map(|texel| texel[0]))),
+                BlockFormat::Bc5 => {
+                    out.extend(encode_bc4(&texels.map(|texel| texel[0])));
+                    out.extend(encode_bc4(&texels.map(|texel| texel[1])));
+                }
+                BlockFormat::Bc7 => out.extend(encode_bc7(&texels)),
+            }
+        }
+    }
+    out
+}
+
+/// Compresses every level of an RGBA8 mip chain packed at `offsets`.
+///
+/// Returns the compressed levels packed back to back with their new offsets;
+/// an empty `offsets` means `data` holds only level 0.
+pub fn encode_mip_chain(
+    format: BlockFormat,
+    data: &[u8],
+    offsets: &[u64],
+    width: u32,
+    height: u32,
+) -> (Vec<u8>, Vec<u64>) {
+    if offsets.is_empty() {
+        return (encode_blocks(format, data, width, height), Vec::new());
+    }
+
+    let mut out = Vec::new();
+    let mut out_offsets = Vec::with_capacity(offsets.len());
+    for (level, start) in offsets.iter().enumerate() {
+        let end = offsets
+            .get(level + 1)
+            .map_or(data.len(), |end| *end as usize);
+        let level_width = (width >> level).max(1);
+        let level_height = (height >> level).max(1);
+        out_offsets.push(out.len() as u64);
+        out.extend(encode_blocks(
+            format,
+            &data[*start as usize..end],
+            level_width,
+            level_height,
+        ));
+    }
+    (out, out_offsets)
+}
+
+fn read_block(pixels: &[u8], width: u32, height: u32, x: u32, y: u32) -> [[u8; 4]; 16] {
+    std::array::from_fn(|idx| {
+        let px = (x + idx as u32 % 4).min(width.saturating_sub(1));
+        let py = (y + idx as u32 / 4).min(height.saturating_sub(1));
+        let start = ((py * width + px) * 4) as usize;
+        pixels
+            .get(start..start + 4)
+            .map_or([0; 4], |texel| [texel[0], texel[1], texel[2], texel[3]])
+    })
+}
+
+/// Encodes one BC1 block; with `allow_alpha`, texels below half alpha become
+/// transparent through the 3-color mode.
+fn encode_bc1(texels: &[[u8; 4]; 16], allow_alpha: bool) -> [u8; 8] {
+    let transparent = |texel: &[u8; 4]| allow_alpha && texel[3] < 128;
+    let colors: Vec<[f32; 3]> = texels
+        .iter()
+        .filter(|texel| !transparent(texel))
+        .map(|texel| [0, 1, 2].map(|channel| f32::from(texel[channel])))
+        .collect();
+    let punch_through = colors.len() < texels.len();
+
+    let (low, high) = if colors.is_empty() {
+        ([0.0; 3], [0.0; 3])
+    } else {
+        fit_endpoints(&colors)
+    };
+    let (mut color0, mut color1) = (pack_565(high), pack_565(low));
+    // The endpoint order selects the mode: color0 > color1 is 4-color.
+    if (color0 < color1) != punch_through {
+        std::mem::swap(&mut color0, &mut color1);
+    }
+
+    let (end0, end1) = (unpack_565(color0), unpack_565(color1));
+    let palette: Vec<[u32; 3]> = if punch_through {
+        vec![
+            end0,
+            end1,
+            std::array::from_fn(|channel| (end0[channel] + end1[channel]) / 2),
+        ]
+    } else {
+        vec![
+            end0,
+            end1,
+            std::array::from_fn(|channel| (2 * end0[channel] + end1[channel]) / 3),
+            std::array::from_fn(|channel| (end0[channel] + 2 * end1[channel]) / 3),
+        ]
+    };
+
+    let mut indices = 0u32;
+    if color0 != color1 || punch_through {
+        for (idx, texel) in texels.iter().enumerate() {
+            let index = if transparent(texel) {
+                3
+            } else {
+                nearest(&palette, |entry| {
+                    (0..3)
+                        .map(|channel| squared(entry[channel], u32::from(texel[channel])))
+                        .sum()
+                })
+            };
+            indices |= index << (idx * 2);
+        }
+    }
+
+    let mut block = [0u8; 8];
+    block[..2].copy_from_slice(&color0.to_le_bytes());
+    block[2..4].copy_from_slice(&color1.to_le_bytes());
+    block[4..].copy_from_slice(&indices.to_le_bytes());
+    block
+}
+
+/// Encodes one single-channel BC4 block in the 8-value mode.
+fn encode_bc4(values: &[u8; 16]) -> [u8; 8] {
+    let high = values.iter().copied().max().unwrap_or(0);
+    let low = values.iter().copied().min().unwrap_or(0);
+
+    let mut indices = 0u64;
+    if high != low {
+        let (high, low) = (u32::from(high), u32::from(low));
+        let mut palette = vec![high, low];
+        palette.extend((1..7).map(|step| ((7 - step) * high + step * low) / 7));
+        for (idx, value) in values.iter().enumerate() {
+            let index = nearest(&palette, |entry| squared(*entry, u32::from(*value)));
+            indices |= u64::from(index) << (idx * 3);
+        }
+    }
+
+    let mut block = [0u8; 8];
+    block[0] = high;
+    block[1] = low;
+    block[2..].copy_from_slice(&indices.to_le_bytes()[..6]);
+    block
+}
+
+/// Encodes one BC7 block in mode 6: a single RGBA subset with 7-bit
+/// endpoints, per-endpoint p-bits and 4-bit indices.
+fn encode_bc7(texels: &[[u8; 4]; 16]) -> [u8; 16] {
+    let colors: Vec<[f32; 4]> = texels.iter().map(|texel| texel.map(f32::from)).collect();
+    let (low, high) = fit_endpoints(&colors);
+    let (mut end0, mut p0) = quantize_bc7_endpoint(low);
+    let (mut end1, mut p1) = quantize_bc7_endpoint(high);
+
+    let expand = |end: [u32; 4], p: u32| end.map(|channel| (channel << 1) | p);
+    let (color0, color1) = (expand(end0, p0), expand(end1, p1));
+    let palette: Vec<[u32; 4]> = BC7_WEIGHTS
+        .iter()
+        .map(|weight| {
+            std::array::from_fn(|channel| {
+                ((64 - weight) * color0[channel] + weight * color1[channel] + 32) >> 6
+            })
+        })
+        .collect();
+    let mut indices: [u32; 16] = std::array::from_fn(|idx| {
+        nearest(&palette, |entry| {
+            (0..4)
+                .map(|channel| squared(entry[channel], u32::from(texels[idx][channel])))
+                .sum()
+        })
+    });
+
+    // The first index is stored with its top bit implied to be zero.
+    if indices[0] >= 8 {
+        std::mem::swap(&mut end0, &mut end1);
+        std::mem::swap(&mut p0, &mut p1);
+        indices = indices.map(|index| 15 - index);
+    }
+
+    let mut bits = BitWriter::default();
+    bits.put(1 << 6, 7);
+    for (start, end) in end0.iter().zip(&end1) {
+        bits.put(*start, 7);
+        bits.put(*end, 7);
+    }
+    bits.put(p0, 1);
+    bits.put(p1, 1);
+    for (idx, index) in indices.iter().enumerate() {
+        bits.put(*index, if idx == 0 { 3 } else { 4 });
+    }
+    bits.value.to_le_bytes()
+}
+
+/// Picks the 7-bit endpoint and shared p-bit closest to `color`.
+fn quantize_bc7_endpoint(color: [f32; 4]) -> ([u32; 4], u32) {
+    (0..2)
+        .map(|p| {
+            let end =
+                color.map(|channel| ((channel - p as f32) / 2.0).round().clamp(0.0, 127.0) as u32);
+            let error: f32 = (0..4)
+                .map(|channel| {
+                    let value = ((end[channel] << 1) | p) as f32;
+                    (value - color[channel]).powi(2)
+                })
+                .sum();
+            (end, p, error)
+        })
+        .min_by(|a, b| a.2.total_cmp(&b.2))
+        .map(|(end, p, _)| (end, p))
+        .unwrap_or(([0; 4], 0))
+}
+
+#[derive(Default)]
+struct BitWriter {
+    value: u128,
+    offset: u32,
+}
+
+impl BitWriter {
+    fn put(&mut self, value: u32, bits: u32) {
+        self.value |= u128::from(value) << self.offset;
+        self.offset += bits;
+    }
+}
+
+/// Fits a line through `colors` along their principal axis and returns its
+/// extremes, clamped to the byte range.
+fn fit_endpoints<const N: usize>(colors: &[[f32; N]]) -> ([f32; N], [f32; N]) {
+    let count = colors.len().max(1) as f32;
+    let mean: [f32; N] = std::array::from_fn(|channel| {
+        colors.iter().map(|color| color[channel]).sum::<f32>() / count
+    });
+
+    let mut covariance = [[0.0f32; N]; N];
+    for color in colors {
+        for (row, cells) in covariance.iter_mut().enumerate() {
+            for (column, cell) in cells.iter_mut().enumerate() {
+                *cell += (color[row] - mean[row]) * (color[column] - mean[column]);
+            }
+        }
+    }
+
+    // Power iteration towards the dominant eigenvector, starting from the
+    // covariance row of the widest channel so anti-correlated channels do not
+    // cancel out.
+    let widest = (0..N)
+        .max_by(|a, b| covariance[*a][*a].total_cmp(&covariance[*b][*b]))
+        .unwrap_or(0);
+    let mut axis = covariance[widest];
+    for _ in 0..8 {
+        let next: [f32; N] = std::array::from_fn(|row| {
+            (0..N)
+                .map(|column| covariance[row][column] * axis[column])
+                .sum()
+        });
+        let length = next.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if length < 1e-6 {
+            return (mean, mean);
+        }
+        axis = next.map(|value| value / length);
+    }
+
+    let project = |color: &[f32; N]| -> f32 {
+        (0..N)
+            .map(|channel| (color[channel] - mean[channel]) * axis[channel])
+            .sum()
+    };
+    let (min, max) = colors
+        .iter()
+        .map(project)
+        .fold((f32::MAX, f32::MIN), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    let point = |along: f32| -> [f32; N] {
+        std::array::from_fn(|channel| (mean[channel] + axis[channel] * along).clamp(0.0, 255.0))
+    };
+    (point(min), point(max))
+}
+
+fn pack_565(color: [f32; 3]) -> u16 {
+    let r = (color[0] * 31.0 / 255.0).round() as u16;
+    let g = (color[1] * 63.0 / 255.0).round() as u16;
+    let b = (color[2] * 31.0 / 255.0).round() as u16;
+    (r << 11) | (g << 5) | b
+}
+
+fn unpack_565(color: u16) -> [u32; 3] {
+    let r = u32::from(color >> 11) & 31;
+    let g = u32::from(color >> 5) & 63;
+    let b = u32::from(color) & 31;
+    [
+        (r << 3) | (r >> 2),
+        (g << 2) | (g >> 4),
+        (b << 3) | (b >> 2),
+    ]
+}
+
+fn squared(a: u32, b: u32) -> u32 {
+    a.abs_diff(b).pow(2)
+}
+
+/// Returns the index of the palette entry with the lowest `error`.
+fn nearest<T>(palette: &[T], error: impl Fn(&T) -> u32) -> u32 {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, entry)| error(entry))
+        .map_or(0, |(index, _)| index as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BC7_WEIGHTS, encode_blocks, encode_mip_chain};
+    use crate::rdb::BlockFormat;
+
+    fn decode_bc4(block: &[u8]) -> [u32; 16] {
+        let (high, low) = (u32::from(block[0]), u32::from(block[1]));
+        let mut palette = vec![high, low];
+        palette.extend((1..7).map(|step| ((7 - step) * high + step * low) / 7));
+        let mut bits = [0u8; 8];
+        bits[..6].copy_from_slice(&block[2..8]);
+        let indices = u64::from_le_bytes(bits);
+        std::array::from_fn(|idx| palette[((indices >> (idx * 3)) & 7) as usize])
+    }
+
+    fn decode_bc7_mode6(block: &[u8]) -> [[u32; 4]; 16] {
+        let bits = u128::from_le_bytes(block.try_into().unwrap());
+        let mut offset = 0;
+        let mut take = |count: u32| {
+            let value = ((bits >> offset) & ((1u128 << count) - 1)) as u32;
+            offset += count;
+            value
+        };
+        assert_eq!(take(7), 1 << 6);
+        // Endpoints alternate per channel: R0 R1 G0 G1 B0 B1 A0 A1.
+        let ends: [u32; 8] = std::array::from_fn(|_| take(7));
+        let (p0, p1) = (take(1), take(1));
+        let color0: [u32; 4] = std::array::from_fn(|channel| (ends[channel * 2] << 1) | p0);
+        let color1: [u32; 4] = std::array::from_fn(|channel| (ends[channel * 2 + 1] << 1) | p1);
+        std::array::from_fn(|idx| {
+            let weight = BC7_WEIGHTS[take(if idx == 0 { 3 } else { 4 }) as usize];
+            std::array::from_fn(|channel| {
+                ((64 - weight) * color0[channel] + weight * color1[channel] + 32) >> 6
+            })
+        })
+    }
+
+    /// Colors along one line, which a single BC7 subset can follow exactly.
+    fn ramp() -> Vec<u8> {
+        (0..16u8)
+            .flat_map(|idx| [idx * 16, 255 - idx * 16, 128, 255])
+            .collect()
+    }
+
+    fn gradient(width: u32, height: u32) -> Vec<u8> {
+        (0..width * height)
+            .flat_map(|idx| {
+                let (x, y) = (idx % width, idx / width);
+                [(x * 16) as u8, (y * 16) as u8, 200, 255]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sizes_levels_by_block_format() {
+        assert_eq!(BlockFormat::Bc1.level_bytes(6, 3), 16);
+        assert_eq!(BlockFormat::Bc7.level_bytes(1, 1), 16);
+
+        let pixels = gradient(8, 8);
+        for format in [
+            BlockFormat::Bc1,
+            BlockFormat::Bc3,
+            BlockFormat::Bc4,
+            BlockFormat::Bc5,
+            BlockFormat::Bc7,
+        ] {
+            let blocks = encode_blocks(format, &pixels, 8, 8);
+            assert_eq!(blocks.len(), format.level_bytes(8, 8));
+        }
+
+        let chain: Vec<u8> = [
+            gradient(8, 4),
+            gradient(4, 2),
+            gradient(2, 1),
+            gradient(1, 1),
+        ]
+        .concat();
+        let (data, offsets) = encode_mip_chain(BlockFormat::Bc1, &chain, &[0, 128, 160, 168], 8, 4);
+        assert_eq!(offsets, vec![0, 16, 24, 32]);
+        assert_eq!(data.len(), 40);
+    }
+
+    #[test]
+    fn bc1_keeps_flat_colors_and_punch_through_alpha() {
+        let mut pixels = [255u8, 0, 0, 255].repeat(16);
+        let block = encode_blocks(BlockFormat::Bc1, &pixels, 4, 4);
+        assert_eq!(u16::from_le_bytes([block[0], block[1]]), 0xF800);
+
+        pixels[3] = 0;
+        let block = encode_blocks(BlockFormat::Bc1, &pixels, 4, 4);
+        let (color0, color1) = (
+            u16::from_le_bytes([block[0], block[1]]),
+            u16::from_le_bytes([block[2], block[3]]),
+        );
+        assert!(color0 <= color1);
+        let indices = u32::from_le_bytes(block[4..8].try_into().unwrap());
+        assert_eq!(indices & 3, 3);
+        assert!((1..16).all(|idx| (indices >> (idx * 2)) & 3 != 3));
+    }
+
+    #[test]
+    fn bc4_and_bc7_reconstruct_gradients_closely() {
+        let pixels = gradient(4, 4);
+        let bc5 = encode_blocks(BlockFormat::Bc5, &pixels, 4, 4);
+        let red = decode_bc4(&bc5[..8]);
+        let green = decode_bc4(&bc5[8..]);
+        for idx in 0..16 {
+            assert!(red[idx].abs_diff(u32::from(pixels[idx * 4])) <= 4);
+            assert!(green[idx].abs_diff(u32::from(pixels[idx * 4 + 1])) <= 4);
+        }
+
+        let pixels = ramp();
+        let bc7 = encode_blocks(BlockFormat::Bc7, &pixels, 4, 4);
+        let decoded = decode_bc7_mode6(&bc7);
+        let error: u32 = (0..16)
+            .flat_map(|idx| (0..4).map(move |channel| (idx, channel)))
+            .map(|(idx, channel)| {
+                decoded[idx][channel].abs_diff(u32::from(pixels[idx * 4 + channel]))
+            })
+            .max()
+            .unwrap();
+        assert!(error <= 10, "max error {error}");
+    }
+}
@@ -1,5 +1,5 @@
 use std::{
-    collections::{BTreeMap, HashMap, HashSet},
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fs::{self, File},
     io::{BufReader, Write},
     path::{Path, PathBuf},
@@ -7,20 +7,22 @@ use std::{
 };
 
 use crate::{
-    DatabaseLayoutFile, NorenError, RDBEntryMeta, RDBFile, RdbErr,
+    BUNDLE_LAYOUT_MEMBER, DBBundleWriter, DatabaseLayoutFile, NorenError, RDBCompression,
+    RDBEntryMeta, RDBFile, RDBView, RdbErr,
     defaults::{
         DEFAULT_IMAGE_ENTRY, default_fonts, default_images, default_primitives, default_sounds,
         ensure_default_assets,
     },
     parsing::{
-        FontBounds, FontGlyph, FontMetrics, MaterialLayoutFile, MeshLayout, MeshLayoutFile,
-        ModelLayout, ModelLayoutFile, MsdfFontLayout, MsdfFontLayoutFile, SdfFontLayout,
-        SdfFontLayoutFile, TextureAtlasLayoutFile, TextureLayout, TextureLayoutFile,
+        FontBounds, FontGlyph, FontKerning, FontMetrics, MaterialLayoutFile, MeshLayout,
+        MeshLayoutFile, ModelLayout, ModelLayoutFile, MsdfFontLayout, MsdfFontLayoutFile,
+        SdfFontLayout, SdfFontLayoutFile, TextureAtlasLayoutFile, TextureLayout, TextureLayoutFile,
     },
     rdb::{
         AnimationChannel, AnimationClip, AnimationInterpolation, AnimationOutput, AnimationSampler,
-        AnimationTargetPath, AudioClip, AudioFormat, GeometryLayer, HostCubemap, HostFont,
-        HostGeometry, HostImage, ImageInfo, Joint, ShaderModule, Skeleton, index_vertices,
+        AnimationTargetPath, AudioClip, AudioCue, AudioFormat, AudioLoop, BlockFormat,
+        GeometryLayer, HostCubemap, HostFont, HostGeometry, HostImage, ImageInfo, Joint, PcmStream,
+        ShaderModule, Skeleton, add_mapped_geometry, add_mapped_image, index_vertices,
         primitives::Vertex,
         terrain::{
             TERRAIN_MUTATION_LAYER_PREFIX, TERRAIN_MUTATION_OP_PREFIX, TerrainChunk,
@@ -31,15 +33,26 @@ use crate::{
         },
     },
     terrain::build_heightmap_chunk_artifact,
+    tools::{
+        bcn::encode_mip_chain,
+        distance_field::GlyphShape,
+        mipmap::{MipFilter, MipmapSettings, full_mip_count, generate_mip_chain},
+        texture_container::{ContainerKind, read_container},
+    },
     validate_database_layout,
 };
 use bento::{
     BentoError, Compiler as BentoCompiler, OptimizationLevel, Request as BentoRequest, ShaderLang,
 };
 use fontdue::{Font, FontSettings};
+use glam::Vec2;
 use gltf::{animation::util::ReadOutputs, image::Format};
 use image::{DynamicImage, GrayImage, ImageBuffer, Luma, Rgb, RgbImage, Rgba, RgbaImage};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+use ttf_parser::{
+    GlyphId, Tag,
+    gpos::{PairAdjustment, PositioningSubtable},
+};
 
 #[derive(Clone, Default)]
 pub struct Logger {
@@ -53,11 +66,50 @@ struct FontAtlasOutput {
     sdf_layouts: HashMap<String, SdfFontLayout>,
 }
 
-#[derive(Clone)]
-struct RasterizedGlyph {
+struct RenderedGlyph {
     unicode: u32,
-    metrics: fontdue::Metrics,
-    bitmap: Vec<u8>,
+    advance: f32,
+    /// Bottom-left corner of the glyph cell relative to the pen position, in pixels.
+    origin: Vec2,
+    width: u32,
+    height: u32,
+    /// RGBA8 multi-channel distance field with the true distance in alpha.
+    pixels: Vec<u8>,
+}
+
+struct FontAtlas {
+    dim: u32,
+    /// RGBA8 multi-channel distance field with the true distance in alpha.
+    pixels: Vec<u8>,
+    glyphs: Vec<FontGlyph>,
+    kerning: Vec<FontKerning>,
+    metrics: FontMetrics,
+}
+
+impl FontAtlas {
+    fn sdf_image(&self, entry: &str) -> HostImage {
+        let pixels = self
+            .pixels
+            .chunks_exact(4)
+            .flat_map(|texel| [texel[3]; 4])
+            .collect();
+        self.image(entry, pixels)
+    }
+
+    fn msdf_image(&self, entry: &str) -> HostImage {
+        self.image(entry, self.pixels.clone())
+    }
+
+    fn image(&self, entry: &str, pixels: Vec<u8>) -> HostImage {
+        let info = ImageInfo {
+            name: entry.to_string(),
+            dim: [self.dim, self.dim, 1],
+            layers: 1,
+            format: dashi::Format::RGBA8,
+            mip_levels: 1,
+        };
+        HostImage::new(info, pixels)
+    }
 }
 
 impl Logger {
@@ -183,6 +235,10 @@ pub fn run_cli() -> Result<(), ()> {
             build_from_path(&spec, BuildOptions { append, ..options }, &logger)
         }
         Command::Validate(args) => run_validation(&args, &logger),
+        Command::Verify(args) => run_verify(&args, &logger),
+        Command::Compact(args) => run_compact(&args, &logger),
+        Command::Bundle(args) => run_bundle(&args, &logger),
+        Command::Diff(args) => run_diff(&args, &logger),
         Command::AppendGeometry(args) => append_geometry(&args, &logger, cli.write_binaries),
         Command::AppendSkeleton(args) => append_skeleton(&args, &logger, cli.write_binaries),
         Command::AppendAnimation(args) => append_animation(&args, &logger, cli.write_binaries),
@@ -250,6 +306,10 @@ fn parse_command(program: &str, args: impl Iterator<Item = String>) -> Result<Cl
                         }
                     }
                     "validate" => parse_validate_command(args)?,
+                    "verify" => parse_verify_command(args)?,
+                    "compact" => parse_compact_command(args)?,
+                    "bundle" => parse_bundle_command(args)?,
+                    "diff" => parse_diff_command(args)?,
                     "append" => parse_append_command(args)?,
                     "terrain" => parse_terrain_command(args)?,
                     path => Command::Build {
@@ -293,6 +353,86 @@ fn parse_validate_command(mut args: impl Iterator<Item = String>) -> Result<Comm
     Ok(Command::Validate(ValidateArgs { spec, base }))
 }
 
+fn parse_verify_command(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let files: Vec<PathBuf> = args.map(PathBuf::from).collect();
+    if files.is_empty() {
+        return Err("verify requires at least one RDB file".into());
+    }
+
+    Ok(Command::Verify(VerifyArgs { files }))
+}
+
+fn parse_compact_command(args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let files: Vec<PathBuf> = args.map(PathBuf::from).collect();
+    if files.is_empty() {
+        return Err("compact requires at least one RDB file".into());
+    }
+
+    Ok(Command::Compact(CompactArgs { files }))
+}
+
+fn parse_bundle_command(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut base_dir: Option<PathBuf> = None;
+    let mut layout: Option<PathBuf> = None;
+    let mut out: Option<PathBuf> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--layout" => {
+                layout = Some(PathBuf::from(next_value("--layout", &mut args)?));
+            }
+            "--out" => {
+                out = Some(PathBuf::from(next_value("--out", &mut args)?));
+            }
+            other => {
+                if base_dir.is_none() {
+                    base_dir = Some(PathBuf::from(other));
+                } else {
+                    return Err(format!("unexpected argument to bundle: {other}"));
+                }
+            }
+        }
+    }
+
+    let base_dir = base_dir.ok_or_else(|| "bundle requires a database directory".to_string())?;
+    let out = out.ok_or_else(|| "bundle requires --out <file.nrb>".to_string())?;
+    Ok(Command::Bundle(BundleArgs {
+        base_dir,
+        layout,
+        out,
+    }))
+}
+
+fn parse_diff_command(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
+    let mut inputs: Vec<PathBuf> = Vec::new();
+    let mut layout: Option<PathBuf> = None;
+    let mut out: Option<PathBuf> = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--layout" => {
+                layout = Some(PathBuf::from(next_value("--layout", &mut args)?));
+            }
+            "--out" => {
+                out = Some(PathBuf::from(next_value("--out", &mut args)?));
+            }
+            other if inputs.len() < 2 => inputs.push(PathBuf::from(other)),
+            other => return Err(format!("unexpected argument to diff: {other}")),
+        }
+    }
+
+    let [old, new]: [PathBuf; 2] = inputs
+        .try_into()
+        .map_err(|_| "diff requires an old and a new database".to_string())?;
+    let out = out.ok_or_else(|| "diff requires --out <patch>".to_string())?;
+    Ok(Command::Diff(DiffArgs {
+        old,
+        new,
+        layout,
+        out,
+    }))
+}
+
 fn parse_append_command(mut args: impl Iterator<Item = String>) -> Result<Command, String> {
     let Some(kind) = args.next() else {
         return Err(
@@ -461,6 +601,7 @@ fn parse_imagery_append(mut args: impl Iterator<Item = String>) -> Result<ImageA
     let mut layers = None;
     let mut format = None;
     let mut mip_levels = None;
+    let mut mipmap = MipmapSettings::default();
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -494,6 +635,28 @@ fn parse_imagery_append(mut args: impl Iterator<Item = String>) -> Result<ImageA
                     .map_err(|_| format!("--mip-levels expects an integer, received '{value}'"))?;
                 mip_levels = Some(parsed);
             }
+            "--mip-filter" => {
+                let value = next_value("--mip-filter", &mut args)?;
+                mipmap.mip_filter = parse_mip_filter(&value)
+                    .ok_or_else(|| format!("unknown mip filter '{value}'"))?;
+            }
+            "--srgb" => {
+                let value = next_value("--srgb", &mut args)?;
+                let parsed = value
+                    .parse::<bool>()
+                    .map_err(|_| format!("--srgb expects true or false, received '{value}'"))?;
+                mipmap.srgb = Some(parsed);
+            }
+            "--alpha-cutoff" => {
+                let value = next_value("--alpha-cutoff", &mut args)?;
+                let parsed = value
+                    .parse::<f32>()
+                    .map_err(|_| format!("--alpha-cutoff expects a number, received '{value}'"))?;
+                mipmap.alpha_cutoff = Some(parsed);
+            }
+            "--normal-map" => {
+                mipmap.normal_map = true;
+            }
             other => return Err(format!("unexpected argument to append imagery: {other}")),
         }
     }
@@ -506,6 +669,7 @@ fn parse_imagery_append(mut args: impl Iterator<Item = String>) -> Result<ImageA
             layers: layers.unwrap_or_else(default_layers),
             format: format.unwrap_or_else(default_format),
             mip_levels: mip_levels.unwrap_or_else(default_mip_levels),
+            mipmap,
         },
     })
 }
@@ -589,6 +753,11 @@ fn parse_audio_append(mut args: impl Iterator<Item = String>) -> Result<AudioApp
     let mut entry = None;
     let mut file = None;
     let mut format = None;
+    let mut loop_start = None;
+    let mut loop_end = None;
+    let mut volume = None;
+    let mut category = None;
+    let mut cues = Vec::new();
 
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -599,6 +768,42 @@ fn parse_audio_append(mut args: impl Iterator<Item = String>) -> Result<AudioApp
             "--entry" => {
                 entry = Some(next_value("--entry", &mut args)?);
             }
+            "--loop-start" => {
+                let value = next_value("--loop-start", &mut args)?;
+                loop_start = Some(value.parse::<u64>().map_err(|_| {
+                    format!("--loop-start expects a frame index, received '{value}'")
+                })?);
+            }
+            "--loop-end" => {
+                let value = next_value("--loop-end", &mut args)?;
+                loop_end = Some(value.parse::<u64>().map_err(|_| {
+                    format!("--loop-end expects a frame index, received '{value}'")
+                })?);
+            }
+            "--volume" => {
+                let value = next_value("--volume", &mut args)?;
+                volume = Some(
+                    value
+                        .parse::<f32>()
+                        .map_err(|_| format!("--volume expects a number, received '{value}'"))?,
+                );
+            }
+            "--category" => {
+                category = Some(next_value("--category", &mut args)?);
+            }
+            "--cue" => {
+                let value = next_value("--cue", &mut args)?;
+                let cue = value
+                    .split_once('=')
+                    .and_then(|(name, frame)| {
+                        Some(AudioCue {
+                            name: name.to_string(),
+                            frame: frame.parse().ok()?,
+                        })
+                    })
+                    .ok_or_else(|| format!("--cue expects <name>=<frame>, received '{value}'"))?;
+                cues.push(cue);
+            }
             "--audio" => {
                 file = Some(next_value("--audio", &mut args)?);
             }
@@ -619,6 +824,11 @@ fn parse_audio_append(mut args: impl Iterator<Item = String>) -> Result<AudioApp
             entry: entry.ok_or_else(|| "--entry is required".to_string())?,
             file: PathBuf::from(file.ok_or_else(|| "--audio is required".to_string())?),
             format,
+            loop_start,
+            loop_end,
+            volume,
+            category,
+            cues,
         },
     })
 }
@@ -657,6 +867,7 @@ fn parse_font_append(mut args: impl Iterator<Item = String>) -> Result<FontAppen
             entry: entry.ok_or_else(|| "--entry is required".to_string())?,
             file: PathBuf::from(file.ok_or_else(|| "--font is required".to_string())?),
             collection_index: collection_index.unwrap_or_default(),
+            atlas: FontAtlasSettings::default(),
         },
     })
 }
@@ -885,6 +1096,10 @@ fn next_value(flag: &str, args: &mut impl Iterator<Item = String>) -> Result<Str
 enum Command {
     Build { append: bool, spec: PathBuf },
     Validate(ValidateArgs),
+    Verify(VerifyArgs),
+    Compact(CompactArgs),
+    Bundle(BundleArgs),
+    Diff(DiffArgs),
     AppendGeometry(GeometryAppendArgs),
     AppendSkeleton(SkeletonAppendArgs),
     AppendAnimation(AnimationAppendArgs),
@@ -910,6 +1125,35 @@ struct ValidateArgs {
     base: Option<PathBuf>,
 }
 
+#[derive(Debug)]
+struct VerifyArgs {
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+struct CompactArgs {
+    files: Vec<PathBuf>,
+}
+
+#[derive(Debug)]
+struct BundleArgs {
+    base_dir: PathBuf,
+    /// Layout file naming the bundled files; `<base_dir>/layout.json` when omitted.
+    layout: Option<PathBuf>,
+    out: PathBuf,
+}
+
+#[derive(Debug)]
+struct DiffArgs {
+    /// Previous build: a database root, or a single RDB file.
+    old: PathBuf,
+    /// Current build, of the same kind as `old`.
+    new: PathBuf,
+    /// Layout file naming the diffed files; `<new>/layout.json` when omitted.
+    layout: Option<PathBuf>,
+    out: PathBuf,
+}
+
 #[derive(Debug)]
 struct GeometryAppendArgs {
     rdb: PathBuf,
@@ -1059,6 +1303,8 @@ pub fn build_from_spec(
         &geometry_path,
         &geometry,
         options.append,
+        output.compression,
+        output.zero_copy,
         options.write_binaries,
         logger,
     )?;
@@ -1070,6 +1316,8 @@ pub fn build_from_spec(
         &gltf_sources,
         &font_atlases.images,
         options.append,
+        output.compression,
+        output.zero_copy,
         options.write_binaries,
         logger,
     )?;
@@ -1078,6 +1326,7 @@ pub fn build_from_spec(
         &audio_path,
         &audio,
         options.append,
+        output.compression,
         options.write_binaries,
         logger,
     )?;
@@ -1086,6 +1335,7 @@ pub fn build_from_spec(
         &fonts_path,
         &fonts,
         options.append,
+        output.compression,
         options.write_binaries,
         logger,
     )?;
@@ -1094,6 +1344,7 @@ pub fn build_from_spec(
         &skeletons_path,
         &skeletons,
         options.append,
+        output.compression,
         options.write_binaries,
         logger,
     )?;
@@ -1102,6 +1353,7 @@ pub fn build_from_spec(
         &animations_path,
         &animations,
         options.append,
+        output.compression,
         options.write_binaries,
         logger,
     )?;
@@ -1110,6 +1362,7 @@ pub fn build_from_spec(
         &shaders_path,
         &shaders,
         options.append,
+        output.compression,
         options.write_binaries,
         logger,
     )?;
@@ -1198,11 +1451,344 @@ fn run_validation(args: &ValidateArgs, logger: &Logger) -> Result<(), BuildError
     validate_database_layout(base_str, Some(spec_str)).map_err(BuildError::from)
 }
 
+fn run_verify(args: &VerifyArgs, logger: &Logger) -> Result<(), BuildError> {
+    let mut failed = Vec::new();
+
+    for path in &args.files {
+        logger.log(format!("verify: checking {}", path.display()));
+        let view = RDBView::load(path).map_err(|err| {
+            BuildError::message(format!("failed to open {}: {err}", path.display()))
+        })?;
+        let report = view.verify();
+
+        if !report.has_checksums() {
+            eprintln!(
+                "{}: version {} file has no checksums, only entry bounds were checked",
+                path.display(),
+                report.version
+            );
+        }
+        if report.digest_matches == Some(false) {
+            eprintln!("{}: file digest mismatch", path.display());
+        }
+        for corrupt in &report.corrupt_entries {
+            eprintln!(
+                "{}: corrupt entry '{}': {}",
+                path.display(),
+                corrupt.name,
+                corrupt.fault
+            );
+        }
+
+        if report.is_ok() {
+            eprintln!("{}: ok", path.display());
+        } else {
+            failed.push(path.display().to_string());
+        }
+    }
+
+    if failed.is_empty() {
+        Ok(())
+    } else {
+        Err(BuildError::message(format!(
+            "verification failed for {}",
+            failed.join(", ")
+        )))
+    }
+}
+
+fn run_compact(args: &CompactArgs, logger: &Logger) -> Result<(), BuildError> {
+    for path in &args.files {
+        logger.log(format!("compact: rewriting {}", path.display()));
+        let before = fs::metadata(path)?.len();
+        let mut rdb = RDBFile::load(path).map_err(|err| {
+            BuildError::message(format!("failed to open {}: {err}", path.display()))
+        })?;
+        rdb.compact(path).map_err(|err| {
+            BuildError::message(format!("failed to compact {}: {err}", path.display()))
+        })?;
+        let after = fs::metadata(path)?.len();
+        eprintln!(
+            "{}: {before} -> {after} bytes ({} reclaimed)",
+            path.display(),
+            before.saturating_sub(after)
+        );
+    }
+
+    Ok(())
+}
+
+/// Reads `layout`, falling back to `<base_dir>/layout.json` and then the default layout.
+fn read_root_layout(
+    base_dir: &Path,
+    layout: Option<&Path>,
+) -> Result<DatabaseLayoutFile, BuildError> {
+    let layout_path = layout
+        .map(Path::to_path_buf)
+        .unwrap_or_else(|| base_dir.join("layout.json"));
+    if layout_path.is_file() {
+        let file = File::open(&layout_path)?;
+        Ok(serde_json::from_reader(BufReader::new(file))?)
+    } else if layout.is_some() {
+        Err(BuildError::message(format!(
+            "layout file {} does not exist",
+            layout_path.display()
+        )))
+    } else {
+        Ok(DatabaseLayoutFile::default())
+    }
+}
+
+fn run_bundle(args: &BundleArgs, logger: &Logger) -> Result<(), BuildError> {
+    let layout = read_root_layout(&args.base_dir, args.layout.as_deref())?;
+
+    let mut writer = DBBundleWriter::new();
+    writer.add_bytes(BUNDLE_LAYOUT_MEMBER, serde_json::to_vec(&layout)?);
+    let files = layout
+        .module_files()
+        .into_iter()
+        .chain(layout.metadata_files());
+    for file in files {
+        let path = args.base_dir.join(file);
+        if path.is_file() {
+            logger.log(format!("bundle: adding {}", path.display()));
+            writer.add_file(file, &path);
+        } else {
+            logger.log(format!("bundle: skipping missing {}", path.display()));
+        }
+    }
+
+    writer.save(&args.out)?;
+    eprintln!(
+        "{}: bundled {} files",
+        args.out.display(),
+        writer.member_names().count() - 1
+    );
+    Ok(())
+}
+
+/// Entry counts of one diffed file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DiffStats {
+    added: usize,
+    changed: usize,
+    removed: usize,
+}
+
+impl DiffStats {
+    fn is_empty(&self) -> bool {
+        self.added == 0 && self.changed == 0
+    }
+
+    fn report(&self, path: &Path) {
+        eprintln!(
+            "{}: {} added, {} changed",
+            path.display(),
+            self.added,
+            self.changed
+        );
+        if self.removed > 0 {
+            eprintln!(
+                "{}: {} removed entries stay visible from the base database",
+                path.display(),
+                self.removed
+            );
+        }
+    }
+}
+
+fn run_diff(args: &DiffArgs, logger: &Logger) -> Result<(), BuildError> {
+    if args.new.is_file() {
+        let stats = diff_rdb_file(&args.old, &args.new, &args.out, logger)?;
+        stats.report(&args.out);
+        return Ok(());
+    }
+
+    let layout = read_root_layout(&args.new, args.layout.as_deref())?;
+    fs::create_dir_all(&args.out)?;
+    let mut written = 0;
+    for file in layout.module_files() {
+        let new = args.new.join(file);
+        if !new.is_file() {
+            continue;
+        }
+        let out = args.out.join(file);
+        let stats = diff_rdb_file(&args.old.join(file), &new, &out, logger)?;
+        if !stats.is_empty() {
+            stats.report(&out);
+            written += 1;
+        }
+    }
+    for file in layout.metadata_files() {
+        let new = args.new.join(file);
+        if !new.is_file() {
+            continue;
+        }
+        let out = args.out.join(file);
+        let stats = diff_metadata_file(&args.old.join(file), &new, &out, logger)?;
+        if !stats.is_empty() {
+            stats.report(&out);
+            written += 1;
+        }
+    }
+
+    eprintln!("{}: wrote {written} patched files", args.out.display());
+    Ok(())
+}
+
+/// Writes the entries of `new` that are missing from or differ in `old` to `out`.
+///
+/// An entry and its `#` sidecars are copied together so the patch layer always
+/// holds a complete zero-copy entry.
+fn diff_rdb_file(
+    old: &Path,
+    new: &Path,
+    out: &Path,
+    logger: &Logger,
+) -> Result<DiffStats, BuildError> {
+    logger.log(format!(
+        "diff: comparing {} to {}",
+        old.display(),
+        new.display()
+    ));
+    let open = |path: &Path| {
+        RDBView::load(path)
+            .map_err(|err| BuildError::message(format!("failed to open {}: {err}", path.display())))
+    };
+    let old_view = if old.is_file() {
+        Some(open(old)?)
+    } else {
+        None
+    };
+    let new_view = open(new)?;
+
+    let old_meta: HashMap<String, RDBEntryMeta> = old_view
+        .iter()
+        .flat_map(|view| view.entries())
+        .map(|meta| (meta.name.clone(), meta))
+        .collect();
+    let group_of = |name: &str| name.split('#').next().unwrap_or(name).to_string();
+
+    let mut stats = DiffStats::default();
+    let mut dirty_groups = HashSet::new();
+    for meta in new_view.entries() {
+        let unchanged = match (&old_view, old_meta.get(&meta.name)) {
+            (Some(old_view), Some(old)) => {
+                old.type_tag == meta.type_tag
+                    && old.pod == meta.pod
+                    && old_view.entry_bytes(&meta.name)? == new_view.entry_bytes(&meta.name)?
+            }
+            _ => {
+                stats.added += 1;
+                dirty_groups.insert(group_of(&meta.name));
+                continue;
+            }
+        };
+        if !unchanged {
+            stats.changed += 1;
+            dirty_groups.insert(group_of(&meta.name));
+        }
+    }
+    stats.removed = old_meta
+        .keys()
+        .filter(|name| !new_view.contains(name))
+        .count();
+
+    if stats.is_empty() {
+        return Ok(stats);
+    }
+
+    let mut patch = RDBFile::new();
+    for meta in new_view.entries() {
+        if dirty_groups.contains(&group_of(&meta.name)) {
+            patch.copy_entry(&new_view, &meta.name)?;
+        }
+    }
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    patch.save(out)?;
+    Ok(stats)
+}
+
+/// Writes the keys of each metadata map in `new` that are missing from or differ in `old`.
+fn diff_metadata_file(
+    old: &Path,
+    new: &Path,
+    out: &Path,
+    logger: &Logger,
+) -> Result<DiffStats, BuildError> {
+    logger.log(format!(
+        "diff: comparing {} to {}",
+        old.display(),
+        new.display()
+    ));
+    let read = |path: &Path| -> Result<serde_json::Value, BuildError> {
+        let raw = fs::read_to_string(path)?;
+        if raw.trim().is_empty() {
+            return Ok(serde_json::Value::Null);
+        }
+        Ok(serde_json::from_str(&raw)?)
+    };
+    let old_json = if old.is_file() {
+        read(old)?
+    } else {
+        serde_json::Value::Null
+    };
+    let new_json = read(new)?;
+
+    let mut stats = DiffStats::default();
+    let mut patch = serde_json::Map::new();
+    if let Some(fields) = new_json.as_object() {
+        for (field, new_map) in fields {
+            let old_map = old_json.get(field);
+            let (Some(new_map), Some(old_map)) = (
+                new_map.as_object(),
+                old_map.and_then(serde_json::Value::as_object),
+            ) else {
+                if old_map != Some(new_map) {
+                    stats.added += 1;
+                    patch.insert(field.clone(), new_map.clone());
+                }
+                continue;
+            };
+
+            let mut changed = serde_json::Map::new();
+            for (key, value) in new_map {
+                match old_map.get(key) {
+                    None => stats.added += 1,
+                    Some(old_value) if old_value != value => stats.changed += 1,
+                    Some(_) => continue,
+                }
+                changed.insert(key.clone(), value.clone());
+            }
+            stats.removed += old_map
+                .keys()
+                .filter(|key| !new_map.contains_key(*key))
+                .count();
+            if !changed.is_empty() {
+                patch.insert(field.clone(), serde_json::Value::Object(changed));
+            }
+        }
+    }
+
+    if stats.is_empty() {
+        return Ok(stats);
+    }
+    if let Some(parent) = out.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(out, serde_json::to_string_pretty(&patch)?)?;
+    Ok(stats)
+}
+
 fn build_geometry(
     base_dir: &Path,
     output: &Path,
     entries: &[GeometryEntry],
     append: bool,
+    compression: RDBCompression,
+    zero_copy: bool,
     write_binaries: bool,
     logger: &Logger,
 ) -> Result<(), BuildError> {
@@ -1215,6 +1801,7 @@ fn build_geometry(
     } else {
         RDBFile::new()
     };
+    rdb.set_compression(compression);
 
     let mut existing_entries: HashSet<String> =
         rdb.entries().into_iter().map(|meta| meta.name).collect();
@@ -1226,7 +1813,11 @@ fn build_geometry(
             resolve_path(base_dir, &entry.file).display()
         ));
         let host = load_geometry(base_dir, entry)?;
-        rdb.add(&entry.entry, &host).map_err(BuildError::from)?;
+        if zero_copy {
+            add_mapped_geometry(&mut rdb, &entry.entry, &host).map_err(BuildError::from)?;
+        } else {
+            rdb.add(&entry.entry, &host).map_err(BuildError::from)?;
+        }
         existing_entries.insert(entry.entry.clone());
     }
 
@@ -1448,9 +2039,12 @@ fn append_imagery(
         RDBFile::new()
     };
     logger.log(format!("append imagery: {}", args.entry.entry));
-    let image = load_image(Path::new("."), &args.entry)?;
     let entry_name = args.entry.entry.clone();
-    rdb.add(&entry_name, &image).map_err(BuildError::from)?;
+    let added = match load_imagery(Path::new("."), &args.entry)? {
+        LoadedImagery::Image(image) => rdb.add(&entry_name, &image),
+        LoadedImagery::Cubemap(cubemap) => rdb.add(&entry_name, &cubemap),
+    };
+    added.map_err(BuildError::from)?;
     inject_default_imagery(&mut rdb, logger)?;
     if write_binaries {
         logger.log(format!("append imagery: writing {}", args.rdb.display()));
@@ -1497,6 +2091,8 @@ fn build_imagery(
     gltf_sources: &[PathBuf],
     extra_images: &[(String, HostImage)],
     append: bool,
+    compression: RDBCompression,
+    zero_copy: bool,
     write_binaries: bool,
     logger: &Logger,
 ) -> Result<(), BuildError> {
@@ -1509,6 +2105,7 @@ fn build_imagery(
     } else {
         RDBFile::new()
     };
+    rdb.set_compression(compression);
 
     let mut seen_entries: HashSet<String> =
         rdb.entries().into_iter().map(|meta| meta.name).collect();
@@ -1518,8 +2115,12 @@ fn build_imagery(
             entry.entry,
             resolve_path(base_dir, &entry.file).display()
         ));
-        let image = load_image(base_dir, entry)?;
-        rdb.add(&entry.entry, &image).map_err(BuildError::from)?;
+        match load_imagery(base_dir, entry)? {
+            LoadedImagery::Image(image) => add_image(&mut rdb, &entry.entry, &image, zero_copy)?,
+            LoadedImagery::Cubemap(cubemap) => {
+                rdb.add(&entry.entry, &cubemap).map_err(BuildError::from)?
+            }
+        }
         seen_entries.insert(entry.entry.clone());
     }
 
@@ -1532,7 +2133,7 @@ fn build_imagery(
                         entry_name,
                         resolve_path(base_dir, gltf_source).display()
                     ));
-                    rdb.add(&entry_name, &image).map_err(BuildError::from)?;
+                    add_image(&mut rdb, &entry_name, &image, zero_copy)?;
                 }
             }
             Err(err) => {
@@ -1550,7 +2151,7 @@ fn build_imagery(
             continue;
         }
         logger.log(format!("imagery: adding generated {entry}"));
-        rdb.add(entry, image).map_err(BuildError::from)?;
+        add_image(&mut rdb, entry, image, zero_copy)?;
         seen_entries.insert(entry.clone());
     }
 
@@ -1563,6 +2164,19 @@ fn build_imagery(
     Ok(())
 }
 
+fn add_image(
+    rdb: &mut RDBFile,
+    entry: &str,
+    image: &HostImage,
+    zero_copy: bool,
+) -> Result<(), BuildError> {
+    if zero_copy {
+        add_mapped_image(rdb, entry, image).map_err(BuildError::from)
+    } else {
+        rdb.add(entry, image).map_err(BuildError::from)
+    }
+}
+
 fn append_audio(
     args: &AudioAppendArgs,
     logger: &Logger,
@@ -1688,6 +2302,7 @@ fn build_audio(
     output: &Path,
     entries: &[AudioEntry],
     append: bool,
+    compression: RDBCompression,
     write_binaries: bool,
     logger: &Logger,
 ) -> Result<(), BuildError> {
@@ -1700,6 +2315,7 @@ fn build_audio(
     } else {
         RDBFile::new()
     };
+    rdb.set_compression(compression);
 
     let mut seen_entries: HashSet<String> =
         rdb.entries().into_iter().map(|meta| meta.name).collect();
@@ -1739,6 +2355,7 @@ fn build_fonts(
     output: &Path,
     entries: &[FontEntry],
     append: bool,
+    compression: RDBCompression,
     write_binaries: bool,
     logger: &Logger,
 ) -> Result<(), BuildError> {
@@ -1751,6 +2368,7 @@ fn build_fonts(
     } else {
         RDBFile::new()
     };
+    rdb.set_compression(compression);
 
     let mut seen_entries: HashSet<String> =
         rdb.entries().into_iter().map(|meta| meta.name).collect();
@@ -1800,7 +2418,7 @@ fn build_font_atlases(
         if seen_entries.contains(&font.info.name) {
             continue;
         }
-        fonts.push(font);
+        fonts.push((font, FontAtlasSettings::default(), None));
     }
 
     for entry in entries {
@@ -1809,56 +2427,72 @@ fn build_font_atlases(
             entry.entry,
             resolve_path(base_dir, &entry.file).display()
         ));
-        fonts.push(load_font(base_dir, entry)?);
+        validate_font_atlas_settings(entry)?;
+        let codepoints = font_codepoints(base_dir, entry)?;
+        fonts.push((load_font(base_dir, entry)?, entry.atlas.clone(), codepoints));
     }
 
     let mut images = Vec::new();
     let mut msdf_layouts = HashMap::new();
     let mut sdf_layouts = HashMap::new();
 
-    for font in fonts {
-        let settings = FontSettings {
+    for (font, settings, codepoints) in fonts {
+        let parse_error =
+            |err: &dyn std::fmt::Display| format!("font parse error for {}: {err}", font.info.name);
+        let font_settings = FontSettings {
             collection_index: font.info.collection_index,
             ..FontSettings::default()
         };
-        let parsed_font = Font::from_bytes(font.data.clone(), settings).map_err(|err| {
-            BuildError::message(format!("font parse error for {}: {err}", font.info.name))
-        })?;
-        let image_entry = font_atlas_image_entry(&font.info.name);
-        let (image, glyphs, metrics) = generate_font_atlas(&parsed_font, &image_entry, 16.0, 2)?;
+        let parsed_font = Font::from_bytes(font.data.clone(), font_settings)
+            .map_err(|err| BuildError::message(parse_error(&err)))?;
+        let face = ttf_parser::Face::parse(&font.data, font.info.collection_index)
+            .map_err(|err| BuildError::message(parse_error(&err)))?;
+        logger.log(format!(
+            "font: generating {:?} atlas for {} at {}px",
+            settings.kind, font.info.name, settings.size
+        ));
+        let atlas = generate_font_atlas(&parsed_font, &face, &settings, codepoints.as_ref())?;
         let display_name = parsed_font
             .name()
             .map(str::to_string)
             .unwrap_or_else(|| font.info.name.clone());
         let leaf = font_leaf_name(&font.info.name);
 
-        images.push((image_entry.clone(), image));
-
-        msdf_layouts.insert(
-            format!("msdf_fonts/{leaf}"),
-            MsdfFontLayout {
-                image: image_entry.clone(),
-                name: Some(format!("{display_name} MSDF Font")),
-                font: Some(font.info.name.clone()),
-                size: 16.0,
-                distance_range: 4.0,
-                angle_threshold: 3.0,
-                metrics: metrics.clone(),
-                glyphs: glyphs.clone(),
-            },
-        );
-        sdf_layouts.insert(
-            format!("sdf_fonts/{leaf}"),
-            SdfFontLayout {
-                image: image_entry,
-                name: Some(format!("{display_name} SDF Font")),
-                font: Some(font.info.name.clone()),
-                size: 16.0,
-                distance_range: 4.0,
-                metrics,
-                glyphs,
-            },
-        );
+        if settings.kind != FontAtlasKind::Sdf {
+            let image_entry = font_atlas_image_entry(&font.info.name, "msdf");
+            images.push((image_entry.clone(), atlas.msdf_image(&image_entry)));
+            msdf_layouts.insert(
+                format!("msdf_fonts/{leaf}"),
+                MsdfFontLayout {
+                    image: image_entry,
+                    name: Some(format!("{display_name} MSDF Font")),
+                    font: Some(font.info.name.clone()),
+                    size: settings.size,
+                    distance_range: settings.distance_range,
+                    angle_threshold: settings.angle_threshold,
+                    metrics: atlas.metrics.clone(),
+                    glyphs: atlas.glyphs.clone(),
+                    kerning: atlas.kerning.clone(),
+                },
+            );
+        }
+        if settings.kind != FontAtlasKind::Msdf {
+            let image_entry = font_atlas_image_entry(&font.info.name, "sdf");
+            images.push((image_entry.clone(), atlas.sdf_image(&image_entry)));
+            sdf_layouts.insert(
+                format!("sdf_fonts/{leaf}"),
+                SdfFontLayout {
+                    image: image_entry,
+                    name: Some(format!("{display_name} SDF Font")),
+                    font: Some(font.info.name.clone()),
+                    size: settings.size,
+                    distance_range: settings.distance_range,
+                    metrics: atlas.metrics,
+                    glyphs: atlas.glyphs,
+                    kerning: atlas.kerning,
+                },
+            );
+        }
     }
 
     Ok(FontAtlasOutput {
@@ -1868,6 +2502,82 @@ fn build_font_atlases(
     })
 }
 
+fn validate_font_atlas_settings(entry: &FontEntry) -> Result<(), BuildError> {
+    let settings = &entry.atlas;
+    for (name, value) in [
+        ("size", settings.size),
+        ("distance_range", settings.distance_range),
+        ("angle_threshold", settings.angle_threshold),
+    ] {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(BuildError::message(format!(
+                "font {}: {name} must be a positive number, got {value}",
+                entry.entry
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the characters a font entry asks for, or `None` for every glyph
+/// in the font.
+fn font_codepoints(
+    base_dir: &Path,
+    entry: &FontEntry,
+) -> Result<Option<BTreeSet<u32>>, BuildError> {
+    let settings = &entry.atlas;
+    if settings.ranges.is_empty() && settings.charset.is_none() {
+        return Ok(None);
+    }
+
+    let mut codepoints = BTreeSet::new();
+    for range in &settings.ranges {
+        let (start, end) = parse_unicode_range(range).ok_or_else(|| {
+            BuildError::message(format!(
+                "font {}: invalid unicode range '{range}', expected e.g. U+0020-U+007E",
+                entry.entry
+            ))
+        })?;
+        codepoints.extend(start..=end);
+    }
+    if let Some(charset) = &settings.charset {
+        let path = resolve_path(base_dir, charset);
+        let text = fs::read_to_string(&path).map_err(|err| {
+            BuildError::message(format!(
+                "font {}: failed to read charset {}: {err}",
+                entry.entry,
+                path.display()
+            ))
+        })?;
+        codepoints.extend(text.chars().filter(|ch| !ch.is_control()).map(u32::from));
+    }
+    Ok(Some(codepoints))
+}
+
+/// Parses `U+XXXX`, `0xXXXX` or decimal code points, optionally as a `start-end` range.
+fn parse_unicode_range(value: &str) -> Option<(u32, u32)> {
+    let codepoint = |text: &str| {
+        let text = text.trim();
+        let hex = text
+            .strip_prefix("U+")
+            .or_else(|| text.strip_prefix("u+"))
+            .or_else(|| text.strip_prefix("0x"));
+        let value = match hex {
+            Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+            None => text.parse().ok()?,
+        };
+        (value <= 0x10FFFF).then_some(value)
+    };
+    let (start, end) = match value.split_once('-') {
+        Some((start, end)) => (codepoint(start)?, codepoint(end)?),
+        None => {
+            let value = codepoint(value)?;
+            (value, value)
+        }
+    };
+    (start <= end).then_some((start, end))
+}
+
 fn font_leaf_name(entry: &str) -> String {
     entry
         .rsplit('/')
@@ -1877,45 +2587,48 @@ fn font_leaf_name(entry: &str) -> String {
         .replace(' ', "_")
 }
 
-fn font_atlas_image_entry(entry: &str) -> String {
+fn font_atlas_image_entry(entry: &str, kind: &str) -> String {
     let leaf = font_leaf_name(entry);
-    format!("imagery/fonts/{leaf}")
+    format!("imagery/fonts/{leaf}_{kind}")
 }
 
 fn generate_font_atlas(
     font: &Font,
-    image_entry: &str,
-    size: f32,
-    padding: u32,
-) -> Result<(HostImage, Vec<FontGlyph>, FontMetrics), BuildError> {
-    let mut glyphs: Vec<RasterizedGlyph> = font
-        .chars()
-        .iter()
-        .map(|(character, glyph_index)| {
-            let (metrics, bitmap) = font.rasterize_indexed(glyph_index.get(), size);
-            RasterizedGlyph {
-                unicode: *character as u32,
-                metrics,
-                bitmap,
-            }
-        })
-        .collect();
-    if glyphs.is_empty() {
-        glyphs = (32u32..=126u32)
+    face: &ttf_parser::Face<'_>,
+    settings: &FontAtlasSettings,
+    codepoints: Option<&BTreeSet<u32>>,
+) -> Result<FontAtlas, BuildError> {
+    let mut glyph_indices: Vec<(u32, u16)> = match codepoints {
+        Some(codepoints) => codepoints
+            .iter()
+            .filter_map(|&codepoint| {
+                let index = font.lookup_glyph_index(char::from_u32(codepoint)?);
+                (index != 0).then_some((codepoint, index))
+            })
+            .collect(),
+        None => font
+            .chars()
+            .iter()
+            .map(|(character, glyph_index)| (*character as u32, glyph_index.get()))
+            .collect(),
+    };
+    if glyph_indices.is_empty() {
+        glyph_indices = (32u32..=126u32)
             .filter_map(|codepoint| {
                 let ch = char::from_u32(codepoint)?;
-                let (metrics, bitmap) = font.rasterize(ch, size);
-                Some(RasterizedGlyph {
-                    unicode: codepoint,
-                    metrics,
-                    bitmap,
-                })
+                Some((codepoint, font.lookup_glyph_index(ch)))
             })
             .collect();
     }
-    glyphs.sort_by_key(|glyph| glyph.unicode);
+    glyph_indices.sort_by_key(|(unicode, _)| *unicode);
+
+    let kerning = font_kerning(face, &glyph_indices, settings.size);
+    let glyphs: Vec<RenderedGlyph> = glyph_indices
+        .into_iter()
+        .map(|(unicode, index)| render_glyph(font, face, unicode, index, settings))
+        .collect();
 
-    let placements = pack_glyphs(&glyphs, padding)?;
+    let placements = pack_glyphs(&glyphs, settings.padding)?;
     let atlas_dim = placements
         .values()
         .fold(1u32, |acc, placement| acc.max(placement.atlas_dim));
@@ -1923,41 +2636,22 @@ fn generate_font_atlas(
 
     for (index, glyph) in glyphs.iter().enumerate() {
         if let Some(placement) = placements.get(&index) {
-            let width = glyph.metrics.width as u32;
-            let height = glyph.metrics.height as u32;
-            for row in 0..height {
-                let src_start = (row * width) as usize;
-                let src_end = src_start + width as usize;
-                let src_row = &glyph.bitmap[src_start..src_end];
-                let dest_y = placement.y + row;
-                let dest_x = placement.x;
-                let dest_start = ((dest_y * atlas_dim + dest_x) * 4) as usize;
-                for (offset, alpha) in src_row.iter().enumerate() {
-                    let dest = dest_start + offset * 4;
-                    pixels[dest] = 255;
-                    pixels[dest + 1] = 255;
-                    pixels[dest + 2] = 255;
-                    pixels[dest + 3] = *alpha;
-                }
+            let row_len = (glyph.width * 4) as usize;
+            for row in 0..glyph.height {
+                let src_start = row as usize * row_len;
+                let src_row = &glyph.pixels[src_start..src_start + row_len];
+                let dest_start = (((placement.y + row) * atlas_dim + placement.x) * 4) as usize;
+                pixels[dest_start..dest_start + row_len].copy_from_slice(src_row);
             }
         }
     }
 
-    let info = ImageInfo {
-        name: image_entry.to_string(),
-        dim: [atlas_dim, atlas_dim, 1],
-        layers: 1,
-        format: dashi::Format::RGBA8,
-        mip_levels: 1,
-    };
-    let image = HostImage::new(info, pixels);
-
-    let line_metrics = font.horizontal_line_metrics(size);
+    let line_metrics = font.horizontal_line_metrics(settings.size);
     let metrics = FontMetrics {
-        em_size: size,
+        em_size: settings.size,
         line_height: line_metrics
             .map(|metrics| metrics.new_line_size)
-            .unwrap_or(size),
+            .unwrap_or(settings.size),
         ascender: line_metrics
             .map(|metrics| metrics.ascent)
             .unwrap_or_default(),
@@ -1973,8 +2667,8 @@ fn generate_font_atlas(
         .enumerate()
         .map(|(index, glyph)| FontGlyph {
             unicode: glyph.unicode,
-            advance: glyph.metrics.advance_width,
-            plane_bounds: glyph_plane_bounds(&glyph.metrics),
+            advance: glyph.advance,
+            plane_bounds: glyph_plane_bounds(glyph),
             atlas_bounds: placements.get(&index).map(|placement| FontBounds {
                 left: placement.x as f32,
                 bottom: placement.y as f32,
@@ -1984,24 +2678,176 @@ fn generate_font_atlas(
         })
         .collect();
 
-    Ok((image, layout_glyphs, metrics))
+    Ok(FontAtlas {
+        dim: atlas_dim,
+        pixels,
+        glyphs: layout_glyphs,
+        kerning,
+        metrics,
+    })
 }
 
-fn glyph_plane_bounds(metrics: &fontdue::Metrics) -> Option<FontBounds> {
-    if metrics.width == 0 || metrics.height == 0 {
-        return None;
+/// Collects kerning between the given glyphs, scaled to pixels at `size`.
+///
+/// Pairs come from format 0 `kern` subtables, or from `GPOS` pair adjustments
+/// in the `kern` feature when the font has no `kern` table.
+fn font_kerning(face: &ttf_parser::Face<'_>, glyphs: &[(u32, u16)], size: f32) -> Vec<FontKerning> {
+    let mut codepoints: HashMap<u16, Vec<u32>> = HashMap::new();
+    for (unicode, index) in glyphs {
+        codepoints.entry(*index).or_default().push(*unicode);
     }
-    let left = metrics.xmin as f32;
-    let bottom = metrics.ymin as f32;
-    Some(FontBounds {
-        left,
-        bottom,
-        right: left + metrics.width as f32,
-        top: bottom + metrics.height as f32,
-    })
-}
 
-struct GlyphPlacement {
+    let mut pairs = kern_table_pairs(face, &codepoints);
+    if pairs.is_empty() {
+        pairs = gpos_kerning_pairs(face, &codepoints);
+    }
+
+    let scale = size / f32::from(face.units_per_em().max(1));
+    let mut kerning: BTreeMap<(u32, u32), f32> = BTreeMap::new();
+    for ((left, right), value) in pairs {
+        for &left in &codepoints[&left] {
+            for &right in &codepoints[&right] {
+                *kerning.entry((left, right)).or_default() += f32::from(value) * scale;
+            }
+        }
+    }
+    kerning
+        .into_iter()
+        .filter(|(_, advance)| *advance != 0.0)
+        .map(|((left, right), advance)| FontKerning {
+            left,
+            right,
+            advance,
+        })
+        .collect()
+}
+
+fn kern_table_pairs(
+    face: &ttf_parser::Face<'_>,
+    codepoints: &HashMap<u16, Vec<u32>>,
+) -> Vec<((u16, u16), i16)> {
+    let Some(kern) = face.tables().kern else {
+        return Vec::new();
+    };
+    let mut pairs = Vec::new();
+    for subtable in kern.subtables {
+        if !subtable.horizontal || subtable.has_cross_stream {
+            continue;
+        }
+        if let ttf_parser::kern::Format::Format0(table) = subtable.format {
+            pairs.extend(
+                table
+                    .pairs
+                    .into_iter()
+                    .map(|pair| ((pair.left().0, pair.right().0), pair.value))
+                    .filter(|((left, right), _)| {
+                        codepoints.contains_key(left) && codepoints.contains_key(right)
+                    }),
+            );
+        }
+    }
+    pairs
+}
+
+fn gpos_kerning_pairs(
+    face: &ttf_parser::Face<'_>,
+    codepoints: &HashMap<u16, Vec<u32>>,
+) -> Vec<((u16, u16), i16)> {
+    let Some(gpos) = face.tables().gpos else {
+        return Vec::new();
+    };
+    let lookups: BTreeSet<u16> = gpos
+        .features
+        .into_iter()
+        .filter(|feature| feature.tag == Tag::from_bytes(b"kern"))
+        .flat_map(|feature| feature.lookup_indices)
+        .collect();
+
+    let mut pairs = Vec::new();
+    for lookup in lookups
+        .into_iter()
+        .filter_map(|index| gpos.lookups.get(index))
+    {
+        // Within a lookup, the first subtable that kerns a pair wins.
+        let mut seen = HashSet::new();
+        for subtable in lookup.subtables.into_iter::<PositioningSubtable>() {
+            let PositioningSubtable::Pair(adjustment) = subtable else {
+                continue;
+            };
+            for &left in codepoints.keys() {
+                let Some(coverage) = adjustment.coverage().get(GlyphId(left)) else {
+                    continue;
+                };
+                for &right in codepoints.keys() {
+                    let records = match adjustment {
+                        PairAdjustment::Format1 { sets, .. } => {
+                            sets.get(coverage).and_then(|set| set.get(GlyphId(right)))
+                        }
+                        PairAdjustment::Format2 {
+                            classes, matrix, ..
+                        } => matrix
+                            .get((classes.0.get(GlyphId(left)), classes.1.get(GlyphId(right)))),
+                    };
+                    if let Some((first, _)) = records {
+                        if seen.insert((left, right)) {
+                            pairs.push(((left, right), first.x_advance));
+                        }
+                    }
+                }
+            }
+        }
+    }
+    pairs
+}
+
+/// Renders one glyph's distance field into a cell that extends half the
+/// distance range past its outline.
+fn render_glyph(
+    font: &Font,
+    face: &ttf_parser::Face<'_>,
+    unicode: u32,
+    index: u16,
+    settings: &FontAtlasSettings,
+) -> RenderedGlyph {
+    let mut glyph = RenderedGlyph {
+        unicode,
+        advance: font.metrics_indexed(index, settings.size).advance_width,
+        origin: Vec2::ZERO,
+        width: 0,
+        height: 0,
+        pixels: Vec::new(),
+    };
+    let Some(mut shape) = GlyphShape::from_face(face, index, settings.size) else {
+        return glyph;
+    };
+    let Some((min, max)) = shape.bounds() else {
+        return glyph;
+    };
+
+    let margin = Vec2::splat(settings.distance_range * 0.5);
+    let origin = (min - margin).floor();
+    let extent = (max + margin).ceil() - origin;
+    shape.color_edges(settings.angle_threshold);
+    glyph.origin = origin;
+    glyph.width = extent.x as u32;
+    glyph.height = extent.y as u32;
+    glyph.pixels = shape.render(origin, glyph.width, glyph.height, settings.distance_range);
+    glyph
+}
+
+fn glyph_plane_bounds(glyph: &RenderedGlyph) -> Option<FontBounds> {
+    if glyph.width == 0 || glyph.height == 0 {
+        return None;
+    }
+    Some(FontBounds {
+        left: glyph.origin.x,
+        bottom: glyph.origin.y,
+        right: glyph.origin.x + glyph.width as f32,
+        top: glyph.origin.y + glyph.height as f32,
+    })
+}
+
+struct GlyphPlacement {
     x: u32,
     y: u32,
     width: u32,
@@ -2010,19 +2856,17 @@ struct GlyphPlacement {
 }
 
 fn pack_glyphs(
-    glyphs: &[RasterizedGlyph],
+    glyphs: &[RenderedGlyph],
     padding: u32,
 ) -> Result<HashMap<usize, GlyphPlacement>, BuildError> {
     let mut sortable: Vec<(usize, u32, u32)> = glyphs
         .iter()
         .enumerate()
         .filter_map(|(index, glyph)| {
-            let width = glyph.metrics.width as u32;
-            let height = glyph.metrics.height as u32;
-            if width == 0 || height == 0 {
+            if glyph.width == 0 || glyph.height == 0 {
                 None
             } else {
-                Some((index, width, height))
+                Some((index, glyph.width, glyph.height))
             }
         })
         .collect();
@@ -2088,6 +2932,7 @@ fn build_skeletons(
     output: &Path,
     entries: &[SkeletonEntry],
     append: bool,
+    compression: RDBCompression,
     write_binaries: bool,
     logger: &Logger,
 ) -> Result<(), BuildError> {
@@ -2100,6 +2945,7 @@ fn build_skeletons(
     } else {
         RDBFile::new()
     };
+    rdb.set_compression(compression);
 
     for entry in entries {
         logger.log(format!(
@@ -2125,6 +2971,7 @@ fn build_animations(
     output: &Path,
     entries: &[AnimationEntry],
     append: bool,
+    compression: RDBCompression,
     write_binaries: bool,
     logger: &Logger,
 ) -> Result<(), BuildError> {
@@ -2137,6 +2984,7 @@ fn build_animations(
     } else {
         RDBFile::new()
     };
+    rdb.set_compression(compression);
 
     for entry in entries {
         logger.log(format!(
@@ -2740,25 +3588,104 @@ fn parse_mutation_layer_entry(name: &str, prefix: &str) -> Option<(String, u32)>
     Some((layer_id, version))
 }
 
+/// An imagery entry; cube containers become cubemaps.
+enum LoadedImagery {
+    Image(HostImage),
+    Cubemap(HostCubemap),
+}
+
+fn load_imagery(base_dir: &Path, entry: &ImageEntry) -> Result<LoadedImagery, BuildError> {
+    let path = resolve_path(base_dir, &entry.file);
+    match ContainerKind::from_path(&path) {
+        Some(kind) => load_texture_container(&path, kind, entry),
+        None => load_image(base_dir, entry).map(LoadedImagery::Image),
+    }
+}
+
+/// Imports a KTX2 or DDS file as stored, keeping its format, levels and layers.
+fn load_texture_container(
+    path: &Path,
+    kind: ContainerKind,
+    entry: &ImageEntry,
+) -> Result<LoadedImagery, BuildError> {
+    let bytes = fs::read(path)?;
+    let texture = read_container(kind, &bytes)
+        .map_err(|err| BuildError::message(format!("{}: {err}", path.display())))?;
+    let info = ImageInfo {
+        name: entry.entry.clone(),
+        dim: [texture.width, texture.height, 1],
+        layers: texture.layers,
+        format: texture.format,
+        mip_levels: texture.mip_levels,
+    };
+
+    if !texture.cube {
+        return Ok(LoadedImagery::Image(HostImage::with_mips(
+            info,
+            texture.data,
+            texture.mip_offsets,
+        )));
+    }
+    if texture.layers != 6 {
+        return Err(BuildError::message(format!(
+            "{}: cubemap arrays are not supported",
+            path.display()
+        )));
+    }
+    Ok(LoadedImagery::Cubemap(HostCubemap::with_mips(
+        info,
+        texture.data,
+        texture.mip_offsets,
+    )))
+}
+
 fn load_image(base_dir: &Path, entry: &ImageEntry) -> Result<HostImage, BuildError> {
     let path = resolve_path(base_dir, &entry.file);
     let image = image::open(&path)?;
     let rgba = to_rgba(image);
     let (width, height) = rgba.dimensions();
-    let data = rgba.into_raw();
+    let mip_levels = entry.mip_levels.clamp(1, full_mip_count(width, height));
+    let srgb = entry
+        .mipmap
+        .srgb
+        .unwrap_or_else(|| is_srgb_format(entry.format));
+
+    let (data, mip_offsets) = if mip_levels > 1 {
+        generate_mip_chain(
+            rgba.as_raw(),
+            width,
+            height,
+            mip_levels,
+            srgb,
+            &entry.mipmap,
+        )
+    } else {
+        (rgba.into_raw(), Vec::new())
+    };
+    let (data, mip_offsets) = match BlockFormat::from_format(entry.format) {
+        Some(block) => encode_mip_chain(block, &data, &mip_offsets, width, height),
+        None => (data, mip_offsets),
+    };
 
     let info = ImageInfo {
         name: entry.entry.clone(),
         dim: [width, height, 1],
         layers: entry.layers,
         format: entry.format,
-        mip_levels: entry.mip_levels,
+        mip_levels,
     };
 
-    Ok(HostImage::new(info, data))
+    Ok(HostImage::with_mips(info, data, mip_offsets))
 }
 
 fn load_cubemap(base_dir: &Path, entry: &CubemapEntry) -> Result<HostCubemap, BuildError> {
+    if BlockFormat::from_format(entry.format).is_some() {
+        return Err(BuildError::message(format!(
+            "cubemap {} cannot use block-compressed format {:?}",
+            entry.entry, entry.format
+        )));
+    }
+
     let face_paths = [
         &entry.pos_x,
         &entry.neg_x,
@@ -2921,7 +3848,64 @@ fn load_audio(base_dir: &Path, entry: &AudioEntry) -> Result<AudioClip, BuildErr
     let data = fs::read(&path)?;
     let format = infer_audio_format(&entry.file, entry.format.clone());
 
-    Ok(AudioClip::new(entry.entry.clone(), format, data))
+    let decode_error = |err: NorenError| {
+        BuildError::message(format!("failed to decode audio {}: {err}", path.display()))
+    };
+
+    let mut clip = AudioClip::new(entry.entry.clone(), format, data);
+    let mut stream = PcmStream::new(clip.clone()).map_err(decode_error)?;
+    let frames = stream.frame_count().map_err(decode_error)?;
+    clip.duration_secs = stream.duration_secs().unwrap_or_default();
+    clip.looping = audio_loop(entry, frames)?;
+    if let Some(volume) = entry.volume {
+        if !volume.is_finite() || volume < 0.0 {
+            return Err(BuildError::message(format!(
+                "audio {}: volume must be a non-negative number, got {volume}",
+                entry.entry
+            )));
+        }
+        clip.volume = volume;
+    }
+    clip.category = entry.category.clone();
+
+    for (idx, cue) in entry.cues.iter().enumerate() {
+        if cue.frame > frames {
+            return Err(BuildError::message(format!(
+                "audio {}: cue '{}' at frame {} is past the end of the clip ({frames} frames)",
+                entry.entry, cue.name, cue.frame
+            )));
+        }
+        if entry.cues[..idx].iter().any(|other| other.name == cue.name) {
+            return Err(BuildError::message(format!(
+                "audio {}: duplicate cue '{}'",
+                entry.entry, cue.name
+            )));
+        }
+    }
+    clip.cues = entry.cues.clone();
+
+    Ok(clip)
+}
+
+/// Resolves the loop region of an audio entry against the clip's frame count.
+fn audio_loop(entry: &AudioEntry, frames: u64) -> Result<Option<AudioLoop>, BuildError> {
+    if entry.loop_start.is_none() && entry.loop_end.is_none() {
+        return Ok(None);
+    }
+
+    let start = entry.loop_start.unwrap_or(0);
+    let end = entry.loop_end.unwrap_or(frames);
+    if start >= end || end > frames {
+        return Err(BuildError::message(format!(
+            "audio {}: loop {start}..{end} must be a non-empty region within the clip ({frames} frames)",
+            entry.entry
+        )));
+    }
+
+    Ok(Some(AudioLoop {
+        start,
+        end: entry.loop_end,
+    }))
 }
 
 fn load_font(base_dir: &Path, entry: &FontEntry) -> Result<HostFont, BuildError> {
@@ -3184,6 +4168,7 @@ fn build_shaders(
     output: &Path,
     entries: &[ShaderEntry],
     append: bool,
+    compression: RDBCompression,
     write_binaries: bool,
     logger: &Logger,
 ) -> Result<(), BuildError> {
@@ -3196,6 +4181,7 @@ fn build_shaders(
     } else {
         RDBFile::new()
     };
+    rdb.set_compression(compression);
 
     let compiler = BentoCompiler::new()?;
 
@@ -3270,6 +4256,13 @@ fn print_usage(program: &str) {
     eprintln!("  {program} <staging-build.json>");
     eprintln!("  {program} --append <staging-build.json>");
     eprintln!("  {program} validate <layout.json> [--base <db root>]");
+    eprintln!("  {program} verify <file.rdb> [<file.rdb> ...]");
+    eprintln!("  {program} compact <file.rdb> [<file.rdb> ...]");
+    eprintln!("  {program} bundle <db root> --out <file.nrb> [--layout <layout.json>]");
+    eprintln!(
+        "  {program} diff <old db root> <new db root> --out <patch root> [--layout <layout.json>]"
+    );
+    eprintln!("  {program} diff <old.rdb> <new.rdb> --out <patch.rdb>");
     eprintln!(
         "  {program} append geometry --rdb <geometry.rdb> --entry <name> --gltf <file> [--mesh <name>] [--primitive <index>]"
     );
@@ -3280,13 +4273,13 @@ fn print_usage(program: &str) {
         "  {program} append animation --rdb <animations.rdb> --entry <name> --gltf <file> [--animation <name>]"
     );
     eprintln!(
-        "  {program} append imagery --rdb <imagery.rdb> --entry <name> --image <file> [--layers <count>] [--mip-levels <count>] [--format <format>]"
+        "  {program} append imagery --rdb <imagery.rdb> --entry <name> --image <file> [--layers <count>] [--mip-levels <count>] [--format <format>] [--mip-filter <box|kaiser>] [--srgb <bool>] [--alpha-cutoff <value>] [--normal-map]"
     );
     eprintln!(
         "  {program} append cubemap --rdb <imagery.rdb> --entry <name> --pos-x <file> --neg-x <file> --pos-y <file> --neg-y <file> --pos-z <file> --neg-z <file> [--mip-levels <count>] [--format <format>]"
     );
     eprintln!(
-        "  {program} append audio --rdb <audio.rdb> --entry <name> --audio <file> [--format <format>]"
+        "  {program} append audio --rdb <audio.rdb> --entry <name> --audio <file> [--format <format>] [--loop-start <frame>] [--loop-end <frame>] [--volume <gain>] [--category <name>] [--cue <name>=<frame>]..."
     );
     eprintln!(
         "  {program} append font --rdb <fonts.rdb> --entry <name> --font <file> [--collection-index <index>]"
@@ -3344,6 +4337,10 @@ pub struct OutputSpec {
     pub directory: PathBuf,
     pub layout_file: PathBuf,
     pub layout: DatabaseLayoutFile,
+    /// Codec applied to entries written to the output RDB files.
+    pub compression: RDBCompression,
+    /// Store vertex, index and pixel data as raw slices that can be read without copying.
+    pub zero_copy: bool,
 }
 
 impl Default for OutputSpec {
@@ -3352,6 +4349,8 @@ impl Default for OutputSpec {
             directory: PathBuf::from("db"),
             layout_file: PathBuf::from("layout.json"),
             layout: DatabaseLayoutFile::default(),
+            compression: RDBCompression::None,
+            zero_copy: false,
         }
     }
 }
@@ -3400,10 +4399,17 @@ pub struct ImageEntry {
     pub file: PathBuf,
     #[serde(default = "default_layers")]
     pub layers: u32,
-    #[serde(default = "default_format")]
+    /// Pixel format, such as `rgba8` or a block-compressed `bc7`.
+    #[serde(
+        default = "default_format",
+        deserialize_with = "deserialize_image_format"
+    )]
     pub format: dashi::Format,
     #[serde(default = "default_mip_levels")]
     pub mip_levels: u32,
+    /// Mip chain settings, given inline on the entry.
+    #[serde(flatten)]
+    pub mipmap: MipmapSettings,
 }
 
 #[derive(Debug)]
@@ -3431,6 +4437,37 @@ fn default_format() -> dashi::Format {
     dashi::Format::RGBA8
 }
 
+/// Reads an image format by the names `parse_image_format` accepts.
+fn deserialize_image_format<'de, D>(deserializer: D) -> Result<dashi::Format, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    parse_image_format(&value)
+        .ok_or_else(|| serde::de::Error::custom(format!("unknown image format '{value}'")))
+}
+
+/// Whether `format` stores color in sRGB, so filtering must happen in linear space.
+fn is_srgb_format(format: dashi::Format) -> bool {
+    matches!(
+        format,
+        dashi::Format::RGB8
+            | dashi::Format::RGBA8
+            | dashi::Format::BGRA8
+            | dashi::Format::BC1
+            | dashi::Format::BC3
+            | dashi::Format::BC7
+    )
+}
+
+fn parse_mip_filter(value: &str) -> Option<MipFilter> {
+    match value.to_ascii_lowercase().as_str() {
+        "box" => Some(MipFilter::Box),
+        "kaiser" => Some(MipFilter::Kaiser),
+        _ => None,
+    }
+}
+
 fn parse_image_format(value: &str) -> Option<dashi::Format> {
     match value.to_ascii_lowercase().as_str() {
         "r8uint" => Some(dashi::Format::R8Uint),
@@ -3442,6 +4479,11 @@ fn parse_image_format(value: &str) -> Option<dashi::Format> {
         "rgba32f" | "rgba32_float" | "rgba32float" => Some(dashi::Format::RGBA32F),
         "bgra8unorm" | "bgra8_unorm" => Some(dashi::Format::BGRA8Unorm),
         "d24s8" => Some(dashi::Format::D24S8),
+        "bc1" => Some(dashi::Format::BC1),
+        "bc3" => Some(dashi::Format::BC3),
+        "bc4" => Some(dashi::Format::BC4),
+        "bc5" => Some(dashi::Format::BC5),
+        "bc7" => Some(dashi::Format::BC7),
         _ => None,
     }
 }
@@ -3452,6 +4494,18 @@ pub struct AudioEntry {
     pub file: PathBuf,
     #[serde(default)]
     pub format: Option<AudioFormat>,
+    /// First sample frame of the loop region; setting either bound makes the clip loop.
+    #[serde(default)]
+    pub loop_start: Option<u64>,
+    /// Exclusive end frame of the loop region; defaults to the end of the clip.
+    #[serde(default)]
+    pub loop_end: Option<u64>,
+    #[serde(default)]
+    pub volume: Option<f32>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub cues: Vec<AudioCue>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -3460,6 +4514,53 @@ pub struct FontEntry {
     pub file: PathBuf,
     #[serde(default)]
     pub collection_index: u32,
+    /// Distance-field atlas settings, given inline on the entry.
+    #[serde(flatten)]
+    pub atlas: FontAtlasSettings,
+}
+
+/// Which distance-field atlases `dbgen` generates for a font.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FontAtlasKind {
+    Sdf,
+    Msdf,
+    #[default]
+    Both,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct FontAtlasSettings {
+    /// Pixels per em in the atlas.
+    pub size: f32,
+    /// Empty pixels between glyph cells.
+    pub padding: u32,
+    /// Distance in pixels between the darkest and brightest field values.
+    pub distance_range: f32,
+    /// MSDF corner threshold in radians, as in msdfgen.
+    pub angle_threshold: f32,
+    pub kind: FontAtlasKind,
+    /// Unicode ranges to include, such as `U+0020-U+007E` or `U+20AC`.
+    pub ranges: Vec<String>,
+    /// Text file whose characters are included, relative to the recipe.
+    ///
+    /// Without `ranges` or `charset`, every glyph in the font is included.
+    pub charset: Option<PathBuf>,
+}
+
+impl Default for FontAtlasSettings {
+    fn default() -> Self {
+        Self {
+            size: 32.0,
+            padding: 2,
+            distance_range: 4.0,
+            angle_threshold: 3.0,
+            kind: FontAtlasKind::Both,
+            ranges: Vec::new(),
+            charset: None,
+        }
+    }
 }
 
 fn parse_audio_format(value: &str) -> Option<AudioFormat> {
@@ -3682,6 +4783,7 @@ impl From<BentoError> for BuildError {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::rdb::{mapped_pixels_entry, mapped_vertices_entry};
     use noren::defaults::{DEFAULT_IMAGE_ENTRY, DEFAULT_MATERIAL_ENTRY, DEFAULT_TEXTURE_ENTRY};
     use rand::{Rng, distributions::Alphanumeric};
     use std::{
@@ -3739,6 +4841,8 @@ mod tests {
                     shader_layouts: "shaders.json".into(),
                     shaders: "shaders.rdb".into(),
                 },
+                compression: RDBCompression::None,
+                zero_copy: false,
             },
             skeletons: Vec::new(),
             animations: Vec::new(),
@@ -3751,6 +4855,7 @@ mod tests {
                     layers: 1,
                     format: dashi::Format::RGBA8,
                     mip_levels: 1,
+                    mipmap: MipmapSettings::default(),
                 },
                 ImageEntry {
                     entry: "imagery/peppers".into(),
@@ -3758,6 +4863,7 @@ mod tests {
                     layers: 1,
                     format: dashi::Format::RGBA8,
                     mip_levels: 1,
+                    mipmap: MipmapSettings::default(),
                 },
             ],
             geometry: vec![GeometryEntry {
@@ -3950,6 +5056,8 @@ mod tests {
                     shader_layouts: "shaders.json".into(),
                     shaders: "shaders.rdb".into(),
                 },
+                compression: RDBCompression::None,
+                zero_copy: false,
             },
             skeletons: Vec::new(),
             animations: Vec::new(),
@@ -3961,6 +5069,7 @@ mod tests {
                 layers: 1,
                 format: dashi::Format::RGBA8,
                 mip_levels: 1,
+                mipmap: MipmapSettings::default(),
             }],
             geometry: vec![GeometryEntry {
                 entry: "geometry/quad".into(),
@@ -4090,6 +5199,8 @@ mod tests {
                     shader_layouts: "shaders.json".into(),
                     shaders: "shaders.rdb".into(),
                 },
+                compression: RDBCompression::None,
+                zero_copy: false,
             },
             skeletons: Vec::new(),
             animations: Vec::new(),
@@ -4216,6 +5327,8 @@ mod tests {
                     shader_layouts: "shaders.json".into(),
                     shaders: "shaders.rdb".into(),
                 },
+                compression: RDBCompression::None,
+                zero_copy: false,
             },
             skeletons: Vec::new(),
             animations: Vec::new(),
@@ -4227,6 +5340,7 @@ mod tests {
                 layers: 1,
                 format: dashi::Format::RGBA8,
                 mip_levels: 1,
+                mipmap: MipmapSettings::default(),
             }],
             geometry: vec![GeometryEntry {
                 entry: "geometry/quad".into(),
@@ -4275,6 +5389,8 @@ mod tests {
                     shader_layouts: "shaders.json".into(),
                     shaders: "shaders.rdb".into(),
                 },
+                compression: RDBCompression::None,
+                zero_copy: false,
             },
             skeletons: Vec::new(),
             animations: Vec::new(),
@@ -4346,6 +5462,8 @@ mod tests {
                     shader_layouts: "shaders.json".into(),
                     shaders: "shaders.rdb".into(),
                 },
+                compression: RDBCompression::None,
+                zero_copy: false,
             },
             skeletons: Vec::new(),
             animations: Vec::new(),
@@ -4394,11 +5512,283 @@ mod tests {
         )
         .unwrap();
 
+        let long_entry = "geometry/environment/forest/props/mossy_boulder_cluster/lod0/quad";
+        assert!(long_entry.len() > 63);
+        append_geometry(
+            &GeometryAppendArgs {
+                rdb: geometry_path.clone(),
+                entry: GeometryEntry {
+                    entry: long_entry.into(),
+                    file: tmp_root.join("sample_pre/gltf/quad.gltf"),
+                    mesh: Some("Quad".into()),
+                    primitive: Some(0),
+                    lods: Vec::new(),
+                },
+            },
+            &logger,
+            true,
+        )
+        .unwrap();
+
         let mut rdb = RDBFile::load(&geometry_path).unwrap();
         let original = rdb.fetch::<HostGeometry>("geometry/original").unwrap();
         assert_eq!(original.vertices.len(), 4);
         let appended = rdb.fetch::<HostGeometry>("geometry/appended").unwrap();
         assert_eq!(appended.vertices.len(), 4);
+        let long = rdb.fetch::<HostGeometry>(long_entry).unwrap();
+        assert_eq!(long.vertices.len(), 4);
+        assert!(rdb.entries().iter().any(|meta| meta.name == long_entry));
+    }
+
+    #[test]
+    fn build_spec_compression_compresses_entries() {
+        let tmp_root = temp_dir();
+        fs::create_dir_all(tmp_root.join("sample_pre/imagery")).unwrap();
+        copy_fixture(
+            "sample/sample_pre/imagery/tulips.png",
+            tmp_root.join("sample_pre/imagery/tulips.png"),
+        );
+
+        let build_path = tmp_root.join("sample_pre/norenbuild.json");
+        fs::write(
+            &build_path,
+            r#"{
+                "output": { "directory": "../db", "compression": "zstd" },
+                "imagery": [{ "entry": "imagery/tulips", "file": "imagery/tulips.png" }]
+            }"#,
+        )
+        .unwrap();
+        build_from_path(
+            &build_path,
+            BuildOptions {
+                append: false,
+                write_binaries: true,
+            },
+            &Logger::disabled(),
+        )
+        .unwrap();
+
+        let view = RDBView::load(tmp_root.join("db/imagery.rdb")).unwrap();
+        let meta = view
+            .entries()
+            .into_iter()
+            .find(|meta| meta.name == "imagery/tulips")
+            .unwrap();
+        assert_eq!(meta.compression, RDBCompression::Zstd);
+        assert!(meta.len < meta.raw_len);
+        let image = view.fetch::<HostImage>("imagery/tulips").unwrap();
+        assert!(!image.data().is_empty());
+    }
+
+    #[test]
+    fn build_spec_generates_mip_chains() {
+        let tmp_root = temp_dir();
+        fs::create_dir_all(tmp_root.join("sample_pre/imagery")).unwrap();
+        RgbaImage::from_pixel(16, 8, Rgba([40, 120, 200, 255]))
+            .save(tmp_root.join("sample_pre/imagery/flat.png"))
+            .unwrap();
+
+        let build_path = tmp_root.join("sample_pre/norenbuild.json");
+        fs::write(
+            &build_path,
+            r#"{
+                "output": { "directory": "../db" },
+                "imagery": [{
+                    "entry": "imagery/flat",
+                    "file": "imagery/flat.png",
+                    "mip_levels": 16,
+                    "mip_filter": "box",
+                    "alpha_cutoff": 0.5
+                }]
+            }"#,
+        )
+        .unwrap();
+        build_from_path(
+            &build_path,
+            BuildOptions {
+                append: false,
+                write_binaries: true,
+            },
+            &Logger::disabled(),
+        )
+        .unwrap();
+
+        let view = RDBView::load(tmp_root.join("db/imagery.rdb")).unwrap();
+        let image = view.fetch::<HostImage>("imagery/flat").unwrap();
+        assert_eq!(image.info().mip_levels, 5);
+        assert_eq!(image.mip_count(), 5);
+        assert_eq!(image.mip_offsets, vec![0, 512, 640, 672, 680]);
+        assert_eq!(image.mip_level(4), Some(&[40, 120, 200, 255][..]));
+        assert!(image.mip_level(5).is_none());
+    }
+
+    #[test]
+    fn build_spec_block_compresses_mip_chains() {
+        let tmp_root = temp_dir();
+        fs::create_dir_all(tmp_root.join("sample_pre/imagery")).unwrap();
+        RgbaImage::from_pixel(16, 8, Rgba([40, 120, 200, 255]))
+            .save(tmp_root.join("sample_pre/imagery/flat.png"))
+            .unwrap();
+
+        let build_path = tmp_root.join("sample_pre/norenbuild.json");
+        fs::write(
+            &build_path,
+            r#"{
+                "output": { "directory": "../db" },
+                "imagery": [
+                    { "entry": "imagery/color", "file": "imagery/flat.png",
+                      "format": "bc7", "mip_levels": 16 },
+                    { "entry": "imagery/normal", "file": "imagery/flat.png",
+                      "format": "bc5", "normal_map": true }
+                ]
+            }"#,
+        )
+        .unwrap();
+        build_from_path(
+            &build_path,
+            BuildOptions {
+                append: false,
+                write_binaries: true,
+            },
+            &Logger::disabled(),
+        )
+        .unwrap();
+
+        let view = RDBView::load(tmp_root.join("db/imagery.rdb")).unwrap();
+        let color = view.fetch::<HostImage>("imagery/color").unwrap();
+        assert_eq!(color.info().format, dashi::Format::BC7);
+        assert_eq!(color.info().block_format(), Some(BlockFormat::Bc7));
+        // 16x8, 8x4, 4x2, 2x1 and 1x1 need 8, 2, 1, 1 and 1 blocks.
+        assert_eq!(color.mip_offsets, vec![0, 128, 160, 176, 192]);
+        assert_eq!(color.data().len(), 208);
+
+        let normal = view.fetch::<HostImage>("imagery/normal").unwrap();
+        assert!(normal.mip_offsets.is_empty());
+        assert_eq!(normal.data().len(), BlockFormat::Bc5.level_bytes(16, 8));
+    }
+
+    #[test]
+    fn build_spec_imports_ktx2_and_dds_containers() {
+        let tmp_root = temp_dir();
+        fs::create_dir_all(tmp_root.join("sample_pre/imagery")).unwrap();
+
+        // A 2x2 VK_FORMAT_R8G8B8A8_SRGB cubemap with one level.
+        let ktx2 = |vk_format: u32| {
+            let mut bytes = vec![
+                0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+            ];
+            for value in [vk_format, 1, 2, 2, 0, 0, 6, 1, 0] {
+                bytes.extend(value.to_le_bytes());
+            }
+            bytes.resize(80, 0);
+            for value in [104u64, 96, 96] {
+                bytes.extend(value.to_le_bytes());
+            }
+            bytes.extend((0..96).map(|idx| idx as u8));
+            bytes
+        };
+        fs::write(tmp_root.join("sample_pre/imagery/sky.ktx2"), ktx2(43)).unwrap();
+
+        // An 8x8 DXT1 texture with 8x8, 4x4, 2x2 and 1x1 levels.
+        let mut dds = b"DDS ".to_vec();
+        dds.resize(128, 0);
+        for (offset, value) in [
+            (4, 124),
+            (8, 0x2_1007),
+            (12, 8),
+            (16, 8),
+            (28, 4),
+            (76, 32),
+            (80, 0x4),
+            (84, u32::from_le_bytes(*b"DXT1")),
+        ] {
+            dds[offset..offset + 4].copy_from_slice(&u32::to_le_bytes(value));
+        }
+        dds.extend([1u8; 32 + 8 + 8 + 8]);
+        fs::write(tmp_root.join("sample_pre/imagery/rock.dds"), dds).unwrap();
+
+        let build_path = tmp_root.join("sample_pre/norenbuild.json");
+        fs::write(
+            &build_path,
+            r#"{
+                "output": { "directory": "../db" },
+                "imagery": [
+                    { "entry": "imagery/sky", "file": "imagery/sky.ktx2" },
+                    { "entry": "imagery/rock", "file": "imagery/rock.dds" }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let options = || BuildOptions {
+            append: false,
+            write_binaries: true,
+        };
+        build_from_path(&build_path, options(), &Logger::disabled()).unwrap();
+
+        let view = RDBView::load(tmp_root.join("db/imagery.rdb")).unwrap();
+        let sky = view.fetch::<HostCubemap>("imagery/sky").unwrap();
+        assert_eq!(sky.info().format, dashi::Format::RGBA8);
+        assert_eq!(sky.info().layers, 6);
+        assert_eq!(sky.data().len(), 96);
+        let rock = view.fetch::<HostImage>("imagery/rock").unwrap();
+        assert_eq!(rock.info().format, dashi::Format::BC1);
+        assert_eq!(rock.info().mip_levels, 4);
+        assert_eq!(rock.mip_offsets, vec![0, 32, 40, 48]);
+
+        // VK_FORMAT_BC6H_UFLOAT_BLOCK cannot be represented by dashi.
+        fs::write(tmp_root.join("sample_pre/imagery/sky.ktx2"), ktx2(143)).unwrap();
+        let err = build_from_path(&build_path, options(), &Logger::disabled()).unwrap_err();
+        assert!(
+            err.to_string().contains("no dashi::Format equivalent"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn build_spec_zero_copy_writes_pod_slices() {
+        let tmp_root = temp_dir();
+        fs::create_dir_all(tmp_root.join("sample_pre/imagery")).unwrap();
+        fs::create_dir_all(tmp_root.join("sample_pre/gltf")).unwrap();
+        copy_fixture(
+            "sample/sample_pre/imagery/tulips.png",
+            tmp_root.join("sample_pre/imagery/tulips.png"),
+        );
+        copy_fixture(
+            "sample/sample_pre/gltf/quad.gltf",
+            tmp_root.join("sample_pre/gltf/quad.gltf"),
+        );
+
+        let build_path = tmp_root.join("sample_pre/norenbuild.json");
+        fs::write(
+            &build_path,
+            r#"{
+                "output": { "directory": "../db", "zero_copy": true },
+                "imagery": [{ "entry": "imagery/tulips", "file": "imagery/tulips.png" }],
+                "geometry": [{ "entry": "geometry/quad", "file": "gltf/quad.gltf", "mesh": "Quad" }]
+            }"#,
+        )
+        .unwrap();
+        build_from_path(
+            &build_path,
+            BuildOptions {
+                append: false,
+                write_binaries: true,
+            },
+            &Logger::disabled(),
+        )
+        .unwrap();
+
+        let geometry = RDBView::load(tmp_root.join("db/geometry.rdb")).unwrap();
+        let vertices = geometry
+            .fetch_pod_slice::<Vertex>(&mapped_vertices_entry("geometry/quad", None))
+            .unwrap();
+        assert_eq!(vertices.len(), 4);
+
+        let imagery = RDBView::load(tmp_root.join("db/imagery.rdb")).unwrap();
+        let pixels = imagery
+            .fetch_pod_slice::<u8>(&mapped_pixels_entry("imagery/tulips"))
+            .unwrap();
+        assert!(!pixels.is_empty());
     }
 
     #[test]
@@ -4421,6 +5811,384 @@ mod tests {
         );
     }
 
+    #[test]
+    fn simple_skin_animation_bends_second_joint() {
+        let base = Path::new("sample/sample_pre");
+        let file = PathBuf::from("gltf/SimpleSkin.gltf");
+        let skeleton = load_skeleton(
+            base,
+            &SkeletonEntry {
+                entry: "skeletons/simple_skin".into(),
+                file: file.clone(),
+                skin: None,
+            },
+        )
+        .unwrap();
+        let clip = load_animation(
+            base,
+            &AnimationEntry {
+                entry: "animations/simple_skin".into(),
+                file,
+                animation: None,
+            },
+        )
+        .unwrap();
+
+        let rest = clip.sample(0.0, &skeleton);
+        for skinning in &rest.skinning {
+            assert!(skinning.abs_diff_eq(glam::Mat4::IDENTITY, 1e-5));
+        }
+
+        // One second in, the upper joint is rotated 90 degrees about Z around (0, 1, 0).
+        let tip = glam::Vec3::new(0.0, 2.0, 0.0);
+        let bent = clip.sample(1.0, &skeleton);
+        assert!(
+            bent.skinning[1]
+                .transform_point3(tip)
+                .abs_diff_eq(glam::Vec3::new(-1.0, 1.0, 0.0), 1e-3)
+        );
+        assert!(
+            bent.global[1]
+                .transform_point3(glam::Vec3::ZERO)
+                .abs_diff_eq(glam::Vec3::Y, 1e-5)
+        );
+
+        // Halfway to the first key, slerp gives 22.5 degrees.
+        let quarter = clip.sample(0.25, &skeleton).local[1].rotation;
+        assert!((quarter.z - (22.5f32.to_radians() / 2.0).sin()).abs() < 1e-3);
+
+        let looped = clip.sample(clip.duration() + 1.0, &skeleton);
+        assert!(looped.skinning[1].abs_diff_eq(bent.skinning[1], 1e-3));
+        let clamped = clip.sample_with(
+            clip.duration() + 1.0,
+            &skeleton,
+            crate::rdb::AnimationWrap::Clamp,
+        );
+        assert!(clamped.skinning[1].abs_diff_eq(glam::Mat4::IDENTITY, 1e-3));
+    }
+
+    #[test]
+    fn append_audio_records_loop_volume_and_cues() {
+        let args = [
+            "--rdb",
+            "audio.rdb",
+            "--entry",
+            "audio/beep",
+            "--audio",
+            "audio/beep.wav",
+            "--loop-start",
+            "16",
+            "--volume",
+            "0.25",
+            "--category",
+            "sfx",
+            "--cue",
+            "hit=8",
+        ];
+        let parsed =
+            parse_audio_append(args.into_iter().map(String::from)).expect("valid audio args");
+
+        let clip = load_audio(Path::new("sample/sample_pre"), &parsed.entry).unwrap();
+        assert!(clip.duration_secs > 0.0);
+        assert_eq!(
+            clip.looping,
+            Some(AudioLoop {
+                start: 16,
+                end: None
+            })
+        );
+        assert_eq!(clip.volume, 0.25);
+        assert_eq!(clip.category.as_deref(), Some("sfx"));
+        assert_eq!(clip.cue("hit").map(|cue| cue.frame), Some(8));
+
+        let mut past_end = parsed.entry;
+        past_end.loop_end = Some(u64::MAX);
+        assert!(load_audio(Path::new("sample/sample_pre"), &past_end).is_err());
+    }
+
+    #[test]
+    fn font_entries_generate_distinct_distance_field_atlases() {
+        let mut entry: FontEntry = serde_json::from_str(
+            r#"{"entry": "fonts/default", "file": "fonts/DejaVuSans.ttf", "size": 12, "distance_range": 6}"#,
+        )
+        .expect("font entry json");
+        assert_eq!(entry.atlas.kind, FontAtlasKind::Both);
+        assert_eq!(entry.atlas.angle_threshold, 3.0);
+
+        let base = Path::new("sample/sample_pre");
+        let output = build_font_atlases(base, std::slice::from_ref(&entry), &Logger::disabled())
+            .expect("font atlases");
+        let msdf = &output.msdf_layouts["msdf_fonts/default"];
+        let sdf = &output.sdf_layouts["sdf_fonts/default"];
+        assert_eq!(msdf.image, "imagery/fonts/default_msdf");
+        assert_eq!(sdf.image, "imagery/fonts/default_sdf");
+        assert_eq!((msdf.size, msdf.distance_range), (12.0, 6.0));
+        assert_eq!(output.images.len(), 2);
+
+        let images: HashMap<_, _> = output.images.into_iter().collect();
+        let glyph = msdf
+            .glyphs
+            .iter()
+            .find(|glyph| glyph.unicode == 'I' as u32)
+            .expect("glyph I");
+        let plane = glyph.plane_bounds.as_ref().expect("plane bounds");
+        assert!(plane.left <= -2.0 && plane.bottom <= -2.0);
+
+        // The cell's corner lies outside the outline, and the MSDF median agrees
+        // with the true distance about which texels are inside.
+        let atlas = glyph.atlas_bounds.as_ref().expect("atlas bounds");
+        let sdf_image = &images[&sdf.image];
+        let msdf_image = &images[&msdf.image];
+        let texel = |image: &HostImage, x: u32, y: u32| {
+            let offset = ((y * image.info.dim[0] + x) * 4) as usize;
+            image.data[offset..offset + 4].to_vec()
+        };
+        assert!(texel(sdf_image, atlas.left as u32, atlas.bottom as u32)[0] < 127);
+        let mut inside = 0;
+        for y in atlas.bottom as u32..atlas.top as u32 {
+            for x in atlas.left as u32..atlas.right as u32 {
+                let distance = texel(sdf_image, x, y);
+                assert!(distance.iter().all(|value| *value == distance[0]));
+                let mut channels = texel(msdf_image, x, y);
+                assert_eq!(channels[3], distance[0]);
+                channels.truncate(3);
+                channels.sort();
+                assert_eq!(channels[1] > 127, distance[0] > 127);
+                inside += usize::from(distance[0] > 127);
+            }
+        }
+        assert!(inside > 0);
+        assert_ne!(sdf_image.data, msdf_image.data);
+
+        entry.atlas.size = 0.0;
+        assert!(build_font_atlases(base, &[entry], &Logger::disabled()).is_err());
+    }
+
+    #[test]
+    fn font_ranges_and_charset_select_glyphs_and_kerning() {
+        let tmp_root = temp_dir();
+        let charset = tmp_root.join("charset.txt");
+        fs::write(&charset, "V€\n").unwrap();
+
+        let mut entry: FontEntry = serde_json::from_value(serde_json::json!({
+            "entry": "fonts/default",
+            "file": "fonts/DejaVuSans.ttf",
+            "size": 16,
+            "kind": "msdf",
+            "ranges": ["U+0041", "0x61-0x63"],
+            "charset": charset,
+        }))
+        .expect("font entry json");
+
+        let base = Path::new("sample/sample_pre");
+        let output = build_font_atlases(base, std::slice::from_ref(&entry), &Logger::disabled())
+            .expect("font atlases");
+        assert!(output.sdf_layouts.is_empty());
+        let msdf = &output.msdf_layouts["msdf_fonts/default"];
+        let unicodes: Vec<u32> = msdf.glyphs.iter().map(|glyph| glyph.unicode).collect();
+        assert_eq!(unicodes, vec![0x41, 0x56, 0x61, 0x62, 0x63, 0x20AC]);
+
+        assert!(
+            msdf.kerning
+                .windows(2)
+                .all(|pair| (pair[0].left, pair[0].right) < (pair[1].left, pair[1].right))
+        );
+        let av = msdf
+            .kerning
+            .iter()
+            .find(|pair| pair.left == 'A' as u32 && pair.right == 'V' as u32)
+            .expect("AV kerning");
+        assert!(av.advance < 0.0);
+
+        let layout = crate::meta::layout_text(msdf, "AV", None);
+        let unkerned = msdf.glyphs[0].advance + msdf.glyphs[1].advance;
+        assert!((layout.width - (unkerned + av.advance)).abs() < 1e-3);
+
+        entry.atlas.ranges = vec!["U+0041-U+0030".to_string()];
+        assert!(build_font_atlases(base, &[entry], &Logger::disabled()).is_err());
+    }
+
+    #[test]
+    fn verify_flags_corrupted_rdb() {
+        let tmp_root = temp_dir();
+        let path = tmp_root.join("verify.rdb");
+
+        let mut rdb = RDBFile::new();
+        rdb.add("audio/beep", &vec![1u8; 64]).unwrap();
+        rdb.save(&path).unwrap();
+
+        let args = VerifyArgs {
+            files: vec![path.clone()],
+        };
+        run_verify(&args, &Logger::disabled()).expect("fresh file verifies");
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        fs::write(&path, &bytes).unwrap();
+
+        let err = run_verify(&args, &Logger::disabled()).expect_err("corruption detected");
+        assert!(err.to_string().contains("verify.rdb"));
+    }
+
+    #[test]
+    fn bundle_opens_as_a_database() {
+        let tmp_root = temp_dir();
+        let db_root = tmp_root.join("db");
+        fs::create_dir_all(&db_root).unwrap();
+
+        let (_, quad) = default_primitives().into_iter().next().unwrap();
+        let mut geometry = RDBFile::new();
+        geometry.add("geometry/bundled", &quad).unwrap();
+        geometry.save(db_root.join("geometry.rdb")).unwrap();
+        fs::write(db_root.join("textures.json"), "{}").unwrap();
+
+        let out = tmp_root.join("db.nrb");
+        let args = BundleArgs {
+            base_dir: db_root.clone(),
+            layout: None,
+            out: out.clone(),
+        };
+        run_bundle(&args, &Logger::disabled()).expect("bundle");
+
+        let bundle = crate::DBBundle::open(&out).unwrap();
+        assert!(bundle.contains(BUNDLE_LAYOUT_MEMBER));
+        assert!(bundle.contains("geometry.rdb"));
+        assert!(bundle.contains("textures.json"));
+        assert!(!bundle.contains("audio.rdb"));
+
+        fs::remove_dir_all(&db_root).unwrap();
+        let out_str = out.to_str().unwrap();
+        let info = crate::DBInfo {
+            base_dir: "",
+            layout_file: None,
+            pooled_geometry_uploads: false,
+            bundle: Some(out_str),
+            overlays: &[],
+        };
+        let mut db = crate::DB::new(&info).expect("open bundle");
+        let fetched = db
+            .geometry_mut()
+            .fetch_raw_geometry("geometry/bundled")
+            .expect("fetch bundled geometry");
+        assert_eq!(fetched.vertices.len(), quad.vertices.len());
+    }
+
+    #[test]
+    fn diff_patch_overlays_base_database() {
+        let tmp_root = temp_dir();
+        let clip =
+            |name: &str, byte: u8| AudioClip::new(name.into(), AudioFormat::Wav, vec![byte; 64]);
+        let write_build = |root: &Path, clips: &[AudioClip], textures: &str| {
+            fs::create_dir_all(root).unwrap();
+            let mut rdb = RDBFile::new();
+            for clip in clips {
+                rdb.add(&clip.name, clip).unwrap();
+            }
+            rdb.save(root.join("audio.rdb")).unwrap();
+            fs::write(root.join("textures.json"), textures).unwrap();
+        };
+
+        let old = tmp_root.join("v1");
+        let new = tmp_root.join("v2");
+        write_build(
+            &old,
+            &[
+                clip("audio/kept", 1),
+                clip("audio/tweaked", 2),
+                clip("audio/cut", 3),
+            ],
+            r#"{"textures": {"tex/kept": {"image": "imagery/a"}, "tex/moved": {"image": "imagery/b"}}}"#,
+        );
+        write_build(
+            &new,
+            &[
+                clip("audio/kept", 1),
+                clip("audio/tweaked", 20),
+                clip("audio/dlc", 4),
+            ],
+            r#"{"textures": {"tex/kept": {"image": "imagery/a"}, "tex/moved": {"image": "imagery/c"}}}"#,
+        );
+
+        let patch = tmp_root.join("patch");
+        let args = DiffArgs {
+            old: old.clone(),
+            new,
+            layout: None,
+            out: patch.clone(),
+        };
+        run_diff(&args, &Logger::disabled()).expect("diff");
+
+        let view = RDBView::load(patch.join("audio.rdb")).unwrap();
+        let mut names: Vec<String> = view.entries().into_iter().map(|meta| meta.name).collect();
+        names.sort();
+        assert_eq!(names, vec!["audio/dlc", "audio/tweaked"]);
+        assert!(view.verify().is_ok());
+        let textures: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(patch.join("textures.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            textures,
+            serde_json::json!({"textures": {"tex/moved": {"image": "imagery/c"}}})
+        );
+
+        let old_str = old.to_str().unwrap();
+        let patch_str = patch.to_str().unwrap();
+        let info = crate::DBInfo {
+            base_dir: old_str,
+            layout_file: None,
+            pooled_geometry_uploads: false,
+            bundle: None,
+            overlays: &[patch_str],
+        };
+        let mut db = crate::DB::new(&info).expect("open layered db");
+        let audio = db.audio_mut();
+        assert_eq!(audio.fetch_clip("audio/kept").unwrap().data, vec![1; 64]);
+        assert_eq!(
+            audio.fetch_clip("audio/tweaked").unwrap().data,
+            vec![20; 64]
+        );
+        assert_eq!(audio.fetch_clip("audio/dlc").unwrap().data, vec![4; 64]);
+        assert_eq!(audio.fetch_clip("audio/cut").unwrap().data, vec![3; 64]);
+        let clips = db.enumerate_audio_clips();
+        for name in ["audio/kept", "audio/tweaked", "audio/cut", "audio/dlc"] {
+            assert_eq!(clips.iter().filter(|clip| *clip == name).count(), 1);
+        }
+        let textures = db.enumerate_textures();
+        assert!(textures.contains(&"tex/kept".to_string()));
+        assert!(textures.contains(&"tex/moved".to_string()));
+    }
+
+    #[test]
+    fn compact_reclaims_appended_tombstones() {
+        let tmp_root = temp_dir();
+        let path = tmp_root.join("compact.rdb");
+
+        let mut rdb = RDBFile::new();
+        rdb.add("terrain/chunk", &vec![1u8; 512]).unwrap();
+        rdb.save(&path).unwrap();
+
+        let mut rdb = RDBFile::load(&path).unwrap();
+        rdb.upsert("terrain/chunk", &vec![2u8; 512]).unwrap();
+        rdb.append(&path).unwrap();
+        rdb.unmap();
+        let grown = fs::metadata(&path).unwrap().len();
+        assert!(RDBView::load(&path).unwrap().dead_bytes() > 0);
+
+        let args = CompactArgs {
+            files: vec![path.clone()],
+        };
+        run_compact(&args, &Logger::disabled()).expect("compact");
+
+        assert!(fs::metadata(&path).unwrap().len() < grown);
+        let view = RDBView::load(&path).unwrap();
+        assert_eq!(view.dead_bytes(), 0);
+        assert_eq!(
+            view.fetch::<Vec<u8>>("terrain/chunk").unwrap(),
+            vec![2u8; 512]
+        );
+    }
+
     fn copy_fixture(src: &str, dst: PathBuf) {
         if let Some(parent) = dst.parent() {
             fs::create_dir_all(parent).unwrap();
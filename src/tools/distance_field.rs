@@ -0,0 +1,572 @@
+use glam::Vec2;
+use ttf_parser::{Face, GlyphId, OutlineBuilder};
+
+const RED: u8 = 0b001;
+const GREEN: u8 = 0b010;
+const BLUE: u8 = 0b100;
+const WHITE: u8 = RED | GREEN | BLUE;
+const CYAN: u8 = GREEN | BLUE;
+const MAGENTA: u8 = RED | BLUE;
+const YELLOW: u8 = RED | GREEN;
+const CHANNELS: [u8; 3] = [RED, GREEN, BLUE];
+const SPLINE_COLORS: [u8; 3] = [CYAN, MAGENTA, YELLOW];
+
+#[derive(Debug, Clone, Copy)]
+enum Segment {
+    Line(Vec2, Vec2),
+    Quad(Vec2, Vec2, Vec2),
+    Cubic(Vec2, Vec2, Vec2, Vec2),
+}
+
+impl Segment {
+    fn start_direction(&self) -> Vec2 {
+        let candidates = match *self {
+            Segment::Line(a, b) => [b - a, b - a, b - a],
+            Segment::Quad(a, b, c) => [b - a, c - a, c - a],
+            Segment::Cubic(a, b, c, d) => [b - a, c - a, d - a],
+        };
+        first_nonzero(candidates)
+    }
+
+    fn end_direction(&self) -> Vec2 {
+        let candidates = match *self {
+            Segment::Line(a, b) => [b - a, b - a, b - a],
+            Segment::Quad(a, b, c) => [c - b, c - a, c - a],
+            Segment::Cubic(a, b, c, d) => [d - c, d - b, d - a],
+        };
+        first_nonzero(candidates)
+    }
+
+    fn point(&self, t: f32) -> Vec2 {
+        let s = 1.0 - t;
+        match *self {
+            Segment::Line(a, b) => a.lerp(b, t),
+            Segment::Quad(a, b, c) => a * (s * s) + b * (2.0 * s * t) + c * (t * t),
+            Segment::Cubic(a, b, c, d) => {
+                a * (s * s * s) + b * (3.0 * s * s * t) + c * (3.0 * s * t * t) + d * (t * t * t)
+            }
+        }
+    }
+
+    /// Appends points along the segment, excluding its start, roughly one
+    /// pixel apart.
+    fn flatten(&self, points: &mut Vec<Vec2>) {
+        let steps = match *self {
+            Segment::Line(..) => 1,
+            Segment::Quad(a, b, c) => subdivisions(a.distance(b) + b.distance(c)),
+            Segment::Cubic(a, b, c, d) => {
+                subdivisions(a.distance(b) + b.distance(c) + c.distance(d))
+            }
+        };
+        for step in 1..=steps {
+            let point = self.point(step as f32 / steps as f32);
+            if points.last() != Some(&point) {
+                points.push(point);
+            }
+        }
+    }
+}
+
+fn first_nonzero(candidates: [Vec2; 3]) -> Vec2 {
+    candidates
+        .into_iter()
+        .find(|direction| *direction != Vec2::ZERO)
+        .unwrap_or(Vec2::ZERO)
+        .normalize_or_zero()
+}
+
+fn subdivisions(length: f32) -> usize {
+    (length.ceil() as usize).clamp(1, 32)
+}
+
+/// An outline edge flattened into a polyline.
+#[derive(Debug, Clone)]
+struct Edge {
+    points: Vec<Vec2>,
+    start_direction: Vec2,
+    end_direction: Vec2,
+    color: u8,
+}
+
+/// A glyph outline in pixel units, ready to render into a distance field.
+#[derive(Debug, Clone, Default)]
+pub struct GlyphShape {
+    contours: Vec<Vec<Edge>>,
+}
+
+impl GlyphShape {
+    /// Reads a glyph outline from `face`, scaled so one em spans `size` pixels.
+    ///
+    /// Returns `None` for glyphs without an outline, such as spaces.
+    pub fn from_face(face: &Face<'_>, glyph: u16, size: f32) -> Option<Self> {
+        let mut builder = ShapeBuilder::new(size / f32::from(face.units_per_em().max(1)));
+        face.outline_glyph(GlyphId(glyph), &mut builder)?;
+        let shape = builder.finish();
+        (!shape.contours.is_empty()).then_some(shape)
+    }
+
+    /// Returns the bottom-left and top-right corners of the outline.
+    pub fn bounds(&self) -> Option<(Vec2, Vec2)> {
+        self.points().fold(None, |bounds, point| match bounds {
+            None => Some((point, point)),
+            Some((min, max)) => Some((min.min(point), max.max(point))),
+        })
+    }
+
+    /// Assigns MSDF channels to edges so that the edges meeting at a corner
+    /// never share more than one channel.
+    ///
+    /// As in msdfgen, a vertex counts as a corner when the outline turns there
+    /// by more than `pi - angle_threshold` radians.
+    pub fn color_edges(&mut self, angle_threshold: f32) {
+        let cross_threshold = angle_threshold.sin();
+        for contour in &mut self.contours {
+            let count = contour.len();
+            let corners: Vec<usize> = (0..count)
+                .filter(|&idx| {
+                    let previous = contour[(idx + count - 1) % count].end_direction;
+                    let next = contour[idx].start_direction;
+                    previous.dot(next) <= 0.0 || previous.perp_dot(next).abs() > cross_threshold
+                })
+                .collect();
+
+            match corners.as_slice() {
+                [] => contour.iter_mut().for_each(|edge| edge.color = WHITE),
+                [corner] => {
+                    // A teardrop: split the contour into three colored runs.
+                    let colors = [MAGENTA, WHITE, YELLOW];
+                    for offset in 0..count {
+                        let run = if count > 1 {
+                            (2.875 * offset as f32 / (count - 1) as f32 + 0.0625) as usize
+                        } else {
+                            1
+                        };
+                        contour[(corner + offset) % count].color = colors[run.min(2)];
+                    }
+                }
+                _ => {
+                    let splines = corners.len();
+                    for (spline, &start) in corners.iter().enumerate() {
+                        let end = corners[(spline + 1) % splines];
+                        let mut color = SPLINE_COLORS[spline % 3];
+                        if spline == splines - 1 && spline % 3 == 0 {
+                            // Keep the closing spline distinct from the first.
+                            color = SPLINE_COLORS[1];
+                        }
+                        let mut idx = start;
+                        loop {
+                            contour[idx].color = color;
+                            idx = (idx + 1) % count;
+                            if idx == end {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Renders a `width` x `height` RGBA8 cell, rows top-down.
+    ///
+    /// RGB hold the multi-channel field, whose median rebuilds sharp corners,
+    /// and alpha holds the true signed distance, so an SDF atlas can use alpha
+    /// alone. Call [`GlyphShape::color_edges`] first.
+    ///
+    /// `origin` is the pixel-space position of the cell's bottom-left corner and
+    /// `range` the distance in pixels between the darkest and brightest values.
+    /// Inside the outline is brighter than 0.5.
+    pub fn render(&self, origin: Vec2, width: u32, height: u32, range: f32) -> Vec<u8> {
+        let (width, height) = (width as usize, height as usize);
+        let center = |x: usize, y: usize| {
+            Vec2::new(
+                origin.x + x as f32 + 0.5,
+                origin.y + (height - 1 - y) as f32 + 0.5,
+            )
+        };
+        let orientation = if self.signed_area() >= 0.0 { 1.0 } else { -1.0 };
+        let reach = range * 0.5 + 1.0;
+
+        let mut samples = vec![Sample::FAR; width * height];
+        for edge in self.contours.iter().flatten() {
+            let last = edge.points.len() - 2;
+            for (idx, pair) in edge.points.windows(2).enumerate() {
+                let (a, b) = (pair[0], pair[1]);
+                let min = a.min(b) - Vec2::splat(reach) - origin;
+                let max = a.max(b) + Vec2::splat(reach) - origin;
+                let x_range = pixel_span(min.x, max.x, width);
+                let row_range = pixel_span(min.y, max.y, height);
+                let line = Line::new(a, b, idx == 0, idx == last);
+                for row in row_range {
+                    let y = height - 1 - row;
+                    for x in x_range.clone() {
+                        let hit = line.hit(center(x, y));
+                        samples[y * width + x].add(hit, orientation, edge.color);
+                    }
+                }
+            }
+        }
+
+        let encode =
+            |distance: f32| ((0.5 + distance / range).clamp(0.0, 1.0) * 255.0).round() as u8;
+        let mut pixels = vec![0u8; width * height * 4];
+        for y in 0..height {
+            let mut crossings = self.crossings(center(0, y).y);
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut crossings = crossings.into_iter().peekable();
+            let mut winding = 0;
+            for x in 0..width {
+                let point = center(x, y);
+                while let Some((_, direction)) = crossings.next_if(|(cx, _)| *cx < point.x) {
+                    winding += direction;
+                }
+
+                let sample = &samples[y * width + x];
+                let inside = winding != 0;
+                let distance = if inside {
+                    sample.distance
+                } else {
+                    -sample.distance
+                };
+                let mut channels = sample.channels.map(|candidate| {
+                    if candidate.distance.is_finite() {
+                        candidate.pseudo
+                    } else {
+                        distance
+                    }
+                });
+                // Fall back to the true distance where the channels would put
+                // the pixel on the wrong side of the outline.
+                if (median(channels) > 0.0) != inside {
+                    channels = [distance; 3];
+                }
+                let [r, g, b] = channels.map(encode);
+                let texel = [r, g, b, encode(distance)];
+                let offset = (y * width + x) * 4;
+                pixels[offset..offset + 4].copy_from_slice(&texel);
+            }
+        }
+        pixels
+    }
+
+    fn points(&self) -> impl Iterator<Item = Vec2> + '_ {
+        self.contours
+            .iter()
+            .flatten()
+            .flat_map(|edge| edge.points.iter().copied())
+    }
+
+    fn segments(&self) -> impl Iterator<Item = (Vec2, Vec2)> + '_ {
+        self.contours
+            .iter()
+            .flatten()
+            .flat_map(|edge| edge.points.windows(2).map(|pair| (pair[0], pair[1])))
+    }
+
+    fn signed_area(&self) -> f32 {
+        self.segments().map(|(a, b)| a.perp_dot(b)).sum::<f32>() * 0.5
+    }
+
+    /// Returns where a horizontal line at `y` crosses the outline, with the
+    /// winding direction of each crossing.
+    fn crossings(&self, y: f32) -> Vec<(f32, i32)> {
+        self.segments()
+            .filter(|(a, b)| (a.y <= y) != (b.y <= y))
+            .map(|(a, b)| {
+                let x = a.x + (y - a.y) / (b.y - a.y) * (b.x - a.x);
+                (x, if b.y > a.y { 1 } else { -1 })
+            })
+            .collect()
+    }
+}
+
+/// Returns the pixel indices whose centers fall within `[min, max]`.
+fn pixel_span(min: f32, max: f32, len: usize) -> std::ops::Range<usize> {
+    let start = (min - 0.5).ceil().max(0.0) as usize;
+    let end = ((max - 0.5).floor() + 1.0).clamp(0.0, len as f32) as usize;
+    start.min(end)..end
+}
+
+fn median([a, b, c]: [f32; 3]) -> f32 {
+    a.min(b).max(a.max(b).min(c))
+}
+
+/// One flattened segment of an edge.
+struct Line {
+    start: Vec2,
+    delta: Vec2,
+    direction: Vec2,
+    inverse_length_squared: f32,
+    /// Whether pseudo-distances extend past the start or end, which only
+    /// happens at the ends of the original edge.
+    extend_start: bool,
+    extend_end: bool,
+}
+
+impl Line {
+    fn new(start: Vec2, end: Vec2, extend_start: bool, extend_end: bool) -> Self {
+        let delta = end - start;
+        Self {
+            start,
+            delta,
+            direction: delta.normalize_or_zero(),
+            inverse_length_squared: delta.length_squared().recip(),
+            extend_start,
+            extend_end,
+        }
+    }
+
+    fn hit(&self, point: Vec2) -> SegmentHit {
+        let relative = point - self.start;
+        let t = relative.dot(self.delta) * self.inverse_length_squared;
+        let offset = relative - self.delta * t.clamp(0.0, 1.0);
+        let distance = offset.length();
+        let side = self.direction.perp_dot(relative);
+        let orthogonality = if distance > 0.0 {
+            (self.direction.perp_dot(offset) / distance).abs()
+        } else {
+            1.0
+        };
+        let pseudo = if (t < 0.0 && self.extend_start) || (t > 1.0 && self.extend_end) {
+            side.abs()
+        } else {
+            distance
+        };
+        SegmentHit {
+            distance,
+            orthogonality,
+            pseudo,
+            side,
+        }
+    }
+}
+
+struct SegmentHit {
+    distance: f32,
+    orthogonality: f32,
+    pseudo: f32,
+    side: f32,
+}
+
+#[derive(Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    orthogonality: f32,
+    /// Signed pseudo-distance, positive inside.
+    pseudo: f32,
+}
+
+#[derive(Clone, Copy)]
+struct Sample {
+    distance: f32,
+    channels: [Candidate; 3],
+}
+
+impl Sample {
+    const FAR: Self = Self {
+        distance: f32::INFINITY,
+        channels: [Candidate {
+            distance: f32::INFINITY,
+            orthogonality: 0.0,
+            pseudo: 0.0,
+        }; 3],
+    };
+
+    fn add(&mut self, hit: SegmentHit, orientation: f32, color: u8) {
+        self.distance = self.distance.min(hit.distance);
+        for (candidate, channel) in self.channels.iter_mut().zip(CHANNELS) {
+            if color & channel == 0 {
+                continue;
+            }
+            let closer = hit.distance < candidate.distance - 1e-4
+                || ((hit.distance - candidate.distance).abs() <= 1e-4
+                    && hit.orthogonality > candidate.orthogonality);
+            if closer {
+                let sign = if hit.side * orientation >= 0.0 {
+                    1.0
+                } else {
+                    -1.0
+                };
+                *candidate = Candidate {
+                    distance: hit.distance,
+                    orthogonality: hit.orthogonality,
+                    pseudo: hit.pseudo * sign,
+                };
+            }
+        }
+    }
+}
+
+/// Collects outline segments from a font face into a [`GlyphShape`].
+struct ShapeBuilder {
+    scale: f32,
+    shape: GlyphShape,
+    contour: Vec<Segment>,
+    start: Vec2,
+    cursor: Vec2,
+}
+
+impl ShapeBuilder {
+    fn new(scale: f32) -> Self {
+        Self {
+            scale,
+            shape: GlyphShape::default(),
+            contour: Vec::new(),
+            start: Vec2::ZERO,
+            cursor: Vec2::ZERO,
+        }
+    }
+
+    fn push(&mut self, segment: Segment, end: Vec2) {
+        self.contour.push(segment);
+        self.cursor = end;
+    }
+
+    fn finish(mut self) -> GlyphShape {
+        self.close();
+        self.shape
+    }
+}
+
+impl OutlineBuilder for ShapeBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.close();
+        self.start = Vec2::new(x, y) * self.scale;
+        self.cursor = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let end = Vec2::new(x, y) * self.scale;
+        self.push(Segment::Line(self.cursor, end), end);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let end = Vec2::new(x, y) * self.scale;
+        let control = Vec2::new(x1, y1) * self.scale;
+        self.push(Segment::Quad(self.cursor, control, end), end);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let end = Vec2::new(x, y) * self.scale;
+        let first = Vec2::new(x1, y1) * self.scale;
+        let second = Vec2::new(x2, y2) * self.scale;
+        self.push(Segment::Cubic(self.cursor, first, second, end), end);
+    }
+
+    fn close(&mut self) {
+        if self.contour.is_empty() {
+            return;
+        }
+        if self.cursor != self.start {
+            self.push(Segment::Line(self.cursor, self.start), self.start);
+        }
+
+        let edges: Vec<Edge> = self
+            .contour
+            .drain(..)
+            .filter_map(|segment| {
+                let start = segment.point(0.0);
+                let mut points = vec![start];
+                segment.flatten(&mut points);
+                (points.len() > 1).then(|| Edge {
+                    points,
+                    start_direction: segment.start_direction(),
+                    end_direction: segment.end_direction(),
+                    color: WHITE,
+                })
+            })
+            .collect();
+        if !edges.is_empty() {
+            self.shape.contours.push(edges);
+        }
+        self.cursor = self.start;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::Vec2;
+    use ttf_parser::OutlineBuilder;
+
+    use super::{CYAN, GlyphShape, MAGENTA, ShapeBuilder, WHITE, YELLOW, median};
+
+    /// A 10px square from (0, 0) to (10, 10), wound counter-clockwise.
+    fn square() -> GlyphShape {
+        let mut builder = ShapeBuilder::new(1.0);
+        builder.move_to(0.0, 0.0);
+        builder.line_to(10.0, 0.0);
+        builder.line_to(10.0, 10.0);
+        builder.line_to(0.0, 10.0);
+        builder.close();
+        builder.finish()
+    }
+
+    fn texel(pixels: &[u8], width: u32, x: u32, y: u32) -> [u8; 4] {
+        let offset = ((y * width + x) * 4) as usize;
+        pixels[offset..offset + 4].try_into().unwrap()
+    }
+
+    #[test]
+    fn colors_corners_and_smooth_contours() {
+        let mut shape = square();
+        shape.color_edges(3.0);
+        let colors: Vec<u8> = shape.contours[0].iter().map(|edge| edge.color).collect();
+        for (idx, color) in colors.iter().enumerate() {
+            let next = colors[(idx + 1) % colors.len()];
+            assert_ne!(*color, next);
+            assert_eq!((color & next).count_ones(), 1);
+        }
+        assert!(
+            colors
+                .iter()
+                .all(|color| [CYAN, MAGENTA, YELLOW].contains(color))
+        );
+
+        // A circle from four quadratic arcs has no corners.
+        let mut builder = ShapeBuilder::new(1.0);
+        builder.move_to(10.0, 0.0);
+        builder.quad_to(10.0, 10.0, 0.0, 10.0);
+        builder.quad_to(-10.0, 10.0, -10.0, 0.0);
+        builder.quad_to(-10.0, -10.0, 0.0, -10.0);
+        builder.quad_to(10.0, -10.0, 10.0, 0.0);
+        let mut circle = builder.finish();
+        circle.color_edges(3.0);
+        assert!(circle.contours[0].iter().all(|edge| edge.color == WHITE));
+    }
+
+    #[test]
+    fn renders_signed_distances_around_square() {
+        let mut shape = square();
+        let (min, max) = shape.bounds().unwrap();
+        assert_eq!((min, max), (Vec2::ZERO, Vec2::splat(10.0)));
+        shape.color_edges(3.0);
+
+        // A 16px cell with 3px of margin around the square and a 4px range.
+        let origin = Vec2::splat(-3.0);
+        let pixels = shape.render(origin, 16, 16, 4.0);
+        assert_eq!(pixels.len(), 16 * 16 * 4);
+        assert_eq!(texel(&pixels, 16, 8, 8), [255; 4]);
+        assert_eq!(texel(&pixels, 16, 0, 0), [0; 4]);
+        // Pixel (3, 8) is centered half a pixel inside the left edge.
+        assert_eq!(texel(&pixels, 16, 3, 8)[3], 159);
+        assert_eq!(texel(&pixels, 16, 2, 8)[3], 96);
+
+        for y in 0..16 {
+            for x in 0..16 {
+                let [r, g, b, a] = texel(&pixels, 16, x, y);
+                let inside = (3..13).contains(&x) && (3..13).contains(&y);
+                assert_eq!(
+                    median([r, g, b].map(f32::from)) > 127.5,
+                    inside,
+                    "({x}, {y})"
+                );
+                assert_eq!(a > 127, inside);
+            }
+        }
+        // Diagonally past the corner, pseudo-distances keep one channel near
+        // the edge value so the median rebuilds a sharp corner.
+        let [r, g, b, a] = texel(&pixels, 16, 1, 1);
+        assert!(a < 64);
+        assert!([r, g, b].iter().any(|channel| *channel > a));
+    }
+}
@@ -0,0 +1,418 @@
+use serde::{Deserialize, Serialize};
+
+/// Filter used to shrink one mip level into the next.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MipFilter {
+    /// Averages each 2x2 block; cheap but slightly blurry.
+    Box,
+    /// A Kaiser-windowed sinc that keeps more detail in smaller levels.
+    #[default]
+    Kaiser,
+}
+
+/// How `dbgen` builds the mip chain of an image.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MipmapSettings {
+    pub mip_filter: MipFilter,
+    /// Whether RGB holds sRGB values that should be filtered in linear space.
+    /// Defaults to whether the image format is sRGB.
+    pub srgb: Option<bool>,
+    /// Alpha-test threshold; every level is rescaled to keep the fraction of
+    /// texels above it, so cutout foliage does not thin out with distance.
+    pub alpha_cutoff: Option<f32>,
+    /// Treats RGB as unit normals and renormalizes them in every level.
+    pub normal_map: bool,
+}
+
+/// Returns the number of levels in a full chain down to 1x1.
+pub fn full_mip_count(width: u32, height: u32) -> u32 {
+    32 - width.max(height).max(1).leading_zeros()
+}
+
+/// Builds `levels` mip levels from RGBA8 `pixels`, largest first.
+///
+/// Returns the levels packed back to back and the byte offset of each.
+/// `levels` is clamped to the full chain; level 0 is `pixels` unchanged.
+pub fn generate_mip_chain(
+    pixels: &[u8],
+    width: u32,
+    height: u32,
+    levels: u32,
+    srgb: bool,
+    settings: &MipmapSettings,
+) -> (Vec<u8>, Vec<u64>) {
+    let levels = levels.clamp(1, full_mip_count(width, height));
+    let srgb = srgb && !settings.normal_map;
+    let mut data = pixels.to_vec();
+    let mut offsets = vec![0];
+
+    let mut level = Level::decode(pixels, width, height, srgb);
+    let coverage = settings
+        .alpha_cutoff
+        .map(|cutoff| (cutoff, level.coverage(cutoff, 1.0)));
+    for _ in 1..levels {
+        level = level.downsample(settings.mip_filter);
+        let mut encoded = level.clone();
+        if settings.normal_map {
+            encoded.renormalize();
+        }
+        if let Some((cutoff, target)) = coverage {
+            encoded.preserve_coverage(cutoff, target);
+        }
+        offsets.push(data.len() as u64);
+        data.extend(encoded.encode(srgb));
+    }
+    (data, offsets)
+}
+
+/// One level in linear floating point, so repeated filtering does not round.
+#[derive(Clone)]
+struct Level {
+    width: u32,
+    height: u32,
+    texels: Vec<[f32; 4]>,
+}
+
+impl Level {
+    fn decode(pixels: &[u8], width: u32, height: u32, srgb: bool) -> Self {
+        let texels = pixels
+            .chunks_exact(4)
+            .map(|texel| {
+                let mut value: [f32; 4] = std::array::from_fn(|idx| f32::from(texel[idx]) / 255.0);
+                if srgb {
+                    for channel in &mut value[..3] {
+                        *channel = srgb_to_linear(*channel);
+                    }
+                }
+                value
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            texels,
+        }
+    }
+
+    fn encode(&self, srgb: bool) -> Vec<u8> {
+        self.texels
+            .iter()
+            .flat_map(|texel| {
+                let mut value = *texel;
+                if srgb {
+                    for channel in &mut value[..3] {
+                        *channel = linear_to_srgb(*channel);
+                    }
+                }
+                value.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8)
+            })
+            .collect()
+    }
+
+    /// Halves both dimensions with a separable filter, rounding odd sizes down.
+    fn downsample(&self, filter: MipFilter) -> Self {
+        let width = (self.width / 2).max(1);
+        let height = (self.height / 2).max(1);
+        let columns = Taps::new(self.width, width, filter);
+        let rows = Taps::new(self.height, height, filter);
+
+        let mut horizontal = Vec::with_capacity((width * self.height) as usize);
+        for y in 0..self.height as usize {
+            let row = &self.texels[y * self.width as usize..][..self.width as usize];
+            horizontal.extend((0..width as usize).map(|x| columns.apply(x, |idx| row[idx])));
+        }
+        let texels = (0..height as usize)
+            .flat_map(|y| {
+                let rows = &rows;
+                let horizontal = &horizontal;
+                (0..width as usize)
+                    .map(move |x| rows.apply(y, |idx| horizontal[idx * width as usize + x]))
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            texels,
+        }
+    }
+
+    /// Maps RGB back to a unit vector in `[-1, 1]` space.
+    fn renormalize(&mut self) {
+        for texel in &mut self.texels {
+            let normal = [0, 1, 2].map(|idx| texel[idx] * 2.0 - 1.0);
+            let length = normal.iter().map(|value| value * value).sum::<f32>().sqrt();
+            if length > 1e-6 {
+                for idx in 0..3 {
+                    texel[idx] = normal[idx] / length * 0.5 + 0.5;
+                }
+            }
+        }
+    }
+
+    /// Fraction of texels whose alpha, multiplied by `scale`, passes `cutoff`.
+    fn coverage(&self, cutoff: f32, scale: f32) -> f32 {
+        let passing = self
+            .texels
+            .iter()
+            .filter(|texel| (texel[3] * scale).min(1.0) > cutoff)
+            .count();
+        passing as f32 / self.texels.len().max(1) as f32
+    }
+
+    /// Scales alpha up until the coverage at `cutoff` reaches `target`.
+    ///
+    /// Levels that already cover enough are left alone, so opaque texels keep
+    /// their alpha.
+    fn preserve_coverage(&mut self, cutoff: f32, target: f32) {
+        if self.coverage(cutoff, 1.0) >= target {
+            return;
+        }
+        let (mut low, mut high) = (1.0f32, 4.0f32);
+        for _ in 0..16 {
+            let scale = (low + high) * 0.5;
+            if self.coverage(cutoff, scale) < target {
+                low = scale;
+            } else {
+                high = scale;
+            }
+        }
+        // `high` is the smallest scale found that reaches the target.
+        let scale = high;
+        for texel in &mut self.texels {
+            texel[3] = (texel[3] * scale).min(1.0);
+        }
+    }
+}
+
+/// Normalized filter weights for every destination index along one axis.
+struct Taps {
+    taps: Vec<Vec<(usize, f32)>>,
+}
+
+impl Taps {
+    fn new(source: u32, destination: u32, filter: MipFilter) -> Self {
+        let scale = source as f32 / destination as f32;
+        let radius = match filter {
+            MipFilter::Box => 0.5,
+            MipFilter::Kaiser => KAISER_WIDTH,
+        } * scale;
+        let last = source as i64 - 1;
+
+        let taps = (0..destination)
+            .map(|idx| {
+                let center = (idx as f32 + 0.5) * scale - 0.5;
+                let first = (center - radius).floor() as i64;
+                let end = (center + radius).ceil() as i64;
+                let mut weights: Vec<(usize, f32)> = Vec::new();
+                for sample in first..=end {
+                    let weight = match filter {
+                        MipFilter::Box => box_weight((sample as f32 - center) / scale),
+                        MipFilter::Kaiser => kaiser_weight((sample as f32 - center) / scale),
+                    };
+                    if weight == 0.0 {
+                        continue;
+                    }
+                    // Clamp to the edge instead of reading outside the image.
+                    let clamped = sample.clamp(0, last) as usize;
+                    match weights.iter_mut().find(|(tap, _)| *tap == clamped) {
+                        Some((_, total)) => *total += weight,
+                        None => weights.push((clamped, weight)),
+                    }
+                }
+                let sum: f32 = weights.iter().map(|(_, weight)| weight).sum();
+                if sum.abs() > 1e-6 {
+                    weights.iter_mut().for_each(|(_, weight)| *weight /= sum);
+                }
+                weights
+            })
+            .collect();
+        Self { taps }
+    }
+
+    fn apply(&self, idx: usize, texel: impl Fn(usize) -> [f32; 4]) -> [f32; 4] {
+        let mut value = [0.0; 4];
+        for &(tap, weight) in &self.taps[idx] {
+            let sample = texel(tap);
+            for channel in 0..4 {
+                value[channel] += sample[channel] * weight;
+            }
+        }
+        value
+    }
+}
+
+/// Radius of the Kaiser filter in destination texels, as in NVTT.
+const KAISER_WIDTH: f32 = 3.0;
+const KAISER_ALPHA: f32 = 4.0;
+
+fn box_weight(offset: f32) -> f32 {
+    if (-0.5..0.5).contains(&offset) {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+fn kaiser_weight(offset: f32) -> f32 {
+    let ratio = offset / KAISER_WIDTH;
+    if ratio.abs() >= 1.0 {
+        return 0.0;
+    }
+    let window = bessel_i0(KAISER_ALPHA * (1.0 - ratio * ratio).sqrt()) / bessel_i0(KAISER_ALPHA);
+    sinc(offset) * window
+}
+
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-4 {
+        1.0
+    } else {
+        let x = x * std::f32::consts::PI;
+        x.sin() / x
+    }
+}
+
+/// Zeroth-order modified Bessel function of the first kind.
+fn bessel_i0(x: f32) -> f32 {
+    let (mut sum, mut term) = (1.0f32, 1.0f32);
+    let half = x * 0.5;
+    for k in 1..32 {
+        term *= half / k as f32;
+        let squared = term * term;
+        sum += squared;
+        if squared < sum * 1e-8 {
+            break;
+        }
+    }
+    sum
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    let value = value.clamp(0.0, 1.0);
+    if value <= 0.003_130_8 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MipFilter, MipmapSettings, full_mip_count, generate_mip_chain};
+
+    fn solid(width: u32, height: u32, texel: [u8; 4]) -> Vec<u8> {
+        texel.repeat((width * height) as usize)
+    }
+
+    fn level(data: &[u8], offsets: &[u64], idx: usize) -> Vec<u8> {
+        let start = offsets[idx] as usize;
+        let end = offsets.get(idx + 1).map_or(data.len(), |end| *end as usize);
+        data[start..end].to_vec()
+    }
+
+    #[test]
+    fn builds_full_chain_with_offsets() {
+        assert_eq!(full_mip_count(8, 4), 4);
+        assert_eq!(full_mip_count(1, 1), 1);
+        assert_eq!(full_mip_count(5, 3), 3);
+
+        let pixels = solid(8, 4, [10, 200, 90, 255]);
+        for filter in [MipFilter::Box, MipFilter::Kaiser] {
+            let settings = MipmapSettings {
+                mip_filter: filter,
+                ..Default::default()
+            };
+            let (data, offsets) = generate_mip_chain(&pixels, 8, 4, 16, true, &settings);
+            assert_eq!(offsets, vec![0, 128, 160, 168]);
+            assert_eq!(data.len(), 172);
+            // A flat color stays flat through every filter.
+            assert!(
+                data.chunks_exact(4)
+                    .all(|texel| texel == [10, 200, 90, 255])
+            );
+        }
+    }
+
+    #[test]
+    fn filters_srgb_in_linear_space() {
+        // Alternating black and white columns average to linear 0.5.
+        let pixels: Vec<u8> = (0..4 * 2)
+            .flat_map(|idx| {
+                if idx % 2 == 0 {
+                    [0, 0, 0, 255]
+                } else {
+                    [255; 4]
+                }
+            })
+            .collect();
+        let settings = MipmapSettings {
+            mip_filter: MipFilter::Box,
+            ..Default::default()
+        };
+        let (data, offsets) = generate_mip_chain(&pixels, 4, 2, 2, true, &settings);
+        assert_eq!(&level(&data, &offsets, 1)[..4], [188, 188, 188, 255]);
+        let (data, offsets) = generate_mip_chain(&pixels, 4, 2, 2, false, &settings);
+        assert_eq!(&level(&data, &offsets, 1)[..4], [128, 128, 128, 255]);
+    }
+
+    #[test]
+    fn keeps_alpha_coverage_and_unit_normals() {
+        // Leaves along the diagonal blocks cover half of each 2x2 block, so a
+        // plain box filter leaves them all below the cutoff.
+        let pixels: Vec<u8> = (0..8 * 8)
+            .flat_map(|idx| {
+                let (x, y) = (idx % 8, idx / 8);
+                let leaf = x / 2 == y / 2 && y % 2 == 0;
+                [255, 255, 255, if leaf { 200 } else { 0 }]
+            })
+            .collect();
+        let plain = MipmapSettings {
+            mip_filter: MipFilter::Box,
+            ..Default::default()
+        };
+        let (data, offsets) = generate_mip_chain(&pixels, 8, 8, 2, false, &plain);
+        assert!(
+            level(&data, &offsets, 1)
+                .chunks(4)
+                .all(|texel| texel[3] < 128)
+        );
+
+        let cutout = MipmapSettings {
+            alpha_cutoff: Some(0.5),
+            ..plain
+        };
+        let (data, offsets) = generate_mip_chain(&pixels, 8, 8, 2, false, &cutout);
+        let passing = level(&data, &offsets, 1)
+            .chunks(4)
+            .filter(|texel| texel[3] >= 128)
+            .count();
+        assert_eq!(passing, 4);
+
+        // Normals tilted in opposite directions average to a short vector.
+        let pixels: Vec<u8> = (0..2)
+            .flat_map(|idx| {
+                if idx == 0 {
+                    [218, 128, 218, 255]
+                } else {
+                    [37, 128, 218, 255]
+                }
+            })
+            .collect();
+        let normals = MipmapSettings {
+            normal_map: true,
+            mip_filter: MipFilter::Box,
+            ..Default::default()
+        };
+        let (data, offsets) = generate_mip_chain(&pixels, 2, 1, 2, true, &normals);
+        assert_eq!(level(&data, &offsets, 1), [128, 128, 255, 255]);
+    }
+}
@@ -1,2 +1,6 @@
+pub mod bcn;
 pub mod dbgen;
+pub mod distance_field;
+pub mod mipmap;
 pub mod rdb;
+pub mod texture_container;
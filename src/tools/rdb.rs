@@ -1 +1,4 @@
-pub use crate::utils::rdbfile::{RDBEntryMeta, RDBFile, RDBView, type_tag_for};
+pub use crate::utils::rdbfile::{
+    RDBCompression, RDBCorruptEntry, RDBEntryFault, RDBEntryMeta, RDBFile, RDBType,
+    RDBVerifyReport, RDBView, decode_payload, fourcc, legacy_type_tag_for, type_tag_for,
+};
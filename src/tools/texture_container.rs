@@ -0,0 +1,476 @@
+use std::path::Path;
+
+use crate::rdb::BlockFormat;
+use crate::tools::mipmap::full_mip_count;
+
+const KTX2_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+const KTX2_SUPERCOMPRESSION_NONE: u32 = 0;
+const KTX2_SUPERCOMPRESSION_ZSTD: u32 = 2;
+
+const DDS_MAGIC: &[u8; 4] = b"DDS ";
+const DDS_HEADER_END: usize = 128;
+const DDS_DX10_HEADER_END: usize = DDS_HEADER_END + 20;
+const DDSD_MIPMAPCOUNT: u32 = 0x2_0000;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_VOLUME: u32 = 0x20_0000;
+const DDS_RESOURCE_MISC_TEXTURECUBE: u32 = 0x4;
+
+/// Pre-built texture containers that `dbgen` imports without re-encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    Ktx2,
+    Dds,
+}
+
+impl ContainerKind {
+    /// Detects the container from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "ktx2" => Some(Self::Ktx2),
+            "dds" => Some(Self::Dds),
+            _ => None,
+        }
+    }
+}
+
+/// Texture read from a KTX2 or DDS file, with its pixels kept as stored.
+#[derive(Debug, Clone)]
+pub struct ContainerTexture {
+    pub format: dashi::Format,
+    pub width: u32,
+    pub height: u32,
+    /// Array layers, counting each cube face as one layer.
+    pub layers: u32,
+    pub cube: bool,
+    pub mip_levels: u32,
+    /// Every level packed largest first, each holding all of its layers.
+    pub data: Vec<u8>,
+    /// Byte offset of each level in `data`; empty when there is one level.
+    pub mip_offsets: Vec<u64>,
+}
+
+/// Parses a container of the given kind.
+pub fn read_container(kind: ContainerKind, bytes: &[u8]) -> Result<ContainerTexture, String> {
+    match kind {
+        ContainerKind::Ktx2 => read_ktx2(bytes),
+        ContainerKind::Dds => read_dds(bytes),
+    }
+}
+
+/// Parses a KTX2 file, decompressing zstd supercompression if present.
+pub fn read_ktx2(bytes: &[u8]) -> Result<ContainerTexture, String> {
+    if bytes.get(..12) != Some(&KTX2_IDENTIFIER[..]) {
+        return Err("not a KTX2 file".into());
+    }
+
+    let vk_format = read_u32(bytes, 12)?;
+    let width = read_u32(bytes, 20)?;
+    let height = read_u32(bytes, 24)?.max(1);
+    let depth = read_u32(bytes, 28)?;
+    let layers = read_u32(bytes, 32)?.max(1);
+    let faces = read_u32(bytes, 36)?;
+    let levels = read_u32(bytes, 40)?.max(1);
+    let supercompression = read_u32(bytes, 44)?;
+
+    let format = vk_format_to_dashi(vk_format)
+        .ok_or_else(|| format!("KTX2 vkFormat {vk_format} has no dashi::Format equivalent"))?;
+    if depth > 1 {
+        return Err("KTX2 volume textures are not supported".into());
+    }
+    if faces != 1 && faces != 6 {
+        return Err(format!("KTX2 face count {faces} must be 1 or 6"));
+    }
+    if !matches!(
+        supercompression,
+        KTX2_SUPERCOMPRESSION_NONE | KTX2_SUPERCOMPRESSION_ZSTD
+    ) {
+        return Err(format!(
+            "KTX2 supercompression scheme {supercompression} is not supported"
+        ));
+    }
+
+    check_levels(levels, width, height)?;
+    let images = layers
+        .checked_mul(faces)
+        .ok_or_else(|| format!("KTX2 holds {layers} layers of {faces} faces"))?;
+    let mut data = Vec::new();
+    let mut mip_offsets = Vec::with_capacity(levels as usize);
+    for level in 0..levels {
+        let index = 80 + level as usize * 24;
+        let offset = read_u64(bytes, index)? as usize;
+        let length = read_u64(bytes, index + 8)? as usize;
+        let stored = offset
+            .checked_add(length)
+            .and_then(|end| bytes.get(offset..end))
+            .ok_or_else(|| format!("KTX2 level {level} lies outside the file"))?;
+        let level_data = if supercompression == KTX2_SUPERCOMPRESSION_ZSTD {
+            zstd::decode_all(stored).map_err(|err| format!("KTX2 level {level}: {err}"))?
+        } else {
+            stored.to_vec()
+        };
+
+        let expected = level_bytes(format, width, height, level)?
+            .checked_mul(images as usize)
+            .ok_or_else(|| format!("KTX2 level {level} is too large"))?;
+        if level_data.len() != expected {
+            return Err(format!(
+                "KTX2 level {level} holds {} bytes, expected {expected}",
+                level_data.len()
+            ));
+        }
+        mip_offsets.push(data.len() as u64);
+        data.extend(level_data);
+    }
+
+    if levels == 1 {
+        mip_offsets.clear();
+    }
+    Ok(ContainerTexture {
+        format,
+        width,
+        height,
+        layers: images,
+        cube: faces == 6,
+        mip_levels: levels,
+        data,
+        mip_offsets,
+    })
+}
+
+/// Parses a DDS file, including the DX10 header extension.
+pub fn read_dds(bytes: &[u8]) -> Result<ContainerTexture, String> {
+    if bytes.get(..4) != Some(&DDS_MAGIC[..]) {
+        return Err("not a DDS file".into());
+    }
+
+    let flags = read_u32(bytes, 8)?;
+    let height = read_u32(bytes, 12)?.max(1);
+    let width = read_u32(bytes, 16)?.max(1);
+    let levels = if flags & DDSD_MIPMAPCOUNT != 0 {
+        read_u32(bytes, 28)?.max(1)
+    } else {
+        1
+    };
+    let pixel_flags = read_u32(bytes, 80)?;
+    let four_cc = bytes.get(84..88).ok_or("DDS header is truncated")?;
+    let caps2 = read_u32(bytes, 112)?;
+    if caps2 & DDSCAPS2_VOLUME != 0 {
+        return Err("DDS volume textures are not supported".into());
+    }
+
+    let (format, cube, layers, data_start) = if pixel_flags & DDPF_FOURCC != 0 && four_cc == b"DX10"
+    {
+        let dxgi_format = read_u32(bytes, DDS_HEADER_END)?;
+        let format = dxgi_format_to_dashi(dxgi_format).ok_or_else(|| {
+            format!("DDS DXGI format {dxgi_format} has no dashi::Format equivalent")
+        })?;
+        let cube = read_u32(bytes, DDS_HEADER_END + 8)? & DDS_RESOURCE_MISC_TEXTURECUBE != 0;
+        let array_size = read_u32(bytes, DDS_HEADER_END + 12)?.max(1);
+        (format, cube, array_size, DDS_DX10_HEADER_END)
+    } else {
+        let format = legacy_dds_format(bytes, pixel_flags, four_cc)?;
+        (format, caps2 & DDSCAPS2_CUBEMAP != 0, 1, DDS_HEADER_END)
+    };
+    check_levels(levels, width, height)?;
+    let faces = if cube { 6 } else { 1 };
+    let images = layers
+        .checked_mul(faces)
+        .ok_or_else(|| format!("DDS holds {layers} layers of {faces} faces"))?;
+
+    // DDS stores every level of one layer before the next layer; reorder the
+    // levels so each one holds all layers, as the GPU upload expects.
+    let level_sizes = (0..levels)
+        .map(|level| level_bytes(format, width, height, level))
+        .collect::<Result<Vec<_>, _>>()?;
+    let image_bytes: usize = level_sizes.iter().sum();
+    let total_bytes = image_bytes
+        .checked_mul(images as usize)
+        .ok_or_else(|| format!("DDS data of {images} images is too large"))?;
+    let payload = bytes
+        .get(data_start..)
+        .filter(|payload| payload.len() >= total_bytes)
+        .ok_or_else(|| {
+            format!("DDS data is truncated: expected {images} images of {image_bytes} bytes")
+        })?;
+
+    let mut data = Vec::with_capacity(total_bytes);
+    let mut mip_offsets = Vec::with_capacity(levels as usize);
+    for (level, size) in level_sizes.iter().enumerate() {
+        mip_offsets.push(data.len() as u64);
+        let level_start: usize = level_sizes[..level].iter().sum();
+        for image in 0..images as usize {
+            let start = image * image_bytes + level_start;
+            data.extend_from_slice(&payload[start..start + size]);
+        }
+    }
+
+    if levels == 1 {
+        mip_offsets.clear();
+    }
+    Ok(ContainerTexture {
+        format,
+        width,
+        height,
+        layers: images,
+        cube,
+        mip_levels: levels,
+        data,
+        mip_offsets,
+    })
+}
+
+/// Reads pre-DX10 DDS formats: the common block FourCCs and 32-bit RGBA masks.
+fn legacy_dds_format(
+    bytes: &[u8],
+    pixel_flags: u32,
+    four_cc: &[u8],
+) -> Result<dashi::Format, String> {
+    if pixel_flags & DDPF_FOURCC != 0 {
+        return match four_cc {
+            b"DXT1" => Ok(dashi::Format::BC1),
+            b"DXT5" => Ok(dashi::Format::BC3),
+            b"ATI1" | b"BC4U" => Ok(dashi::Format::BC4),
+            b"ATI2" | b"BC5U" => Ok(dashi::Format::BC5),
+            other => Err(format!(
+                "DDS FourCC '{}' has no dashi::Format equivalent",
+                String::from_utf8_lossy(other)
+            )),
+        };
+    }
+
+    let bit_count = read_u32(bytes, 88)?;
+    let red_mask = read_u32(bytes, 92)?;
+    if pixel_flags & DDPF_RGB != 0 && bit_count == 32 {
+        match red_mask {
+            0x0000_00FF => return Ok(dashi::Format::RGBA8Unorm),
+            0x00FF_0000 => return Ok(dashi::Format::BGRA8Unorm),
+            _ => {}
+        }
+    }
+    Err(format!(
+        "DDS pixel format ({bit_count} bits, red mask {red_mask:#010x}) has no dashi::Format equivalent"
+    ))
+}
+
+fn vk_format_to_dashi(vk_format: u32) -> Option<dashi::Format> {
+    match vk_format {
+        13 => Some(dashi::Format::R8Uint),
+        14 => Some(dashi::Format::R8Sint),
+        29 => Some(dashi::Format::RGB8),
+        37 => Some(dashi::Format::RGBA8Unorm),
+        43 => Some(dashi::Format::RGBA8),
+        44 => Some(dashi::Format::BGRA8Unorm),
+        50 => Some(dashi::Format::BGRA8),
+        109 => Some(dashi::Format::RGBA32F),
+        131..=134 => Some(dashi::Format::BC1),
+        137 | 138 => Some(dashi::Format::BC3),
+        139 => Some(dashi::Format::BC4),
+        141 => Some(dashi::Format::BC5),
+        145 | 146 => Some(dashi::Format::BC7),
+        _ => None,
+    }
+}
+
+fn dxgi_format_to_dashi(dxgi_format: u32) -> Option<dashi::Format> {
+    match dxgi_format {
+        2 => Some(dashi::Format::RGBA32F),
+        28 => Some(dashi::Format::RGBA8Unorm),
+        29 => Some(dashi::Format::RGBA8),
+        62 => Some(dashi::Format::R8Uint),
+        64 => Some(dashi::Format::R8Sint),
+        87 => Some(dashi::Format::BGRA8Unorm),
+        91 => Some(dashi::Format::BGRA8),
+        71 | 72 => Some(dashi::Format::BC1),
+        77 | 78 => Some(dashi::Format::BC3),
+        80 => Some(dashi::Format::BC4),
+        83 => Some(dashi::Format::BC5),
+        98 | 99 => Some(dashi::Format::BC7),
+        _ => None,
+    }
+}
+
+/// Rejects level counts past the chain down to 1x1; this also keeps every
+/// level below 32 so `level_bytes` can shift by it.
+fn check_levels(levels: u32, width: u32, height: u32) -> Result<(), String> {
+    let full = full_mip_count(width, height);
+    if levels > full {
+        return Err(format!(
+            "{levels} mip levels exceed the {full} a {width}x{height} texture can hold"
+        ));
+    }
+    Ok(())
+}
+
+/// Size of one layer of `level` in `format`.
+fn level_bytes(
+    format: dashi::Format,
+    width: u32,
+    height: u32,
+    level: u32,
+) -> Result<usize, String> {
+    let width = (width >> level).max(1);
+    let height = (height >> level).max(1);
+    let (columns, rows, unit) = match BlockFormat::from_format(format) {
+        Some(block) => (width.div_ceil(4), height.div_ceil(4), block.block_bytes()),
+        None => (width, height, texel_bytes(format)?),
+    };
+    (columns as usize)
+        .checked_mul(rows as usize)
+        .and_then(|count| count.checked_mul(unit))
+        .ok_or_else(|| format!("level {level} of a {width}x{height} texture is too large"))
+}
+
+fn texel_bytes(format: dashi::Format) -> Result<usize, String> {
+    let texel = match format {
+        dashi::Format::R8Uint | dashi::Format::R8Sint => 1,
+        dashi::Format::RGB8 => 3,
+        dashi::Format::RGBA8
+        | dashi::Format::RGBA8Unorm
+        | dashi::Format::BGRA8
+        | dashi::Format::BGRA8Unorm => 4,
+        dashi::Format::RGBA32F => 16,
+        other => return Err(format!("format {other:?} cannot be imported")),
+    };
+    Ok(texel)
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> Result<u32, String> {
+    bytes
+        .get(offset..offset + 4)
+        .map(|value| u32::from_le_bytes([value[0], value[1], value[2], value[3]]))
+        .ok_or_else(|| format!("header is truncated at byte {offset}"))
+}
+
+fn read_u64(bytes: &[u8], offset: usize) -> Result<u64, String> {
+    let low = read_u32(bytes, offset)?;
+    let high = read_u32(bytes, offset + 4)?;
+    Ok(u64::from(low) | (u64::from(high) << 32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ContainerKind, KTX2_IDENTIFIER, read_dds, read_ktx2};
+    use std::path::Path;
+
+    fn ktx2(vk_format: u32, width: u32, height: u32, levels: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = KTX2_IDENTIFIER.to_vec();
+        for value in [vk_format, 1, width, height, 0, 0, 1, levels.len() as u32, 0] {
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.resize(80, 0);
+        let mut offset = 80 + levels.len() * 24;
+        for level in levels {
+            for value in [offset, level.len(), level.len()] {
+                bytes.extend((value as u64).to_le_bytes());
+            }
+            offset += level.len();
+        }
+        for level in levels {
+            bytes.extend(level);
+        }
+        bytes
+    }
+
+    fn dds_cube_dx10(dxgi_format: u32, size: u32, levels: &[Vec<u8>]) -> Vec<u8> {
+        let mut bytes = b"DDS ".to_vec();
+        bytes.resize(128, 0);
+        let mut put = |offset: usize, value: u32| {
+            bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        };
+        put(4, 124);
+        put(8, 0x2_1007);
+        put(12, size);
+        put(16, size);
+        put(28, levels.len() as u32);
+        put(76, 32);
+        put(80, 0x4);
+        put(84, u32::from_le_bytes(*b"DX10"));
+        put(112, 0xFE00);
+        for value in [dxgi_format, 3, 0x4, 1, 0] {
+            bytes.extend(value.to_le_bytes());
+        }
+        for face in 0..6u8 {
+            for level in levels {
+                bytes.extend(level.iter().map(|byte| byte + face));
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn detects_containers_by_extension() {
+        assert_eq!(
+            ContainerKind::from_path(Path::new("rock.KTX2")),
+            Some(ContainerKind::Ktx2)
+        );
+        assert_eq!(
+            ContainerKind::from_path(Path::new("sky.dds")),
+            Some(ContainerKind::Dds)
+        );
+        assert_eq!(ContainerKind::from_path(Path::new("rock.png")), None);
+    }
+
+    #[test]
+    fn reads_ktx2_levels_without_reencoding() {
+        let levels = vec![vec![7u8; 32], vec![9u8; 16]];
+        // VK_FORMAT_BC7_SRGB_BLOCK, 8x4 then 4x2.
+        let texture = read_ktx2(&ktx2(146, 8, 4, &levels)).unwrap();
+        assert_eq!(texture.format, dashi::Format::BC7);
+        assert_eq!((texture.width, texture.height), (8, 4));
+        assert_eq!((texture.layers, texture.mip_levels), (1, 2));
+        assert!(!texture.cube);
+        assert_eq!(texture.mip_offsets, vec![0, 32]);
+        assert_eq!(texture.data, levels.concat());
+
+        // VK_FORMAT_BC6H_UFLOAT_BLOCK has no dashi format.
+        let err = read_ktx2(&ktx2(143, 8, 4, &levels)).unwrap_err();
+        assert!(err.contains("vkFormat 143"), "{err}");
+        let err = read_ktx2(&ktx2(146, 8, 4, &[vec![0; 8]])).unwrap_err();
+        assert!(err.contains("expected 32"), "{err}");
+    }
+
+    #[test]
+    fn reorders_dds_cube_faces_by_level() {
+        // DXGI_FORMAT_R8G8B8A8_UNORM, 2x2 and 1x1 levels per face.
+        let levels = vec![vec![10u8; 16], vec![50u8; 4]];
+        let texture = read_dds(&dds_cube_dx10(28, 2, &levels)).unwrap();
+        assert_eq!(texture.format, dashi::Format::RGBA8Unorm);
+        assert!(texture.cube);
+        assert_eq!((texture.layers, texture.mip_levels), (6, 2));
+        assert_eq!(texture.mip_offsets, vec![0, 96]);
+        assert_eq!(texture.data.len(), 120);
+        assert_eq!(texture.data[16], 11);
+        assert_eq!(texture.data[96..100], [50; 4]);
+        assert_eq!(texture.data[116..], [55; 4]);
+
+        let err = read_dds(&dds_cube_dx10(95, 2, &levels)).unwrap_err();
+        assert!(err.contains("DXGI format 95"), "{err}");
+    }
+
+    #[test]
+    fn rejects_impossible_level_and_layer_counts() {
+        let levels = vec![vec![7u8; 32], vec![9u8; 16]];
+        let mut bytes = ktx2(146, 8, 4, &levels);
+        bytes[40..44].copy_from_slice(&40u32.to_le_bytes());
+        let err = read_ktx2(&bytes).unwrap_err();
+        assert!(err.contains("40 mip levels"), "{err}");
+        bytes[40..44].copy_from_slice(&5u32.to_le_bytes());
+        let err = read_ktx2(&bytes).unwrap_err();
+        assert!(err.contains("exceed the 4"), "{err}");
+
+        let levels = vec![vec![10u8; 16], vec![50u8; 4]];
+        let mut bytes = dds_cube_dx10(28, 2, &levels);
+        bytes[28..32].copy_from_slice(&33u32.to_le_bytes());
+        let err = read_dds(&bytes).unwrap_err();
+        assert!(err.contains("33 mip levels"), "{err}");
+        bytes[28..32].copy_from_slice(&2u32.to_le_bytes());
+        bytes[140..144].copy_from_slice(&u32::MAX.to_le_bytes());
+        let err = read_dds(&bytes).unwrap_err();
+        assert!(err.contains("6 faces"), "{err}");
+    }
+}
@@ -0,0 +1,288 @@
+use bytemuck::{Pod, Zeroable};
+use memmap2::Mmap;
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufWriter, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use super::rdbfile::RDBView;
+use crate::error::RdbErr;
+
+const MAGIC: [u8; 4] = *b"NRB0";
+const VERSION: u16 = 1;
+/// Alignment of every member, so POD entries of embedded RDB files stay aligned.
+const MEMBER_ALIGN: usize = 16;
+
+/// Member holding the bundled [`crate::DatabaseLayoutFile`] as JSON.
+pub const BUNDLE_LAYOUT_MEMBER: &str = "@layout";
+
+#[repr(C)]
+#[derive(Clone, Copy, Zeroable, Pod)]
+struct BundleHeader {
+    magic: [u8; 4],    // "NRB0"
+    version: u16,      // 1
+    reserved: u16,     // future flags
+    member_count: u32, // number of member records
+    names_len: u32,    // string table size in bytes
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Zeroable, Pod)]
+struct MemberRecord {
+    offset: u64,      // file offset of the member bytes
+    len: u64,         // member length in bytes
+    name_offset: u32, // offset of the name within the string table
+    name_len: u32,    // name length in bytes (UTF-8, no terminator)
+}
+
+enum MemberSource {
+    Bytes(Vec<u8>),
+    File(PathBuf),
+}
+
+/// Builds a single-file `.nrb` bundle from RDB and metadata files.
+#[derive(Default)]
+pub struct DBBundleWriter {
+    members: Vec<(String, MemberSource)>,
+}
+
+impl DBBundleWriter {
+    /// Creates an empty bundle writer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an in-memory member, replacing any member with the same name.
+    pub fn add_bytes(&mut self, name: &str, bytes: Vec<u8>) {
+        self.insert(name, MemberSource::Bytes(bytes));
+    }
+
+    /// Adds a file that is copied into the bundle when it is saved.
+    pub fn add_file(&mut self, name: &str, path: impl AsRef<Path>) {
+        self.insert(name, MemberSource::File(path.as_ref().to_path_buf()));
+    }
+
+    fn insert(&mut self, name: &str, source: MemberSource) {
+        self.members.retain(|(existing, _)| existing != name);
+        self.members.push((name.to_string(), source));
+    }
+
+    /// Returns the names of the members added so far.
+    pub fn member_names(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Writes the bundle to `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RdbErr> {
+        let mut names = Vec::new();
+        let mut records = Vec::with_capacity(self.members.len());
+        for (name, source) in &self.members {
+            let len = match source {
+                MemberSource::Bytes(bytes) => bytes.len() as u64,
+                MemberSource::File(path) => std::fs::metadata(path)?.len(),
+            };
+            records.push(MemberRecord {
+                offset: 0,
+                len,
+                name_offset: u32::try_from(names.len()).map_err(|_| RdbErr::NameTooLong)?,
+                name_len: u32::try_from(name.len()).map_err(|_| RdbErr::NameTooLong)?,
+            });
+            names.extend_from_slice(name.as_bytes());
+        }
+
+        let mut offset = std::mem::size_of::<BundleHeader>()
+            + std::mem::size_of_val(records.as_slice())
+            + names.len();
+        for record in &mut records {
+            offset = offset.next_multiple_of(MEMBER_ALIGN);
+            record.offset = offset as u64;
+            offset += record.len as usize;
+        }
+
+        let header = BundleHeader {
+            magic: MAGIC,
+            version: VERSION,
+            reserved: 0,
+            member_count: records.len() as u32,
+            names_len: u32::try_from(names.len()).map_err(|_| RdbErr::NameTooLong)?,
+        };
+
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(bytemuck::bytes_of(&header))?;
+        out.write_all(bytemuck::cast_slice(&records))?;
+        out.write_all(&names)?;
+        let mut written = std::mem::size_of::<BundleHeader>()
+            + std::mem::size_of_val(records.as_slice())
+            + names.len();
+
+        for ((_, source), record) in self.members.iter().zip(&records) {
+            let padding = record.offset as usize - written;
+            out.write_all(&[0u8; MEMBER_ALIGN][..padding])?;
+            let copied = match source {
+                MemberSource::Bytes(bytes) => {
+                    out.write_all(bytes)?;
+                    bytes.len() as u64
+                }
+                MemberSource::File(path) => io::copy(&mut File::open(path)?, &mut out)?,
+            };
+            // A member file that changed size while bundling would corrupt the offsets.
+            if copied != record.len {
+                return Err(RdbErr::Io());
+            }
+            written = record.offset as usize + record.len as usize;
+        }
+
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// A memory-mapped `.nrb` bundle whose members are read in place.
+pub struct DBBundle {
+    mmap: Arc<Mmap>,
+    members: BTreeMap<String, Range<usize>>,
+}
+
+impl DBBundle {
+    /// Maps the bundle at `path` and reads its member table.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, RdbErr> {
+        let f = File::open(path)?;
+        let map = unsafe { Mmap::map(&f)? };
+
+        let header_sz = std::mem::size_of::<BundleHeader>();
+        if map.len() < header_sz {
+            return Err(RdbErr::TooSmall);
+        }
+        let header: BundleHeader = bytemuck::pod_read_unaligned(&map[..header_sz]);
+        if header.magic != MAGIC || header.version != VERSION {
+            return Err(RdbErr::BadHeader);
+        }
+
+        let records_end = (header.member_count as usize)
+            .checked_mul(std::mem::size_of::<MemberRecord>())
+            .and_then(|len| len.checked_add(header_sz))
+            .ok_or(RdbErr::BadHeader)?;
+        let names_end = records_end
+            .checked_add(header.names_len as usize)
+            .ok_or(RdbErr::BadHeader)?;
+        if map.len() < names_end {
+            return Err(RdbErr::TooSmall);
+        }
+
+        let names = &map[records_end..names_end];
+        let mut members = BTreeMap::new();
+        for raw in map[header_sz..records_end].chunks_exact(std::mem::size_of::<MemberRecord>()) {
+            let record: MemberRecord = bytemuck::pod_read_unaligned(raw);
+            let name_start = record.name_offset as usize;
+            let name = names
+                .get(name_start..name_start + record.name_len as usize)
+                .ok_or(RdbErr::BadHeader)?;
+            let start = usize::try_from(record.offset).map_err(|_| RdbErr::BadHeader)?;
+            let end = usize::try_from(record.len)
+                .ok()
+                .and_then(|len| start.checked_add(len))
+                .ok_or(RdbErr::BadHeader)?;
+            if end > map.len() {
+                return Err(RdbErr::TooSmall);
+            }
+            members.insert(String::from_utf8_lossy(name).into_owned(), start..end);
+        }
+
+        Ok(Self {
+            mmap: Arc::new(map),
+            members,
+        })
+    }
+
+    /// Returns the member names in the bundle, in name order.
+    pub fn member_names(&self) -> impl Iterator<Item = &str> {
+        self.members.keys().map(String::as_str)
+    }
+
+    /// Returns whether a member with the exact given name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.members.contains_key(name)
+    }
+
+    /// Borrows the bytes of a member, if present.
+    pub fn member(&self, name: &str) -> Option<&[u8]> {
+        self.members
+            .get(name)
+            .map(|range| &self.mmap[range.clone()])
+    }
+
+    /// Opens an RDB member as a view sharing the bundle's mapping.
+    pub fn view(&self, name: &str) -> Result<RDBView, RdbErr> {
+        let range = self.members.get(name).ok_or(RdbErr::BadHeader)?;
+        RDBView::from_shared(Arc::clone(&self.mmap), range.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BUNDLE_LAYOUT_MEMBER, DBBundle, DBBundleWriter, MEMBER_ALIGN};
+    use crate::{RDBFile, error::RdbErr};
+
+    #[test]
+    fn bundled_rdb_members_open_in_place() {
+        let mut geometry = RDBFile::new();
+        geometry
+            .add("mesh/quad", &vec![1u32, 2, 3])
+            .expect("add mesh");
+        geometry
+            .add_pod_slice("mesh/quad#indices", &[0u32, 1, 2])
+            .expect("add indices");
+        geometry
+            .save("target/bundle_geometry.rdb")
+            .expect("save geometry");
+
+        let mut writer = DBBundleWriter::new();
+        writer.add_bytes(BUNDLE_LAYOUT_MEMBER, b"{}".to_vec());
+        writer.add_bytes("textures.json", b"odd".to_vec());
+        writer.add_file("geometry.rdb", "target/bundle_geometry.rdb");
+        writer.save("target/bundle.nrb").expect("save bundle");
+
+        let bundle = DBBundle::open("target/bundle.nrb").expect("open bundle");
+        assert_eq!(
+            bundle.member_names().collect::<Vec<_>>(),
+            vec![BUNDLE_LAYOUT_MEMBER, "geometry.rdb", "textures.json"]
+        );
+        assert_eq!(bundle.member("textures.json"), Some(&b"odd"[..]));
+        assert_eq!(
+            bundle.member("geometry.rdb").map(<[u8]>::len),
+            std::fs::metadata("target/bundle_geometry.rdb")
+                .map(|meta| meta.len() as usize)
+                .ok()
+        );
+
+        let view = bundle.view("geometry.rdb").expect("open member view");
+        assert_eq!(
+            view.fetch::<Vec<u32>>("mesh/quad").expect("fetch mesh"),
+            vec![1, 2, 3]
+        );
+        let indices = view
+            .fetch_pod_slice::<u32>("mesh/quad#indices")
+            .expect("borrow indices");
+        assert_eq!(indices, &[0, 1, 2]);
+        assert_eq!(indices.as_ptr() as usize % MEMBER_ALIGN, 0);
+        assert!(view.verify().is_ok());
+
+        assert!(matches!(bundle.view("audio.rdb"), Err(RdbErr::BadHeader)));
+    }
+
+    #[test]
+    fn open_rejects_non_bundles() {
+        let mut rdb = RDBFile::new();
+        rdb.add("value", &1u32).expect("add value");
+        rdb.save("target/not_a_bundle.rdb").expect("save");
+
+        assert!(matches!(
+            DBBundle::open("target/not_a_bundle.rdb"),
+            Err(RdbErr::BadHeader)
+        ));
+    }
+}
@@ -1,6 +1,8 @@
-use std::{
-    collections::{HashMap, hash_map::Entry},
-    time::Instant,
+use std::{collections::hash_map::Entry, time::Instant};
+
+use super::{
+    error::NorenError,
+    handle::{EntryId, EntryIdMap},
 };
 
 #[derive(Debug)]
@@ -8,18 +10,34 @@ pub struct CacheEntry<T> {
     pub payload: T,
     pub refcount: usize,
     pub unload_at: Option<Instant>,
+    /// Bytes the payload keeps resident, as reported when it was inserted.
+    pub bytes: u64,
+    /// When the entry was last fetched or released.
+    pub last_used: Instant,
+    /// Set when the backing data changed while the entry was still referenced.
+    pub stale: bool,
 }
 
 impl<T> CacheEntry<T> {
     /// Creates a cache entry with an initial reference count of one.
-    pub fn new(payload: T) -> Self {
+    pub fn new(payload: T, bytes: u64) -> Self {
         Self {
             payload,
             refcount: 1,
             unload_at: None,
+            bytes,
+            last_used: Instant::now(),
+            stale: false,
         }
     }
 
+    /// Takes another reference, cancelling any pending unload.
+    pub fn acquire(&mut self) {
+        self.refcount += 1;
+        self.clear_unload();
+        self.last_used = Instant::now();
+    }
+
     /// Marks the cache entry for unloading at the specified time.
     pub fn mark_for_unload(&mut self, when: Instant) {
         self.unload_at = Some(when);
@@ -31,50 +49,170 @@ impl<T> CacheEntry<T> {
     }
 }
 
-#[derive(Debug, Default)]
+/// Resident size of a GPU cache, as reported by `memory_stats`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Bytes held by every cached entry, referenced or not.
+    pub resident_bytes: u64,
+    /// Number of cached entries.
+    pub entries: usize,
+    /// Byte budget beyond which unreferenced entries are evicted, if any.
+    pub budget: Option<u64>,
+}
+
+/// Key used to look up a [`DataCache`] entry.
+///
+/// Names are compared against the name stored with the entry, so a name whose
+/// [`EntryId`] collides with a cached entry never resolves to it. IDs come from
+/// handles of entries that were inserted under their own name.
+pub trait CacheKey {
+    fn id(&self) -> EntryId;
+
+    fn name(&self) -> Option<&str> {
+        None
+    }
+}
+
+impl CacheKey for EntryId {
+    fn id(&self) -> EntryId {
+        *self
+    }
+}
+
+impl CacheKey for &str {
+    fn id(&self) -> EntryId {
+        EntryId::new(self)
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+impl CacheKey for &String {
+    fn id(&self) -> EntryId {
+        EntryId::new(self)
+    }
+
+    fn name(&self) -> Option<&str> {
+        Some(self)
+    }
+}
+
+/// Refcounted cache keyed by [`EntryId`]; the entry name is kept for reporting
+/// and to reject colliding names.
+#[derive(Debug)]
 pub struct DataCache<T> {
-    data: HashMap<String, CacheEntry<T>>,
+    data: EntryIdMap<(String, CacheEntry<T>)>,
+    resident: u64,
+    /// Number of cached entries marked stale.
+    stale: usize,
+}
+
+impl<T> Default for DataCache<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> DataCache<T> {
     /// Creates an empty cache.
     pub fn new() -> Self {
         Self {
-            data: HashMap::new(),
+            data: EntryIdMap::default(),
+            resident: 0,
+            stale: 0,
+        }
+    }
+
+    /// Returns the number of bytes held by all cached entries.
+    pub fn resident_bytes(&self) -> u64 {
+        self.resident
+    }
+
+    /// Returns the number of cached entries.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns true when nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the ID of the entry `key` refers to, if it is cached under that name.
+    fn find(&self, key: &impl CacheKey) -> Option<EntryId> {
+        let id = key.id();
+        let (name, _) = self.data.get(&id)?;
+        match key.name() {
+            Some(requested) if requested != name => None,
+            _ => Some(id),
         }
     }
 
-    /// Returns an immutable reference to a cached entry by key.
-    pub fn get(&self, key: &str) -> Option<&CacheEntry<T>> {
-        self.data.get(key)
+    /// Returns an immutable reference to a cached entry by name or ID.
+    pub fn get(&self, key: impl CacheKey) -> Option<&CacheEntry<T>> {
+        let id = self.find(&key)?;
+        self.data.get(&id).map(|(_, entry)| entry)
     }
 
-    /// Returns a mutable reference to a cached entry by key.
-    pub fn get_mut(&mut self, key: &str) -> Option<&mut CacheEntry<T>> {
-        self.data.get_mut(key)
+    /// Returns a mutable reference to a cached entry by name or ID.
+    pub fn get_mut(&mut self, key: impl CacheKey) -> Option<&mut CacheEntry<T>> {
+        let id = self.find(&key)?;
+        self.data.get_mut(&id).map(|(_, entry)| entry)
     }
 
-    /// Inserts a new entry or increments the reference count on an existing one.
-    pub fn insert_or_increment<F>(&mut self, key: &str, create: F) -> &mut CacheEntry<T>
+    /// Fails if `key` hashes to the ID of a cached entry with a different name.
+    ///
+    /// Check this before uploading a payload that will be passed to
+    /// [`DataCache::insert_or_increment`].
+    pub fn check_key(&self, key: &str) -> Result<(), NorenError> {
+        match self.data.get(&EntryId::new(key)) {
+            Some((name, _)) if name != key => Err(NorenError::EntryIdCollision {
+                entry: key.to_string(),
+                cached: name.clone(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Inserts a new entry holding `bytes` or increments the reference count on an existing one.
+    pub fn insert_or_increment<F>(
+        &mut self,
+        key: &str,
+        bytes: u64,
+        create: F,
+    ) -> Result<&mut CacheEntry<T>, NorenError>
     where
         F: FnOnce() -> T,
     {
-        match self.data.entry(key.to_string()) {
-            Entry::Occupied(mut occupied) => {
-                {
-                    let entry = occupied.get_mut();
-                    entry.refcount += 1;
-                    entry.clear_unload();
+        match self.data.entry(EntryId::new(key)) {
+            Entry::Occupied(occupied) => {
+                let (name, entry) = occupied.into_mut();
+                if name != key {
+                    return Err(NorenError::EntryIdCollision {
+                        entry: key.to_string(),
+                        cached: name.clone(),
+                    });
                 }
-                occupied.into_mut()
+                entry.acquire();
+                Ok(entry)
+            }
+            Entry::Vacant(vacant) => {
+                self.resident += bytes;
+                let (_, entry) = vacant.insert((key.to_string(), CacheEntry::new(create(), bytes)));
+                Ok(entry)
             }
-            Entry::Vacant(vacant) => vacant.insert(CacheEntry::new(create())),
         }
     }
 
     /// Decrements the reference count for a key and schedules unload when it reaches zero.
-    pub fn decrement(&mut self, key: &str, unload_at: Instant) -> Option<&mut CacheEntry<T>> {
-        if let Some(entry) = self.data.get_mut(key) {
+    pub fn decrement(
+        &mut self,
+        key: impl CacheKey,
+        unload_at: Instant,
+    ) -> Option<&mut CacheEntry<T>> {
+        if let Some(entry) = self.get_mut(key) {
             if entry.refcount > 0 {
                 entry.refcount -= 1;
             }
@@ -82,6 +220,7 @@ impl<T> DataCache<T> {
             if entry.refcount == 0 {
                 entry.mark_for_unload(unload_at);
             }
+            entry.last_used = Instant::now();
 
             Some(entry)
         } else {
@@ -89,11 +228,103 @@ impl<T> DataCache<T> {
         }
     }
 
+    /// Removes an entry regardless of its reference count.
+    pub fn remove(&mut self, key: impl CacheKey) -> Option<CacheEntry<T>> {
+        let id = self.find(&key)?;
+        let (_, entry) = self.data.remove(&id)?;
+        self.resident -= entry.bytes;
+        if entry.stale {
+            self.stale -= 1;
+        }
+        Some(entry)
+    }
+
+    /// Removes an entry whose backing data changed if nothing references it.
+    ///
+    /// Referenced entries are marked stale instead and keep serving their
+    /// payload until [`DataCache::drain_stale`] removes them once released.
+    pub fn retire(&mut self, key: impl CacheKey) -> Option<(String, CacheEntry<T>)> {
+        let id = self.find(&key)?;
+        let (name, entry) = self.data.get_mut(&id)?;
+        if entry.refcount > 0 {
+            if !entry.stale {
+                entry.stale = true;
+                self.stale += 1;
+            }
+            return None;
+        }
+
+        let name = name.clone();
+        self.remove(id).map(|entry| (name, entry))
+    }
+
+    /// Removes and returns stale entries whose last reference was released.
+    pub fn drain_stale(&mut self) -> Vec<(String, CacheEntry<T>)> {
+        if self.stale == 0 {
+            return Vec::new();
+        }
+
+        let released: Vec<EntryId> = self
+            .data
+            .iter()
+            .filter(|(_, (_, entry))| entry.stale && entry.refcount == 0)
+            .map(|(id, _)| *id)
+            .collect();
+
+        released
+            .into_iter()
+            .filter_map(|id| {
+                let name = self.data.get(&id)?.0.clone();
+                self.remove(id).map(|entry| (name, entry))
+            })
+            .collect()
+    }
+
+    /// Returns unreferenced entries with their last use, least recently used first.
+    pub fn eviction_candidates(&self) -> Vec<(String, Instant)> {
+        let mut candidates: Vec<(String, Instant)> = self
+            .data
+            .values()
+            .filter(|(_, entry)| entry.refcount == 0)
+            .map(|(key, entry)| (key.clone(), entry.last_used))
+            .collect();
+        candidates.sort_by_key(|(_, last_used)| *last_used);
+        candidates
+    }
+
+    /// Removes unreferenced entries, least recently used first, until at most
+    /// `budget` bytes are resident or only referenced entries remain.
+    pub fn evict_to(&mut self, budget: u64) -> Vec<(String, CacheEntry<T>)> {
+        let mut evicted = Vec::new();
+        if self.resident <= budget {
+            return evicted;
+        }
+
+        for (key, _) in self.eviction_candidates() {
+            if self.resident <= budget {
+                break;
+            }
+            if let Some(entry) = self.remove(&key) {
+                evicted.push((key, entry));
+            }
+        }
+        evicted
+    }
+
+    /// Summarizes the cache for `memory_stats`.
+    pub fn stats(&self, budget: Option<u64>) -> CacheStats {
+        CacheStats {
+            resident_bytes: self.resident,
+            entries: self.data.len(),
+            budget,
+        }
+    }
+
     /// Removes and returns any entries whose unload time has expired.
     pub fn drain_expired(&mut self, now: Instant) -> Vec<(String, CacheEntry<T>)> {
         let expired_keys: Vec<String> = self
             .data
-            .iter()
+            .values()
             .filter_map(|(key, entry)| match entry.unload_at {
                 Some(when) if when <= now => Some(key.clone()),
                 _ => None,
@@ -102,7 +333,7 @@ impl<T> DataCache<T> {
 
         expired_keys
             .into_iter()
-            .filter_map(|key| self.data.remove(&key).map(|entry| (key, entry)))
+            .filter_map(|key| self.remove(&key).map(|entry| (key, entry)))
             .collect()
     }
 
@@ -111,8 +342,95 @@ impl<T> DataCache<T> {
     where
         F: FnMut(&mut T),
     {
-        for entry in self.data.values_mut() {
+        for (_, entry) in self.data.values_mut() {
             func(&mut entry.payload);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CacheEntry, DataCache, EntryId};
+    use crate::utils::NorenError;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn evict_to_drops_least_recently_released_entries() {
+        let mut cache = DataCache::new();
+        for key in ["a", "b", "c"] {
+            cache.insert_or_increment(key, 10, || key).unwrap();
+        }
+        assert_eq!(cache.resident_bytes(), 30);
+
+        cache.decrement("b", Instant::now());
+        std::thread::sleep(Duration::from_millis(2));
+        cache.decrement("a", Instant::now());
+        assert!(cache.evict_to(30).is_empty());
+
+        let evicted: Vec<String> = cache.evict_to(15).into_iter().map(|(key, _)| key).collect();
+        assert_eq!(evicted, vec!["b", "a"]);
+        assert_eq!(cache.resident_bytes(), 10);
+        assert!(
+            cache.evict_to(0).is_empty(),
+            "referenced entries stay resident"
+        );
+
+        cache.insert_or_increment("c", 99, || "ignored").unwrap();
+        assert_eq!(cache.get("c").map(|entry| entry.refcount), Some(2));
+        assert_eq!(cache.stats(Some(15)).resident_bytes, 10);
+    }
+
+    #[test]
+    fn retired_entries_wait_for_their_last_reference() {
+        let mut cache = DataCache::new();
+        cache.insert_or_increment("idle", 10, || "idle").unwrap();
+        cache.insert_or_increment("live", 10, || "live").unwrap();
+        cache.decrement("idle", Instant::now());
+
+        let retired = cache.retire("idle").map(|(key, _)| key);
+        assert_eq!(retired.as_deref(), Some("idle"));
+        assert!(cache.retire("live").is_none());
+        assert!(cache.get("live").is_some_and(|entry| entry.stale));
+        assert!(cache.drain_stale().is_empty());
+
+        cache.decrement("live", Instant::now());
+        let drained: Vec<String> = cache
+            .drain_stale()
+            .into_iter()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(drained, vec!["live"]);
+        assert!(cache.is_empty());
+        assert_eq!(cache.resident_bytes(), 0);
+    }
+
+    #[test]
+    fn colliding_names_never_resolve_to_another_entry() {
+        let mut cache = DataCache::new();
+        // Stand in for "b" hashing to the ID already taken by "a".
+        let id = EntryId::new("b");
+        cache
+            .data
+            .insert(id, ("a".to_string(), CacheEntry::new("a", 10)));
+
+        assert!(cache.get("b").is_none());
+        assert!(cache.get(id).is_some(), "IDs from handles still resolve");
+        assert!(matches!(
+            cache.check_key("b"),
+            Err(NorenError::EntryIdCollision { ref entry, ref cached })
+                if entry == "b" && cached == "a"
+        ));
+        let err = cache
+            .insert_or_increment("b", 10, || "b")
+            .map(|_| ())
+            .unwrap_err();
+        assert_eq!(err.entry(), Some("b"));
+        assert_eq!(
+            err.to_string(),
+            "Entry 'b' has the same cache ID as cached entry 'a'"
+        );
+        assert!(cache.decrement("b", Instant::now()).is_none());
+        assert!(cache.remove("b").is_none());
+        assert_eq!(cache.get(id).map(|entry| entry.refcount), Some(1));
+    }
+}
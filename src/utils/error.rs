@@ -4,6 +4,18 @@ pub enum RdbErr {
     BadHeader,
     TooSmall,
     NameTooLong,
+    /// An entry was found but it stores a different type than requested.
+    TypeMismatch {
+        entry: String,
+        expected: u32,
+        found: u32,
+    },
+    /// An entry was found but its payload could not be deserialized.
+    Decode {
+        entry: String,
+        type_tag: u32,
+        reason: String,
+    },
 }
 
 impl std::fmt::Display for RdbErr {
@@ -12,7 +24,23 @@ impl std::fmt::Display for RdbErr {
             RdbErr::Io() => write!(f, "RDB file I/O error"),
             RdbErr::BadHeader => write!(f, "RDB file has an invalid header"),
             RdbErr::TooSmall => write!(f, "RDB file is too small"),
-            RdbErr::NameTooLong => write!(f, "RDB entry name exceeds 63 bytes"),
+            RdbErr::NameTooLong => write!(f, "RDB entry name exceeds 65535 bytes"),
+            RdbErr::TypeMismatch {
+                entry,
+                expected,
+                found,
+            } => write!(
+                f,
+                "RDB entry '{entry}' type mismatch (expected {expected:#010X}, found {found:#010X})"
+            ),
+            RdbErr::Decode {
+                entry,
+                type_tag,
+                reason,
+            } => write!(
+                f,
+                "failed to decode RDB entry '{entry}' (type {type_tag:#010X}): {reason}"
+            ),
         }
     }
 }
@@ -33,6 +61,7 @@ pub enum NorenError {
     InvalidMaterial(String),
     InvalidAtlas(String),
     InvalidFont(String),
+    InvalidAudio(String),
     InvalidModel(String),
     InvalidShaderLayout(Vec<crate::ShaderValidationError>),
     InvalidShaderState(String),
@@ -41,6 +70,31 @@ pub enum NorenError {
     YAMLError(serde_yaml::Error),
     IOFailure(std::io::Error),
     RDBFileError(RdbErr),
+    /// An entry exists in a database file but could not be decoded.
+    EntryDecodeFailure {
+        entry: String,
+        /// Type tag stored with the entry, which tells a stale file apart.
+        type_tag: u32,
+        reason: String,
+    },
+    /// Two entry names hash to the same cache ID.
+    EntryIdCollision {
+        entry: String,
+        /// Name of the entry already cached under that ID.
+        cached: String,
+    },
+}
+
+impl NorenError {
+    /// Returns the database entry the error refers to, if known.
+    pub fn entry(&self) -> Option<&str> {
+        match self {
+            NorenError::EntryDecodeFailure { entry, .. }
+            | NorenError::EntryIdCollision { entry, .. }
+            | NorenError::RDBFileError(RdbErr::TypeMismatch { entry, .. }) => Some(entry),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for NorenError {
@@ -62,6 +116,9 @@ impl std::fmt::Display for NorenError {
             NorenError::InvalidFont(reason) => {
                 write!(f, "Invalid font: {}", reason)
             }
+            NorenError::InvalidAudio(reason) => {
+                write!(f, "Invalid audio: {}", reason)
+            }
             NorenError::InvalidModel(reason) => {
                 write!(f, "Invalid model layout: {}", reason)
             }
@@ -91,6 +148,20 @@ impl std::fmt::Display for NorenError {
                 write!(f, "Furikake integration failed: {}", reason)
             }
             NorenError::RDBFileError(rdb_err) => write!(f, "RDB file error: {}", rdb_err),
+            NorenError::EntryDecodeFailure {
+                entry,
+                type_tag,
+                reason,
+            } => write!(
+                f,
+                "Failed to decode entry '{}' (type {:#010X}): {}",
+                entry, type_tag, reason
+            ),
+            NorenError::EntryIdCollision { entry, cached } => write!(
+                f,
+                "Entry '{}' has the same cache ID as cached entry '{}'",
+                entry, cached
+            ),
             NorenError::IOFailure(error) => write!(f, "I/O failure: {}", error),
             NorenError::JSONError(error) => write!(f, "JSON processing error: {}", error),
             NorenError::YAMLError(error) => write!(f, "YAML processing error: {}", error),
@@ -100,7 +171,18 @@ impl std::fmt::Display for NorenError {
 
 impl From<RdbErr> for NorenError {
     fn from(value: RdbErr) -> Self {
-        NorenError::RDBFileError(value)
+        match value {
+            RdbErr::Decode {
+                entry,
+                type_tag,
+                reason,
+            } => NorenError::EntryDecodeFailure {
+                entry,
+                type_tag,
+                reason,
+            },
+            other => NorenError::RDBFileError(other),
+        }
     }
 }
 
@@ -128,6 +210,12 @@ impl From<furikake::error::FurikakeError> for NorenError {
     }
 }
 
+impl From<symphonia::core::errors::Error> for NorenError {
+    fn from(value: symphonia::core::errors::Error) -> Self {
+        NorenError::InvalidAudio(value.to_string())
+    }
+}
+
 impl std::error::Error for NorenError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         None
@@ -176,6 +264,10 @@ mod tests {
             format!("{}", NorenError::InvalidFont("reason".into())),
             "Invalid font: reason"
         );
+        assert_eq!(
+            format!("{}", NorenError::InvalidAudio("reason".into())),
+            "Invalid audio: reason"
+        );
         assert_eq!(
             format!("{}", NorenError::InvalidShaderState("bad state".into())),
             "Invalid shader state: bad state"
@@ -216,7 +308,48 @@ mod tests {
         assert_eq!(format!("{}", RdbErr::TooSmall), "RDB file is too small");
         assert_eq!(
             format!("{}", RdbErr::NameTooLong),
-            "RDB entry name exceeds 63 bytes"
+            "RDB entry name exceeds 65535 bytes"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                RdbErr::Decode {
+                    entry: "geometry/quad".into(),
+                    type_tag: 0x1234,
+                    reason: "unexpected end of file".into(),
+                }
+            ),
+            "failed to decode RDB entry 'geometry/quad' (type 0x00001234): unexpected end of file"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                RdbErr::TypeMismatch {
+                    entry: "imagery/sky".into(),
+                    expected: 0x1,
+                    found: 0x2,
+                }
+            ),
+            "RDB entry 'imagery/sky' type mismatch (expected 0x00000001, found 0x00000002)"
+        );
+    }
+
+    #[test]
+    fn decode_errors_keep_entry_name() {
+        let err = NorenError::from(RdbErr::Decode {
+            entry: "imagery/grass".into(),
+            type_tag: 7,
+            reason: "bad length".into(),
+        });
+        assert_eq!(err.entry(), Some("imagery/grass"));
+        assert_eq!(
+            format!("{}", err),
+            "Failed to decode entry 'imagery/grass' (type 0x00000007): bad length"
         );
+        assert!(matches!(
+            err,
+            NorenError::EntryDecodeFailure { type_tag: 7, .. }
+        ));
+        assert_eq!(NorenError::from(RdbErr::BadHeader).entry(), None);
     }
 }
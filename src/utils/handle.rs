@@ -0,0 +1,172 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    hash::{BuildHasherDefault, Hasher},
+    ops::Deref,
+    sync::{Arc, mpsc},
+};
+
+use super::rdbfile::{FNV_OFFSET_BASIS, fnv1a64_extend};
+
+/// 64-bit ID of a database entry name, hashed once with FNV-1a.
+///
+/// Caches are keyed by this ID, so lookups through it skip hashing the name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntryId(u64);
+
+impl EntryId {
+    /// Hashes an entry name.
+    pub fn new(entry: &str) -> Self {
+        Self(fnv1a64_extend(FNV_OFFSET_BASIS, entry.as_bytes()))
+    }
+
+    /// Returns the raw 64-bit value.
+    pub fn value(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<&str> for EntryId {
+    fn from(entry: &str) -> Self {
+        Self::new(entry)
+    }
+}
+
+impl From<&String> for EntryId {
+    fn from(entry: &String) -> Self {
+        Self::new(entry)
+    }
+}
+
+/// Passes [`EntryId`]s through unchanged; they are already hashes.
+#[derive(Default)]
+pub(crate) struct EntryIdHasher(u64);
+
+impl Hasher for EntryIdHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = fnv1a64_extend(self.0 ^ FNV_OFFSET_BASIS, bytes);
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.0 = value;
+    }
+}
+
+pub(crate) type EntryIdMap<V> = HashMap<EntryId, V, BuildHasherDefault<EntryIdHasher>>;
+
+/// A cached GPU asset that holds one cache reference for all of its clones.
+///
+/// Cloning is an `Arc` bump. When the last clone drops, the reference is
+/// queued for release and handed back on the owning module's next fetch or
+/// `unload_pulse`.
+pub struct AssetHandle<T> {
+    inner: Arc<HandleInner<T>>,
+}
+
+struct HandleInner<T> {
+    id: EntryId,
+    payload: T,
+    releases: mpsc::Sender<EntryId>,
+}
+
+impl<T> Drop for HandleInner<T> {
+    fn drop(&mut self) {
+        // The module may already be gone, in which case nothing needs releasing.
+        let _ = self.releases.send(self.id);
+    }
+}
+
+impl<T> AssetHandle<T> {
+    /// Returns the ID of the entry the handle refers to.
+    pub fn id(&self) -> EntryId {
+        self.inner.id
+    }
+}
+
+impl<T> Clone for AssetHandle<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Deref for AssetHandle<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner.payload
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for AssetHandle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AssetHandle")
+            .field("id", &self.inner.id)
+            .field("payload", &self.inner.payload)
+            .finish()
+    }
+}
+
+/// Queue of references released by dropped [`AssetHandle`]s.
+pub(crate) struct HandleReleases {
+    tx: mpsc::Sender<EntryId>,
+    rx: mpsc::Receiver<EntryId>,
+}
+
+impl Default for HandleReleases {
+    fn default() -> Self {
+        let (tx, rx) = mpsc::channel();
+        Self { tx, rx }
+    }
+}
+
+impl HandleReleases {
+    /// Wraps a payload whose cache reference was already taken.
+    pub(crate) fn handle<T>(&self, id: EntryId, payload: T) -> AssetHandle<T> {
+        AssetHandle {
+            inner: Arc::new(HandleInner {
+                id,
+                payload,
+                releases: self.tx.clone(),
+            }),
+        }
+    }
+
+    /// Returns the IDs released since the last drain, one per dropped handle.
+    pub(crate) fn drain(&self) -> Vec<EntryId> {
+        self.rx.try_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EntryId, EntryIdMap, HandleReleases};
+
+    #[test]
+    fn last_clone_releases_the_reference() {
+        let releases = HandleReleases::default();
+        let id = EntryId::new("geometry/quad");
+        let handle = releases.handle(id, 42u32);
+        let clone = handle.clone();
+        assert_eq!(*clone, 42);
+
+        drop(handle);
+        assert!(releases.drain().is_empty());
+        drop(clone);
+        assert_eq!(releases.drain(), vec![id]);
+    }
+
+    #[test]
+    fn entry_ids_key_maps_like_names() {
+        let mut map = EntryIdMap::default();
+        map.insert(EntryId::new("a"), 1);
+        map.insert(EntryId::from("b"), 2);
+        assert_eq!(map.get(&EntryId::from(&"a".to_string())), Some(&1));
+        assert_ne!(EntryId::new("a"), EntryId::new("b"));
+    }
+}
@@ -0,0 +1,193 @@
+use serde::de::DeserializeOwned;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use super::rdbfile::{RDBEntryMeta, RDBRawEntry, RDBType, RDBView};
+use crate::error::RdbErr;
+
+/// A module's RDB files stacked base first, with patches layered on top.
+///
+/// Lookups resolve from the highest-priority layer that holds the entry, so a
+/// patch shadows the base entry of the same name. Clones share the mapped files.
+#[derive(Clone, Default)]
+pub struct RDBLayers {
+    /// Highest priority first.
+    layers: Arc<Vec<RDBView>>,
+}
+
+impl RDBLayers {
+    /// Stacks `layers`, given lowest priority (the base database) first.
+    pub fn new(layers: impl IntoIterator<Item = RDBView>) -> Self {
+        let mut layers: Vec<RDBView> = layers.into_iter().collect();
+        layers.reverse();
+        Self {
+            layers: Arc::new(layers),
+        }
+    }
+
+    /// Returns true when no layer is loaded.
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Returns the number of loaded layers.
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns whether any layer holds an entry with the exact given name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    /// Returns the highest-priority layer holding `name`.
+    pub fn find(&self, name: &str) -> Option<&RDBView> {
+        self.layers.iter().find(|layer| layer.contains(name))
+    }
+
+    /// Fetches `name` from the highest-priority layer holding it.
+    pub fn fetch<T: DeserializeOwned + RDBType>(&self, name: &str) -> Result<T, RdbErr> {
+        self.find(name).ok_or(RdbErr::BadHeader)?.fetch(name)
+    }
+
+    /// Detaches `name` from the highest-priority layer holding it.
+    pub fn raw_entry(&self, name: &str) -> Option<RDBRawEntry> {
+        self.find(name)?.raw_entry(name)
+    }
+
+    /// Returns the visible entries of every layer, skipping shadowed ones.
+    ///
+    /// Base entries keep their file order; entries only found in patches follow.
+    pub fn entries(&self) -> Vec<RDBEntryMeta> {
+        let mut merged: Vec<RDBEntryMeta> = Vec::new();
+        let mut index = HashMap::new();
+        for layer in self.layers.iter().rev() {
+            for meta in layer.entries() {
+                match index.get(&meta.name) {
+                    Some(&idx) => merged[idx] = meta,
+                    None => {
+                        index.insert(meta.name.clone(), merged.len());
+                        merged.push(meta);
+                    }
+                }
+            }
+        }
+        merged
+    }
+
+    /// Returns the visible entries whose names start with `prefix`, in name order.
+    pub fn entries_with_prefix(&self, prefix: &str) -> Vec<RDBEntryMeta> {
+        let mut merged = BTreeMap::new();
+        for layer in self.layers.iter().rev() {
+            for meta in layer.entries_with_prefix(prefix) {
+                merged.insert(meta.name.clone(), meta);
+            }
+        }
+        merged.into_values().collect()
+    }
+
+    /// Returns the names of visible entries that were added, removed, or
+    /// rewritten relative to `previous`, in name order.
+    ///
+    /// Changed `#` sidecars are reported under the entry that owns them.
+    pub fn changed_entries(&self, previous: &RDBLayers) -> Vec<String> {
+        let index = |layers: &RDBLayers| -> HashMap<String, RDBEntryMeta> {
+            layers
+                .entries()
+                .into_iter()
+                .map(|meta| (meta.name.clone(), meta))
+                .collect()
+        };
+        let owner = |name: &str| {
+            name.split_once('#')
+                .map_or(name, |(owner, _)| owner)
+                .to_string()
+        };
+        let current = index(self);
+        let old = index(previous);
+
+        let mut changed = BTreeSet::new();
+        for (name, meta) in &current {
+            if !old.get(name).is_some_and(|prev| same_contents(prev, meta)) {
+                changed.insert(owner(name));
+            }
+        }
+        for name in old.keys().filter(|name| !current.contains_key(*name)) {
+            changed.insert(owner(name));
+        }
+        changed.into_iter().collect()
+    }
+}
+
+/// Compares two entry records by content; the offset only counts when there is no checksum.
+fn same_contents(a: &RDBEntryMeta, b: &RDBEntryMeta) -> bool {
+    a.type_tag == b.type_tag
+        && a.len == b.len
+        && a.raw_len == b.raw_len
+        && a.compression == b.compression
+        && a.pod == b.pod
+        && a.checksum == b.checksum
+        && (a.checksum != 0 || a.offset == b.offset)
+}
+
+impl From<Option<RDBView>> for RDBLayers {
+    fn from(view: Option<RDBView>) -> Self {
+        Self::new(view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RDBLayers;
+    use crate::{RDBFile, RDBView};
+
+    fn layer(name: &str, entries: &[(&str, u32)]) -> RDBView {
+        let mut rdb = RDBFile::new();
+        for (entry, value) in entries {
+            rdb.add(entry, value).expect("add entry");
+        }
+        let path = format!("target/layers_{name}.rdb");
+        rdb.save(&path).expect("save layer");
+        RDBView::load(&path).expect("load layer")
+    }
+
+    #[test]
+    fn patches_shadow_base_entries() {
+        let base = layer("base", &[("a", 1), ("b", 2), ("c", 3)]);
+        let dlc = layer("dlc", &[("d", 40), ("b", 20)]);
+        let hotfix = layer("hotfix", &[("b", 200)]);
+        let layers = RDBLayers::new([base, dlc, hotfix]);
+
+        assert_eq!(layers.fetch::<u32>("a").unwrap(), 1);
+        assert_eq!(layers.fetch::<u32>("b").unwrap(), 200);
+        assert_eq!(layers.fetch::<u32>("d").unwrap(), 40);
+        assert!(layers.fetch::<u32>("missing").is_err());
+
+        let names: Vec<String> = layers.entries().into_iter().map(|m| m.name).collect();
+        assert_eq!(names, vec!["a", "b", "c", "d"]);
+        let prefixed: Vec<String> = layers
+            .entries_with_prefix("b")
+            .into_iter()
+            .map(|m| m.name)
+            .collect();
+        assert_eq!(prefixed, vec!["b"]);
+    }
+
+    #[test]
+    fn changed_entries_reports_rewritten_names() {
+        let old = RDBLayers::new([layer("reload_old", &[("a", 1), ("b", 2), ("c", 3)])]);
+        let mut rdb = RDBFile::new();
+        rdb.add("c", &3u32).expect("add c");
+        rdb.add("b", &20u32).expect("add b");
+        rdb.add("d", &4u32).expect("add d");
+        rdb.add_pod_slice("a#indices", &[0u32, 1])
+            .expect("add sidecar");
+        rdb.save("target/layers_reload_new.rdb").expect("save");
+        let new = RDBLayers::new([RDBView::load("target/layers_reload_new.rdb").expect("load")]);
+
+        assert_eq!(new.changed_entries(&old), vec!["a", "b", "d"]);
+        assert!(new.changed_entries(&new).is_empty());
+    }
+}
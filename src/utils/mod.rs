@@ -1,6 +1,12 @@
+pub mod bundle;
 pub mod data_cache;
 pub mod error;
+pub mod handle;
+pub mod layers;
+pub mod range_allocator;
 pub mod rdbfile;
 
 pub use data_cache::*;
 pub use error::*;
+pub use handle::*;
+pub use range_allocator::*;
@@ -0,0 +1,201 @@
+use std::collections::BTreeMap;
+
+/// First-fit sub-allocator handing out byte ranges of a growable buffer.
+///
+/// Sizes are rounded up to the alignment, so every range starts aligned. Freed
+/// ranges are coalesced with their free neighbours.
+#[derive(Debug, Clone)]
+pub struct RangeAllocator {
+    capacity: u32,
+    alignment: u32,
+    /// Free ranges as offset -> size; never adjacent to each other.
+    free: BTreeMap<u32, u32>,
+}
+
+impl RangeAllocator {
+    /// Creates an allocator managing `capacity` bytes, all of them free.
+    pub fn new(capacity: u32, alignment: u32) -> Self {
+        let alignment = alignment.max(1);
+        let capacity = capacity - capacity % alignment;
+        let mut free = BTreeMap::new();
+        if capacity > 0 {
+            free.insert(0, capacity);
+        }
+        Self {
+            capacity,
+            alignment,
+            free,
+        }
+    }
+
+    /// Returns the number of bytes managed by the allocator.
+    pub fn capacity(&self) -> u32 {
+        self.capacity
+    }
+
+    /// Returns the number of free bytes across all ranges.
+    pub fn free_bytes(&self) -> u32 {
+        self.free.values().sum()
+    }
+
+    /// Returns the number of allocated bytes, including alignment padding.
+    pub fn used_bytes(&self) -> u32 {
+        self.capacity - self.free_bytes()
+    }
+
+    /// Returns the size of the largest free range.
+    pub fn largest_free(&self) -> u32 {
+        self.free.values().copied().max().unwrap_or(0)
+    }
+
+    /// Returns the number of disjoint free ranges.
+    pub fn free_ranges(&self) -> usize {
+        self.free.len()
+    }
+
+    fn padded(&self, size: u32) -> u32 {
+        size.next_multiple_of(self.alignment)
+    }
+
+    /// Reserves `size` bytes from the lowest free range that fits, returning its offset.
+    ///
+    /// Zero-sized requests succeed at offset zero without reserving anything.
+    pub fn allocate(&mut self, size: u32) -> Option<u32> {
+        if size == 0 {
+            return Some(0);
+        }
+
+        let size = self.padded(size);
+        let (&offset, &free_size) = self.free.iter().find(|&(_, &free)| free >= size)?;
+        self.free.remove(&offset);
+        if free_size > size {
+            self.free.insert(offset + size, free_size - size);
+        }
+        Some(offset)
+    }
+
+    /// Returns a range obtained from [`RangeAllocator::allocate`] to the free list.
+    pub fn free(&mut self, offset: u32, size: u32) {
+        if size == 0 {
+            return;
+        }
+
+        let mut start = offset;
+        let mut end = offset + self.padded(size);
+        debug_assert!(end <= self.capacity, "range outside the allocator");
+
+        if let Some((&prev, &prev_size)) = self.free.range(..start).next_back() {
+            debug_assert!(prev + prev_size <= start, "double free");
+            if prev + prev_size == start {
+                self.free.remove(&prev);
+                start = prev;
+            }
+        }
+        if let Some(next_size) = self.free.remove(&end) {
+            end += next_size;
+        }
+        self.free.insert(start, end - start);
+    }
+
+    /// Extends the managed range to `capacity` bytes; the new tail is free.
+    pub fn grow(&mut self, capacity: u32) {
+        let capacity = capacity - capacity % self.alignment;
+        if capacity <= self.capacity {
+            return;
+        }
+
+        let old = self.capacity;
+        self.capacity = capacity;
+        self.free(old, capacity - old);
+    }
+
+    /// Plans packing `live` ranges (offset, size) to the front of the buffer.
+    ///
+    /// Returns `(old offset, new offset, size)` moves in offset order and
+    /// resets the allocator so only the packed ranges are in use.
+    pub fn compact(&mut self, live: &[(u32, u32)]) -> Vec<(u32, u32, u32)> {
+        let mut live: Vec<(u32, u32)> =
+            live.iter().copied().filter(|&(_, size)| size > 0).collect();
+        live.sort_unstable();
+        live.dedup_by_key(|(offset, _)| *offset);
+
+        let mut next = 0;
+        let moves = live
+            .into_iter()
+            .map(|(offset, size)| {
+                let moved = (offset, next, size);
+                next += self.padded(size);
+                moved
+            })
+            .collect();
+
+        self.free.clear();
+        if next < self.capacity {
+            self.free.insert(next, self.capacity - next);
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RangeAllocator;
+
+    #[test]
+    fn allocations_reuse_freed_ranges() {
+        let mut alloc = RangeAllocator::new(64, 4);
+        let a = alloc.allocate(16).unwrap();
+        let b = alloc.allocate(6).unwrap();
+        let c = alloc.allocate(16).unwrap();
+        assert_eq!((a, b, c), (0, 16, 24));
+        assert_eq!(alloc.used_bytes(), 40);
+
+        alloc.free(b, 6);
+        assert_eq!(alloc.allocate(8), Some(16));
+        assert_eq!(alloc.allocate(32), None);
+        assert_eq!(alloc.allocate(24), Some(40));
+        assert_eq!(alloc.free_bytes(), 0);
+    }
+
+    #[test]
+    fn freed_neighbours_coalesce() {
+        let mut alloc = RangeAllocator::new(48, 1);
+        let ranges: Vec<u32> = (0..3).map(|_| alloc.allocate(16).unwrap()).collect();
+
+        alloc.free(ranges[0], 16);
+        alloc.free(ranges[2], 16);
+        assert_eq!(alloc.free_ranges(), 2);
+        assert_eq!(alloc.largest_free(), 16);
+
+        alloc.free(ranges[1], 16);
+        assert_eq!(alloc.free_ranges(), 1);
+        assert_eq!(alloc.largest_free(), 48);
+    }
+
+    #[test]
+    fn grow_extends_the_trailing_free_range() {
+        let mut alloc = RangeAllocator::new(32, 4);
+        alloc.allocate(24).unwrap();
+        assert_eq!(alloc.allocate(16), None);
+
+        alloc.grow(64);
+        assert_eq!(alloc.capacity(), 64);
+        assert_eq!(alloc.free_ranges(), 1);
+        assert_eq!(alloc.allocate(16), Some(24));
+    }
+
+    #[test]
+    fn compact_packs_live_ranges() {
+        let mut alloc = RangeAllocator::new(64, 4);
+        let a = alloc.allocate(8).unwrap();
+        let b = alloc.allocate(8).unwrap();
+        let c = alloc.allocate(10).unwrap();
+        alloc.free(a, 8);
+
+        let moves = alloc.compact(&[(c, 10), (b, 8), (b, 8)]);
+        assert_eq!(moves, vec![(8, 0, 8), (16, 8, 10)]);
+        assert_eq!(alloc.used_bytes(), 20);
+        assert_eq!(alloc.free_ranges(), 1);
+        assert_eq!(alloc.allocate(4), Some(20));
+    }
+}
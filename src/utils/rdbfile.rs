@@ -1,53 +1,290 @@
 use bytemuck::{Pod, Zeroable};
 use memmap2::{Mmap, MmapMut};
-use serde::{Serialize, de::DeserializeOwned};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::{
-    collections::HashMap,
+    borrow::Cow,
+    collections::BTreeMap,
     fs::{File, OpenOptions},
-    io::{Seek, SeekFrom},
-    path::Path,
+    io::{Read, Seek, SeekFrom, Write},
+    ops::{Bound, Range},
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
 const MAGIC: [u8; 4] = *b"RDB0";
-const VERSION: u16 = 1;
+const VERSION: u16 = 2;
+/// Oldest container version the readers still understand.
+const MIN_VERSION: u16 = 1;
+/// Longest entry name accepted by the writer.
+pub const MAX_NAME_LEN: usize = u16::MAX as usize;
+pub(crate) const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+/// Bits of [`EntryRecord::flags`] holding the entry's compression codec.
+const ENTRY_CODEC_MASK: u32 = 0xFF;
+/// Set in [`EntryRecord::flags`] when the entry holds a raw POD slice instead of bincode.
+const ENTRY_FLAG_POD: u32 = 1 << 8;
+/// Set in [`EntryRecord::flags`] when the record describes a replaced entry.
+const ENTRY_FLAG_TOMBSTONE: u32 = 1 << 9;
+/// Alignment of the data section and of every blob written into it.
+const ENTRY_ALIGN: usize = 16;
 use std::any::type_name;
 
 use crate::error::RdbErr;
 
-/// Simple, portable FNV-1a 64-bit hash of a string.
+/// Folds `bytes` into a running FNV-1a 64-bit hash.
 #[inline]
-fn fnv1a64(s: &str) -> u64 {
-    let mut h: u64 = 0xcbf29ce484222325; // offset basis
-    for &b in s.as_bytes() {
+pub(crate) fn fnv1a64_extend(mut h: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
         h ^= b as u64;
         h = h.wrapping_mul(0x100000001b3); // FNV prime
     }
     h
 }
 
+/// Simple, portable FNV-1a 64-bit hash of a string.
+#[inline]
+fn fnv1a64(s: &str) -> u64 {
+    fnv1a64_extend(FNV_OFFSET_BASIS, s.as_bytes())
+}
+
+/// Content hash stored alongside each entry, folded down to 32 bits.
+#[inline]
+fn entry_checksum(bytes: &[u8]) -> u32 {
+    let h = fnv1a64_extend(FNV_OFFSET_BASIS, bytes);
+    (h ^ (h >> 32)) as u32
+}
+
 /// A cross-device, cross-process "type hash".
 /// Stable as long as the type's *name/path* doesn't change.
 fn portable_type_hash<T>() -> u64 {
     fnv1a64(type_name::<T>())
 }
 
+/// Packs four ASCII bytes into an RDB type tag, e.g. `fourcc(b"GEOM")`.
+pub const fn fourcc(code: &[u8; 4]) -> u32 {
+    u32::from_le_bytes(*code)
+}
+
+/// A value that can be stored in an RDB entry.
+///
+/// Asset types set [`RDBType::TYPE_TAG`] to a fixed tag so renaming or moving
+/// the Rust type keeps existing files readable. Types without one are tagged
+/// by a hash of their type name.
+///
+/// A type whose stored layout changes takes a new tag, lists the old one in
+/// [`RDBType::OLDER_TAGS`] and overrides [`RDBType::upgrade`] to read it.
+pub trait RDBType {
+    /// Tag written for this type, or `None` to use the type-name hash.
+    const TYPE_TAG: Option<u32> = None;
+
+    /// Tags this type was written under before its layout last changed.
+    const OLDER_TAGS: &'static [u32] = &[];
+
+    /// Decodes a payload stored under an older tag of this type: one of
+    /// [`RDBType::OLDER_TAGS`] or the type-name hash.
+    fn upgrade(name: &str, type_tag: u32, bytes: &[u8]) -> Result<Self, RdbErr>
+    where
+        Self: DeserializeOwned,
+    {
+        from_bytes(name, type_tag, bytes)
+    }
+}
+
+macro_rules! impl_rdb_type {
+    ($($ty:ty),* $(,)?) => {
+        $(impl RDBType for $ty {})*
+    };
+}
+
+impl_rdb_type!(
+    bool, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, char, String,
+);
+
+impl<T: RDBType> RDBType for Vec<T> {}
+impl<T: RDBType> RDBType for Option<T> {}
+impl<T: RDBType, const N: usize> RDBType for [T; N] {}
+impl<T: RDBType + ?Sized> RDBType for &T {
+    const TYPE_TAG: Option<u32> = T::TYPE_TAG;
+    const OLDER_TAGS: &'static [u32] = T::OLDER_TAGS;
+}
+
 /// Returns the portable type tag used to identify serialized values in an RDB.
+///
+/// Types with an [`RDBType::TYPE_TAG`] use their assigned tag; everything else
+/// falls back to a hash of the type name.
 #[inline]
-pub fn type_tag_for<T>() -> u32 {
+pub fn type_tag_for<T: RDBType>() -> u32 {
+    match T::TYPE_TAG {
+        Some(tag) => tag,
+        None => legacy_type_tag_for::<T>(),
+    }
+}
+
+/// Returns the name-hash tag written for `T` before stable tags existed.
+#[inline]
+pub fn legacy_type_tag_for<T>() -> u32 {
     portable_type_hash::<T>() as u32
 }
 
+/// Checks the tag stored for `name` against `T`.
+fn check_type_tag<T: RDBType>(name: &str, found: u32) -> Result<(), RdbErr> {
+    if tag_matches::<T>(found) {
+        Ok(())
+    } else {
+        Err(RdbErr::TypeMismatch {
+            entry: name.to_string(),
+            expected: type_tag_for::<T>(),
+            found,
+        })
+    }
+}
+
+/// Returns whether a stored tag was written for `T` by this or an older build.
+fn tag_matches<T: RDBType>(found: u32) -> bool {
+    found == type_tag_for::<T>()
+        || found == legacy_type_tag_for::<T>()
+        || T::OLDER_TAGS.contains(&found)
+}
+
 fn to_bytes<T: Serialize>(value: &T) -> Vec<u8> {
     bincode::serialize(value).unwrap()
 }
 
-fn from_bytes<T: DeserializeOwned>(bytes: &[u8]) -> T {
-    bincode::deserialize(bytes).unwrap()
+/// Decodes an entry payload, reporting failures against the entry name.
+pub(crate) fn from_bytes<T: DeserializeOwned>(
+    name: &str,
+    type_tag: u32,
+    bytes: &[u8],
+) -> Result<T, RdbErr> {
+    bincode::deserialize(bytes).map_err(|err| RdbErr::Decode {
+        entry: name.to_string(),
+        type_tag,
+        reason: err.to_string(),
+    })
+}
+
+/// Decodes the serialized payload of an entry stored for `name` under
+/// `type_tag`, upgrading payloads written under an older tag of `T`.
+pub fn decode_payload<T: DeserializeOwned + RDBType>(
+    name: &str,
+    type_tag: u32,
+    bytes: &[u8],
+) -> Result<T, RdbErr> {
+    check_type_tag::<T>(name, type_tag)?;
+    if type_tag == type_tag_for::<T>() {
+        from_bytes(name, type_tag, bytes)
+    } else {
+        T::upgrade(name, type_tag, bytes)
+    }
+}
+
+/// Codec applied to an entry's serialized bytes before they are stored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RDBCompression {
+    #[default]
+    None,
+    Zstd,
+    Lz4,
+}
+
+impl RDBCompression {
+    /// Returns the lowercase codec name used in build specs.
+    pub fn name(self) -> &'static str {
+        match self {
+            RDBCompression::None => "none",
+            RDBCompression::Zstd => "zstd",
+            RDBCompression::Lz4 => "lz4",
+        }
+    }
+
+    fn flags(self) -> u32 {
+        match self {
+            RDBCompression::None => 0,
+            RDBCompression::Zstd => 1,
+            RDBCompression::Lz4 => 2,
+        }
+    }
+
+    fn from_flags(flags: u32) -> Option<Self> {
+        match flags & ENTRY_CODEC_MASK {
+            0 => Some(RDBCompression::None),
+            1 => Some(RDBCompression::Zstd),
+            2 => Some(RDBCompression::Lz4),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for RDBCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Compresses `raw` with `codec`, keeping the raw bytes when that is not smaller.
+fn compress(raw: Vec<u8>, codec: RDBCompression) -> Result<(RDBCompression, Vec<u8>), RdbErr> {
+    let packed = match codec {
+        RDBCompression::None => return Ok((RDBCompression::None, raw)),
+        RDBCompression::Zstd => zstd::bulk::compress(&raw, zstd::DEFAULT_COMPRESSION_LEVEL)?,
+        RDBCompression::Lz4 => lz4_flex::block::compress(&raw),
+    };
+
+    if packed.len() < raw.len() {
+        Ok((codec, packed))
+    } else {
+        Ok((RDBCompression::None, raw))
+    }
+}
+
+/// Returns the serialized bytes of `entry`, decompressing `stored` if needed.
+fn decompress<'a>(entry: &Entry, stored: &'a [u8]) -> Result<Cow<'a, [u8]>, RdbErr> {
+    let raw_len = usize::try_from(entry.raw_len).map_err(|_| RdbErr::BadHeader)?;
+    let raw = match entry.compression {
+        RDBCompression::None => return Ok(Cow::Borrowed(stored)),
+        RDBCompression::Zstd => {
+            zstd::bulk::decompress(stored, raw_len).map_err(|err| err.to_string())
+        }
+        RDBCompression::Lz4 => {
+            lz4_flex::block::decompress(stored, raw_len).map_err(|err| err.to_string())
+        }
+    };
+
+    match raw {
+        Ok(raw) if raw.len() == raw_len => Ok(Cow::Owned(raw)),
+        Ok(raw) => Err(decompress_error(
+            entry,
+            format!("expected {raw_len} bytes, got {}", raw.len()),
+        )),
+        Err(reason) => Err(decompress_error(entry, reason)),
+    }
+}
+
+fn decompress_error(entry: &Entry, reason: String) -> RdbErr {
+    RdbErr::Decode {
+        entry: entry.name.clone(),
+        type_tag: entry.type_tag,
+        reason: format!("{} decompression failed: {reason}", entry.compression),
+    }
+}
+
+/// Deserializes a bincode entry from its stored bytes.
+fn decode_entry<T: DeserializeOwned + RDBType>(entry: &Entry, stored: &[u8]) -> Result<T, RdbErr> {
+    check_type_tag::<T>(&entry.name, entry.type_tag)?;
+    if entry.pod {
+        return Err(RdbErr::Decode {
+            entry: entry.name.clone(),
+            type_tag: entry.type_tag,
+            reason: "entry holds a raw POD slice".to_string(),
+        });
+    }
+
+    let bytes = decompress(entry, stored)?;
+    decode_payload::<T>(&entry.name, entry.type_tag, &bytes)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{RDBFile, name64, portable_type_hash};
+    use super::{MAX_NAME_LEN, RDBFile, check_name, portable_type_hash};
     use crate::error::RdbErr;
     #[test]
     fn same_everywhere_for_same_type() {
@@ -57,23 +294,21 @@ mod tests {
     }
 
     #[test]
-    fn name64_rejects_long_names() {
-        let long = "abc".repeat(30); // 90 chars
-        assert!(name64(&long).is_err());
+    fn check_name_accepts_names_up_to_the_limit() {
+        assert!(check_name(&"abc".repeat(30)).is_ok());
+        assert!(check_name(&"x".repeat(MAX_NAME_LEN)).is_ok());
     }
 
     #[test]
-    fn name64_sets_nul_terminator_within_bounds() {
-        let name = "abc".repeat(10);
-        let encoded = name64(&name).expect("encoding name within limit");
-        assert_eq!(&encoded[..name.len()], name.as_bytes());
-        assert_eq!(encoded[name.len()], 0);
+    fn check_name_rejects_names_past_the_limit() {
+        let long = "x".repeat(MAX_NAME_LEN + 1);
+        assert!(matches!(check_name(&long), Err(RdbErr::NameTooLong)));
     }
 
     #[test]
     fn add_rejects_long_names_without_panic() {
         let mut rdb = RDBFile::new();
-        let long = "x".repeat(80);
+        let long = "x".repeat(MAX_NAME_LEN + 1);
         let err = rdb.add(&long, &123u32).expect_err("add long name");
         assert!(matches!(err, RdbErr::NameTooLong));
         assert!(rdb.entries.is_empty());
@@ -105,82 +340,346 @@ mod tests {
 #[derive(Clone, Copy, Zeroable, Pod)]
 struct Header {
     magic: [u8; 4],   // "RDB0"
-    version: u16,     // 1
+    version: u16,     // 1 or 2
     reserved: u16,    // alignment / future flags
     entry_count: u32, // number of entries
 }
 
+/// Follows [`Header`] in version 2 files.
 #[repr(C)]
-#[derive(Clone, Copy, Zeroable)]
-pub struct Entry {
-    pub type_tag: u32,  // e.g. u32::from_le_bytes(*b"GEOM")
-    pub offset: u64,    // absolute file offset to blob
-    pub len: u64,       // blob length
-    pub name: [u8; 64], // ASCII/UTF-8 (nul padded)
+#[derive(Clone, Copy, Zeroable, Pod)]
+struct HeaderExt {
+    flags: u32,     // future container flags
+    names_len: u32, // string table size in bytes
+    digest: u64,    // FNV-1a over everything after the table locator
+}
+
+/// Follows [`HeaderExt`] in version 2 files; appends move the entry table.
+#[repr(C)]
+#[derive(Clone, Copy, Zeroable, Pod)]
+struct TableLocator {
+    table_offset: u64, // file offset of the current entry table
+    data_offset: u64,  // file offset of the data section
+    dead_len: u64,     // bytes held by tombstones and superseded tables
+}
+
+/// Everything written at the start of a version 2 file.
+#[derive(Clone, Copy)]
+struct FileHeader {
+    hdr: Header,
+    ext: HeaderExt,
+    locator: TableLocator,
 }
 
-unsafe impl Pod for Entry {}
+impl FileHeader {
+    const LEN: usize = std::mem::size_of::<Header>()
+        + std::mem::size_of::<HeaderExt>()
+        + std::mem::size_of::<TableLocator>();
+
+    fn read(bytes: &[u8]) -> Self {
+        let ext_start = std::mem::size_of::<Header>();
+        let locator_start = ext_start + std::mem::size_of::<HeaderExt>();
+        Self {
+            hdr: bytemuck::pod_read_unaligned(&bytes[..ext_start]),
+            ext: bytemuck::pod_read_unaligned(&bytes[ext_start..locator_start]),
+            locator: bytemuck::pod_read_unaligned(&bytes[locator_start..Self::LEN]),
+        }
+    }
+
+    fn to_bytes(self) -> [u8; Self::LEN] {
+        let mut out = [0u8; Self::LEN];
+        let ext_start = std::mem::size_of::<Header>();
+        let locator_start = ext_start + std::mem::size_of::<HeaderExt>();
+        out[..ext_start].copy_from_slice(bytemuck::bytes_of(&self.hdr));
+        out[ext_start..locator_start].copy_from_slice(bytemuck::bytes_of(&self.ext));
+        out[locator_start..].copy_from_slice(bytemuck::bytes_of(&self.locator));
+        out
+    }
+}
+
+/// Fixed-width entry record used by version 1 files.
+#[repr(C)]
+#[derive(Clone, Copy, Zeroable, Pod)]
+struct FixedEntryRecord {
+    type_tag: u32,  // e.g. u32::from_le_bytes(*b"GEOM")
+    checksum: u32,  // uninitialised padding
+    offset: u64,    // offset of the blob within the data section
+    len: u64,       // blob length
+    name: [u8; 64], // ASCII/UTF-8 (nul padded)
+}
+
+/// Entry record used by version 2 files; names live in the string table.
+#[repr(C)]
+#[derive(Clone, Copy, Zeroable, Pod)]
+struct EntryRecord {
+    type_tag: u32,    // e.g. u32::from_le_bytes(*b"GEOM")
+    checksum: u32,    // folded FNV-1a of the stored (possibly compressed) blob
+    offset: u64,      // offset of the blob within the data section
+    len: u64,         // stored blob length
+    raw_len: u64,     // serialized length before compression
+    name_offset: u32, // offset of the name within the string table
+    name_len: u32,    // name length in bytes (UTF-8, no terminator)
+    flags: u32,       // low byte: compression codec, bit 8: raw POD slice, bit 9: tombstone
+    reserved: u32,    // future use
+}
+
+/// Decoded entry table record, independent of the on-disk revision.
+#[derive(Clone, Debug)]
+struct Entry {
+    name: String,
+    type_tag: u32,
+    checksum: u32,
+    offset: u64,
+    len: u64,
+    raw_len: u64,
+    compression: RDBCompression,
+    pod: bool,
+    tombstone: bool,
+}
+
+impl Entry {
+    fn flags(&self) -> u32 {
+        let mut flags = self.compression.flags();
+        if self.pod {
+            flags |= ENTRY_FLAG_POD;
+        }
+        if self.tombstone {
+            flags |= ENTRY_FLAG_TOMBSTONE;
+        }
+        flags
+    }
+}
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct RDBEntryMeta {
     pub name: String,
     pub type_tag: u32,
     pub offset: u64,
+    /// Stored length in bytes, after compression.
     pub len: u64,
+    /// Serialized length in bytes, before compression.
+    pub raw_len: u64,
+    /// Codec the stored bytes are compressed with.
+    pub compression: RDBCompression,
+    /// Whether the entry holds a raw POD slice rather than bincode.
+    pub pod: bool,
+    /// Stored content hash, or zero when the file predates checksums.
+    pub checksum: u32,
 }
 
-impl From<Entry> for RDBEntryMeta {
-    fn from(value: Entry) -> Self {
-        let name = stored_name_bytes(&value.name);
+impl From<&Entry> for RDBEntryMeta {
+    fn from(value: &Entry) -> Self {
         Self {
-            name: String::from_utf8_lossy(name).into_owned(),
+            name: value.name.clone(),
             type_tag: value.type_tag,
             offset: value.offset,
             len: value.len,
+            raw_len: value.raw_len,
+            compression: value.compression,
+            pod: value.pod,
+            checksum: value.checksum,
         }
     }
 }
 
-#[inline]
-fn entry_size() -> usize {
-    std::mem::size_of::<Entry>()
+/// Why an entry failed [`RDBView::verify`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RDBEntryFault {
+    /// The entry points past the end of the data region (e.g. a truncated file).
+    OutOfBounds,
+    /// The stored checksum does not match the entry bytes.
+    ChecksumMismatch { expected: u32, found: u32 },
+}
+
+impl std::fmt::Display for RDBEntryFault {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RDBEntryFault::OutOfBounds => write!(f, "entry extends past the end of the file"),
+            RDBEntryFault::ChecksumMismatch { expected, found } => write!(
+                f,
+                "checksum mismatch (expected {expected:#010X}, found {found:#010X})"
+            ),
+        }
+    }
+}
+
+/// A single corrupt entry reported by [`RDBView::verify`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RDBCorruptEntry {
+    pub name: String,
+    pub fault: RDBEntryFault,
 }
 
-struct EntryIter<'a> {
-    bytes: &'a [u8],
-    idx: usize,
+/// Result of checking an RDB file against its stored hashes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RDBVerifyReport {
+    /// Container version read from the header.
+    pub version: u16,
+    /// Whether the whole-file digest matched; `None` for files without one.
+    pub digest_matches: Option<bool>,
+    /// Entries that are out of bounds or fail their checksum.
+    pub corrupt_entries: Vec<RDBCorruptEntry>,
 }
-impl<'a> EntryIter<'a> {
-    fn new(bytes: &'a [u8]) -> Self {
-        Self { bytes, idx: 0 }
+
+impl RDBVerifyReport {
+    /// Returns true when the file stores checksums that can be verified.
+    pub fn has_checksums(&self) -> bool {
+        self.version >= 2
+    }
+
+    /// Returns true when no corruption was detected.
+    pub fn is_ok(&self) -> bool {
+        self.digest_matches != Some(false) && self.corrupt_entries.is_empty()
     }
 }
-impl<'a> Iterator for EntryIter<'a> {
-    type Item = Entry;
-    fn next(&mut self) -> Option<Self::Item> {
-        let sz = entry_size();
-        if self.idx + sz > self.bytes.len() {
-            return None;
+
+/// Section offsets resolved from a file header.
+struct Layout {
+    version: u16,
+    entries_start: usize,
+    names_start: usize,
+    names_end: usize,
+    data_start: usize,
+    /// Start of the bytes covered by `digest`.
+    digest_start: usize,
+    digest: Option<u64>,
+    /// Full header of version 2 files.
+    header: Option<FileHeader>,
+}
+
+fn read_layout(bytes: &[u8]) -> Result<Layout, RdbErr> {
+    let header_sz = std::mem::size_of::<Header>();
+    if bytes.len() < header_sz {
+        return Err(RdbErr::TooSmall);
+    }
+    let hdr: Header = bytemuck::pod_read_unaligned(&bytes[..header_sz]);
+    if hdr.magic != MAGIC || hdr.version < MIN_VERSION || hdr.version > VERSION {
+        return Err(RdbErr::BadHeader);
+    }
+
+    // Version 1 files have no extension, locator or string table.
+    let (entries_start, names_len, data_offset, digest_start, digest, header) = if hdr.version >= 2
+    {
+        if bytes.len() < FileHeader::LEN {
+            return Err(RdbErr::TooSmall);
         }
-        let e = bytemuck::pod_read_unaligned::<Entry>(&self.bytes[self.idx..self.idx + sz]);
-        self.idx += sz;
-        Some(e)
+        let header = FileHeader::read(bytes);
+        let table_offset =
+            usize::try_from(header.locator.table_offset).map_err(|_| RdbErr::BadHeader)?;
+        let data_offset =
+            usize::try_from(header.locator.data_offset).map_err(|_| RdbErr::BadHeader)?;
+        (
+            table_offset,
+            header.ext.names_len as usize,
+            Some(data_offset),
+            FileHeader::LEN,
+            Some(header.ext.digest),
+            Some(header),
+        )
+    } else {
+        (header_sz, 0, None, header_sz, None, None)
+    };
+
+    let entries_sz = (hdr.entry_count as usize)
+        .checked_mul(record_size(hdr.version))
+        .ok_or(RdbErr::BadHeader)?;
+    let names_start = entries_start
+        .checked_add(entries_sz)
+        .ok_or(RdbErr::BadHeader)?;
+    let names_end = names_start
+        .checked_add(names_len)
+        .ok_or(RdbErr::BadHeader)?;
+    let data_start = data_offset.unwrap_or(names_end);
+    if bytes.len() < names_end || bytes.len() < data_start {
+        return Err(RdbErr::TooSmall);
     }
+
+    Ok(Layout {
+        version: hdr.version,
+        entries_start,
+        names_start,
+        names_end,
+        data_start,
+        digest_start,
+        digest,
+        header,
+    })
 }
 
-fn name64(s: &str) -> Result<[u8; 64], RdbErr> {
-    let mut out = [0u8; 64];
-    let bytes = s.as_bytes();
-    if bytes.len() > out.len() - 1 {
-        return Err(RdbErr::NameTooLong);
+/// Decodes the entry table (and string table, for version 2) of a mapped file.
+fn read_entries(bytes: &[u8], layout: &Layout) -> Result<Vec<Entry>, RdbErr> {
+    let table = &bytes[layout.entries_start..layout.names_start];
+    let record_sz = record_size(layout.version);
+
+    if layout.version < 2 {
+        let entries = table
+            .chunks_exact(record_sz)
+            .map(|raw| {
+                let record: FixedEntryRecord = bytemuck::pod_read_unaligned(raw);
+                Entry {
+                    name: String::from_utf8_lossy(stored_name_bytes(&record.name)).into_owned(),
+                    type_tag: record.type_tag,
+                    // Version 1 wrote uninitialised padding here.
+                    checksum: 0,
+                    offset: record.offset,
+                    len: record.len,
+                    raw_len: record.len,
+                    compression: RDBCompression::None,
+                    pod: false,
+                    tombstone: false,
+                }
+            })
+            .collect();
+        return Ok(entries);
+    }
+
+    let names = &bytes[layout.names_start..layout.names_end];
+    table
+        .chunks_exact(record_sz)
+        .map(|raw| {
+            let record: EntryRecord = bytemuck::pod_read_unaligned(raw);
+            let start = record.name_offset as usize;
+            let end = start
+                .checked_add(record.name_len as usize)
+                .ok_or(RdbErr::BadHeader)?;
+            let name = names.get(start..end).ok_or(RdbErr::BadHeader)?;
+            Ok(Entry {
+                name: String::from_utf8_lossy(name).into_owned(),
+                type_tag: record.type_tag,
+                checksum: record.checksum,
+                offset: record.offset,
+                len: record.len,
+                raw_len: record.raw_len,
+                compression: RDBCompression::from_flags(record.flags).ok_or(RdbErr::BadHeader)?,
+                pod: record.flags & ENTRY_FLAG_POD != 0,
+                tombstone: record.flags & ENTRY_FLAG_TOMBSTONE != 0,
+            })
+        })
+        .collect()
+}
+
+/// Returns the blob for `entry` within `data`, or `None` if it is out of bounds.
+fn blob<'a>(data: &'a [u8], entry: &Entry) -> Option<&'a [u8]> {
+    let start = usize::try_from(entry.offset).ok()?;
+    let end = start.checked_add(usize::try_from(entry.len).ok()?)?;
+    data.get(start..end)
+}
+
+/// Size of one entry table record for the given container version.
+#[inline]
+fn record_size(version: u16) -> usize {
+    if version >= 2 {
+        std::mem::size_of::<EntryRecord>()
+    } else {
+        std::mem::size_of::<FixedEntryRecord>()
     }
+}
 
-    let n = bytes.len();
-    if n > 0 {
-        out[..n].copy_from_slice(bytes);
+fn check_name(name: &str) -> Result<(), RdbErr> {
+    if name.len() > MAX_NAME_LEN {
+        return Err(RdbErr::NameTooLong);
     }
-    out[n] = b'\0';
-    Ok(out)
+    Ok(())
 }
 
 fn stored_name_bytes(name: &[u8; 64]) -> &[u8] {
@@ -188,10 +687,107 @@ fn stored_name_bytes(name: &[u8; 64]) -> &[u8] {
     &name[..end]
 }
 
+/// Serializes and compresses `obj`, returning its entry record and stored bytes.
+fn encode_entry<T: Serialize + RDBType>(
+    name: &str,
+    obj: &T,
+    compression: RDBCompression,
+) -> Result<(Entry, Vec<u8>), RdbErr> {
+    let raw = to_bytes(obj);
+    let raw_len = raw.len() as u64;
+    let (compression, bytes) = compress(raw, compression)?;
+
+    let entry = Entry {
+        name: name.to_string(),
+        type_tag: type_tag_for::<T>(),
+        checksum: entry_checksum(&bytes),
+        offset: 0,
+        len: bytes.len() as u64,
+        raw_len,
+        compression,
+        pod: false,
+        tombstone: false,
+    };
+    Ok((entry, bytes))
+}
+
+/// Builds the entry table for `entries` and the string table it points into.
+fn encode_table<'a>(
+    entries: impl IntoIterator<Item = &'a Entry>,
+) -> Result<(Vec<EntryRecord>, Vec<u8>), RdbErr> {
+    let mut names = Vec::new();
+    let mut records = Vec::new();
+    for entry in entries {
+        records.push(EntryRecord {
+            type_tag: entry.type_tag,
+            checksum: entry.checksum,
+            offset: entry.offset,
+            len: entry.len,
+            raw_len: entry.raw_len,
+            name_offset: u32::try_from(names.len()).map_err(|_| RdbErr::NameTooLong)?,
+            name_len: entry.name.len() as u32,
+            flags: entry.flags(),
+            reserved: 0,
+        });
+        names.extend_from_slice(entry.name.as_bytes());
+    }
+    Ok((records, names))
+}
+
+/// On-disk state mirrored by an [`RDBFile`], used to check that appends are safe.
+#[derive(Clone, Copy)]
+struct Synced {
+    header: FileHeader,
+    file_len: u64,
+}
+
+impl Synced {
+    /// Returns true when `path` still holds exactly the file this state describes.
+    fn matches(&self, path: &Path) -> bool {
+        let Ok(mut file) = File::open(path) else {
+            return false;
+        };
+        let mut header = [0u8; FileHeader::LEN];
+        file.metadata()
+            .is_ok_and(|meta| meta.len() == self.file_len)
+            && file.read_exact(&mut header).is_ok()
+            && header == self.header.to_bytes()
+    }
+}
+
+/// Sibling path a file is written to before it replaces `path`.
+fn temp_path_for(path: &Path) -> PathBuf {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    temp_path.into()
+}
+
+/// Moves `from` over `to`, removing `to` first when the platform refuses to
+/// rename onto an existing file.
+fn replace_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match std::fs::rename(from, to) {
+        Err(_) if to.exists() => {
+            std::fs::remove_file(to)?;
+            std::fs::rename(from, to)
+        }
+        result => result,
+    }
+}
+
 pub struct RDBFile {
     entries: Vec<Entry>,
+    /// Replaced entries whose blobs are still held in the data section.
+    tombstones: Vec<Entry>,
+    /// Data section of the loaded file, read from `mmap` without copying.
+    base: Range<usize>,
+    /// Bytes added to the data section after `base`.
     data: Vec<u8>,
+    /// Bytes of the backing file no longer referenced by live entries.
+    dead_len: u64,
+    /// File this RDB mirrors byte for byte from its data section onwards.
+    synced: Option<Synced>,
     mmap: Option<Mmap>,
+    compression: RDBCompression,
 }
 
 impl RDBFile {
@@ -199,321 +795,652 @@ impl RDBFile {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            tombstones: Vec::new(),
+            base: 0..0,
             data: Vec::new(),
+            dead_len: 0,
+            synced: None,
             mmap: None,
+            compression: RDBCompression::None,
         }
     }
 
+    /// Sets the codec used by [`RDBFile::add`] and [`RDBFile::upsert`].
+    pub fn set_compression(&mut self, compression: RDBCompression) {
+        self.compression = compression;
+    }
+
+    /// Returns the codec used by [`RDBFile::add`] and [`RDBFile::upsert`].
+    pub fn compression(&self) -> RDBCompression {
+        self.compression
+    }
+
     /// Adds a serializable object to the in-memory RDB under the provided name.
-    pub fn add<T: Serialize>(&mut self, name: &str, obj: &T) -> Result<(), RdbErr> {
-        let nameb = name64(name)?;
+    pub fn add<T: Serialize + RDBType>(&mut self, name: &str, obj: &T) -> Result<(), RdbErr> {
+        self.add_compressed(name, obj, self.compression)
+    }
 
-        let bytes = to_bytes(obj);
-        self.entries.push(Entry {
-            type_tag: portable_type_hash::<T>() as u32,
-            offset: self.data.len() as u64,
+    /// Adds a serializable object, compressing its bytes with `compression`.
+    ///
+    /// Entries that do not shrink under the codec are stored uncompressed.
+    pub fn add_compressed<T: Serialize + RDBType>(
+        &mut self,
+        name: &str,
+        obj: &T,
+        compression: RDBCompression,
+    ) -> Result<(), RdbErr> {
+        check_name(name)?;
+
+        let (entry, bytes) = encode_entry(name, obj, compression)?;
+        self.push_blob(entry, &bytes);
+
+        Ok(())
+    }
+
+    /// Adds a slice of plain-old-data values stored as raw, aligned bytes.
+    ///
+    /// These entries are never compressed and can be borrowed without copying
+    /// through [`RDBView::fetch_pod_slice`].
+    pub fn add_pod_slice<T: Pod + RDBType>(
+        &mut self,
+        name: &str,
+        values: &[T],
+    ) -> Result<(), RdbErr> {
+        check_name(name)?;
+
+        let bytes: &[u8] = bytemuck::cast_slice(values);
+        let entry = Entry {
+            name: name.to_string(),
+            type_tag: type_tag_for::<T>(),
+            checksum: entry_checksum(bytes),
+            offset: 0,
             len: bytes.len() as u64,
-            name: nameb,
-        });
+            raw_len: bytes.len() as u64,
+            compression: RDBCompression::None,
+            pod: true,
+            tombstone: false,
+        };
+        self.push_blob(entry, bytes);
+
+        Ok(())
+    }
 
-        self.data.extend_from_slice(&bytes);
+    /// Copies an entry from `view` as stored, without decoding or recompressing it.
+    pub fn copy_entry(&mut self, view: &RDBView, name: &str) -> Result<(), RdbErr> {
+        let entry = view.entry(name).ok_or(RdbErr::BadHeader)?;
+        let stored = blob(&view.bytes()[view.data_start..], entry).ok_or(RdbErr::BadHeader)?;
+        let entry = Entry {
+            checksum: entry_checksum(stored),
+            ..entry.clone()
+        };
+        self.push_blob(entry, stored);
 
         Ok(())
     }
 
-    /// Adds or replaces a serializable object to the in-memory RDB under the provided name.
-    pub fn upsert<T: Serialize>(&mut self, name: &str, obj: &T) -> Result<(), RdbErr> {
-        let nameb = name64(name)?;
-        let new_bytes = to_bytes(obj);
+    fn push_blob(&mut self, entry: Entry, bytes: &[u8]) {
+        let offset = self.push_data(bytes);
+        self.entries.push(Entry { offset, ..entry });
+    }
 
-        let mut new_entries = Vec::with_capacity(self.entries.len() + 1);
-        let mut new_data = Vec::with_capacity(self.data.len() + new_bytes.len());
-        let mut replaced = false;
+    /// Appends `bytes` to the data section and returns their offset.
+    fn push_data(&mut self, bytes: &[u8]) -> u64 {
+        let offset = self.data_len().next_multiple_of(ENTRY_ALIGN);
+        self.data.resize(offset - self.base.len(), 0);
+        self.data.extend_from_slice(bytes);
+        offset as u64
+    }
 
-        for entry in &self.entries {
-            if stored_name_bytes(&entry.name) == name.as_bytes() {
-                let offset = new_data.len() as u64;
-                new_data.extend_from_slice(&new_bytes);
-                new_entries.push(Entry {
-                    type_tag: portable_type_hash::<T>() as u32,
-                    offset,
-                    len: new_bytes.len() as u64,
-                    name: nameb,
-                });
-                replaced = true;
-            } else {
-                let data_start = entry.offset as usize;
-                let data_end = data_start + entry.len as usize;
-                let offset = new_data.len() as u64;
-                new_data.extend_from_slice(&self.data[data_start..data_end]);
-                new_entries.push(Entry {
-                    type_tag: entry.type_tag,
-                    offset,
-                    len: entry.len,
-                    name: entry.name,
-                });
-            }
-        }
+    /// Length of the data section, including the part still in the mapped file.
+    fn data_len(&self) -> usize {
+        self.base.len() + self.data.len()
+    }
 
-        if !replaced {
-            let offset = new_data.len() as u64;
-            new_data.extend_from_slice(&new_bytes);
-            new_entries.push(Entry {
-                type_tag: portable_type_hash::<T>() as u32,
-                offset,
-                len: new_bytes.len() as u64,
-                name: nameb,
-            });
+    /// Returns the stored bytes of `entry` from the mapped file or the added data.
+    fn stored(&self, entry: &Entry) -> Option<&[u8]> {
+        let mapped = self
+            .mmap
+            .as_deref()
+            .map_or(&[][..], |map| &map[self.base.clone()]);
+        let start = usize::try_from(entry.offset).ok()?;
+        let end = start.checked_add(usize::try_from(entry.len).ok()?)?;
+        if end <= mapped.len() {
+            mapped.get(start..end)
+        } else {
+            let start = start.checked_sub(mapped.len())?;
+            self.data.get(start..end - mapped.len())
         }
+    }
 
-        self.entries = new_entries;
-        self.data = new_data;
-        self.mmap = None;
+    /// Adds or replaces a serializable object to the in-memory RDB under the provided name.
+    pub fn upsert<T: Serialize + RDBType>(&mut self, name: &str, obj: &T) -> Result<(), RdbErr> {
+        self.upsert_compressed(name, obj, self.compression)
+    }
+
+    /// Adds or replaces a serializable object, compressing its bytes with `compression`.
+    ///
+    /// The new bytes are appended to the data section and a replaced entry
+    /// becomes a tombstone, so existing data is never moved.
+    pub fn upsert_compressed<T: Serialize + RDBType>(
+        &mut self,
+        name: &str,
+        obj: &T,
+        compression: RDBCompression,
+    ) -> Result<(), RdbErr> {
+        check_name(name)?;
+        let (new_entry, new_bytes) = encode_entry(name, obj, compression)?;
+
+        let Some(idx) = self.entries.iter().position(|entry| entry.name == name) else {
+            self.push_blob(new_entry, &new_bytes);
+            return Ok(());
+        };
+
+        let offset = self.push_data(&new_bytes);
+        let old = std::mem::replace(
+            &mut self.entries[idx],
+            Entry {
+                offset,
+                ..new_entry
+            },
+        );
+        self.dead_len += old.len;
+        self.tombstones.push(Entry {
+            tombstone: true,
+            ..old
+        });
 
         Ok(())
     }
 
     /// Retrieves a deserialized object that was previously added by name.
-    pub fn fetch<T: DeserializeOwned>(&mut self, name: &str) -> Result<T, RdbErr> {
-        let name_bytes = name.as_bytes();
-        if let Some(entry) = self
-            .entries
-            .iter()
-            .find(|entry| stored_name_bytes(&entry.name) == name_bytes)
-        {
-            // Types match?
-            if entry.type_tag == portable_type_hash::<T>() as u32 {
-                let data_start = entry.offset as usize;
-                let data_end = data_start + entry.len as usize;
-
-                let obj_bytes = &self.data[data_start..data_end];
-                return Ok(from_bytes::<T>(obj_bytes));
-            }
+    pub fn fetch<T: DeserializeOwned + RDBType>(&mut self, name: &str) -> Result<T, RdbErr> {
+        if let Some(entry) = self.entries.iter().find(|entry| entry.name == name) {
+            let stored = self.stored(entry).ok_or(RdbErr::BadHeader)?;
+            return decode_entry(entry, stored);
         }
 
         return Err(RdbErr::BadHeader);
     }
 
-    /// Returns the raw byte contents for a named entry.
-    pub fn entry_bytes(&self, name: &str) -> Result<&[u8], RdbErr> {
-        let name_bytes = name.as_bytes();
-        if let Some(entry) = self
-            .entries
-            .iter()
-            .find(|entry| stored_name_bytes(&entry.name) == name_bytes)
-        {
-            let data_start = entry.offset as usize;
-            let data_end = data_start
-                .checked_add(entry.len as usize)
-                .ok_or(RdbErr::BadHeader)?;
-            if data_end > self.data.len() {
-                return Err(RdbErr::BadHeader);
-            }
-            return Ok(&self.data[data_start..data_end]);
+    /// Returns the serialized byte contents for a named entry, decompressed if needed.
+    pub fn entry_bytes(&self, name: &str) -> Result<Cow<'_, [u8]>, RdbErr> {
+        if let Some(entry) = self.entries.iter().find(|entry| entry.name == name) {
+            let stored = self.stored(entry).ok_or(RdbErr::BadHeader)?;
+            return decompress(entry, stored);
         }
 
         Err(RdbErr::BadHeader)
     }
 
-    /// Save using MmapMut for zero-copy struct writes.
-    /// (This writes header + entries only; blobs should be appended separately
-    /// and their offsets/lengths filled beforehand.)
+    /// Writes a compacted copy of the file to disk so it can later be consumed via [`RDBView`].
     ///
-    /// This writes the file to disk so it can later be consumed via [`RDBView`].
+    /// Only live entries are written; tombstones and their blobs are dropped.
+    /// The copy is written next to `path` and moved over it, so saving over
+    /// the file this RDB was loaded from keeps its mapped entries readable.
     pub fn save(&self, path: impl AsRef<Path>) -> Result<(), RdbErr> {
-        // Write header
-        let hdr = Header {
-            magic: MAGIC,
-            version: VERSION,
-            reserved: 0,
-            entry_count: self.entries.len() as u32,
-        };
+        let path = path.as_ref();
+        let temp_path = temp_path_for(path);
+        self.write_compacted(&temp_path)?;
+        replace_file(&temp_path, path)?;
+
+        Ok(())
+    }
+
+    /// Writes the live entries to `path`, truncating whatever it held.
+    fn write_compacted(&self, path: &Path) -> Result<(), RdbErr> {
+        // Repack the live blobs back to back
+        let mut entries = Vec::with_capacity(self.entries.len());
+        let mut data_len = 0usize;
+        for entry in &self.entries {
+            data_len = data_len.next_multiple_of(ENTRY_ALIGN);
+            entries.push(Entry {
+                offset: data_len as u64,
+                ..entry.clone()
+            });
+            data_len += entry.len as usize;
+        }
 
-        // Compute file size: header + entries
-        let header_sz = std::mem::size_of::<Header>() as u64;
-        let entries_sz = (self.entries.len() * std::mem::size_of::<Entry>()) as u64;
-        let data_sz = self.data.len();
-        let hdr_bytes = bytemuck::bytes_of(&hdr);
-
-        let header_start = 0 as usize;
-        let header_end = hdr_bytes.len();
-        let entries_start = header_sz as usize;
-        let entries_end = header_sz as usize + entries_sz as usize;
-        let data_start = entries_end;
-        let data_end = entries_end + self.data.len();
-        let total = header_sz + entries_sz + data_sz as u64;
-
-        let mut f = OpenOptions::new()
+        let (records, mut names) = encode_table(&entries)?;
+        let ent_bytes = bytemuck::cast_slice::<EntryRecord, u8>(&records);
+
+        // Pad the string table so the data section starts on an ENTRY_ALIGN boundary.
+        let entries_start = FileHeader::LEN;
+        let names_start = entries_start + ent_bytes.len();
+        names.resize(
+            (names_start + names.len()).next_multiple_of(ENTRY_ALIGN) - names_start,
+            0,
+        );
+        let names_len = u32::try_from(names.len()).map_err(|_| RdbErr::NameTooLong)?;
+        let data_start = names_start + names.len();
+        let total = data_start + data_len;
+
+        let f = OpenOptions::new()
             .read(true)
             .write(true)
             .create(true)
-            .open(path)
-            .unwrap();
-
-        f.set_len(total).unwrap(); // extend file to final size
-        f.seek(SeekFrom::Start(0))?;
+            .truncate(true)
+            .open(path)?;
+        f.set_len(total as u64)?; // extend file to final size
 
         // Map for writing
-        let mut map = unsafe { MmapMut::map_mut(&f).unwrap() };
-
-        // Write entries
-        let ent_bytes = bytemuck::cast_slice::<Entry, u8>(&self.entries);
+        let mut map = unsafe { MmapMut::map_mut(&f)? };
+
+        map[entries_start..names_start].copy_from_slice(ent_bytes);
+        map[names_start..data_start].copy_from_slice(&names);
+        for (packed, entry) in entries.iter().zip(&self.entries) {
+            let bytes = self.stored(entry).ok_or(RdbErr::BadHeader)?;
+            let start = data_start + packed.offset as usize;
+            map[start..start + bytes.len()].copy_from_slice(bytes);
+        }
 
-        map[header_start..header_end].copy_from_slice(hdr_bytes);
-        map[entries_start..entries_end].copy_from_slice(ent_bytes);
-        map[data_start..data_end].copy_from_slice(&self.data);
+        let header = FileHeader {
+            hdr: Header {
+                magic: MAGIC,
+                version: VERSION,
+                reserved: 0,
+                entry_count: records.len() as u32,
+            },
+            ext: HeaderExt {
+                flags: 0,
+                names_len,
+                digest: fnv1a64_extend(FNV_OFFSET_BASIS, &map[FileHeader::LEN..]),
+            },
+            locator: TableLocator {
+                table_offset: entries_start as u64,
+                data_offset: data_start as u64,
+                dead_len: 0,
+            },
+        };
+        map[..FileHeader::LEN].copy_from_slice(&header.to_bytes());
 
         // Flush to disk
-        map.flush().unwrap();
+        map.flush()?;
+
+        Ok(())
+    }
+
+    /// Writes the changes made since this file was loaded or last appended to the end of `path`.
+    ///
+    /// New blobs and a fresh entry table go after the existing bytes and only the
+    /// header is rewritten in place; replaced entries stay behind as tombstones
+    /// until [`RDBFile::compact`] reclaims them. When `path` no longer holds the
+    /// file this RDB was loaded from, the whole file is rewritten instead.
+    pub fn append(&mut self, path: impl AsRef<Path>) -> Result<(), RdbErr> {
+        let path = path.as_ref();
+        let Some(synced) = self.synced.take().filter(|synced| synced.matches(path)) else {
+            return self.compact(path);
+        };
+        let old = synced.header;
+
+        // The data section mirrors the file from its start on, so new blobs are
+        // already at their final offsets.
+        let tail_start = (synced.file_len - old.locator.data_offset) as usize - self.base.len();
+        let table_offset = old.locator.data_offset + self.data_len() as u64;
+        let (records, names) = encode_table(self.entries.iter().chain(&self.tombstones))?;
+        let names_len = u32::try_from(names.len()).map_err(|_| RdbErr::NameTooLong)?;
+        self.data
+            .extend_from_slice(bytemuck::cast_slice::<EntryRecord, u8>(&records));
+        self.data.extend_from_slice(&names);
+        let tail = &self.data[tail_start..];
+
+        let mut f = OpenOptions::new().write(true).open(path)?;
+        f.seek(SeekFrom::Start(synced.file_len))?;
+        f.write_all(tail)?;
+        f.sync_data()?;
+
+        // The previous table is now dead space too.
+        let old_table_len =
+            old.hdr.entry_count as u64 * record_size(VERSION) as u64 + old.ext.names_len as u64;
+        self.dead_len += old_table_len;
+        let header = FileHeader {
+            hdr: Header {
+                entry_count: records.len() as u32,
+                ..old.hdr
+            },
+            ext: HeaderExt {
+                names_len,
+                digest: fnv1a64_extend(old.ext.digest, tail),
+                ..old.ext
+            },
+            locator: TableLocator {
+                table_offset,
+                dead_len: self.dead_len,
+                ..old.locator
+            },
+        };
+        f.seek(SeekFrom::Start(0))?;
+        f.write_all(&header.to_bytes())?;
+        f.sync_data()?;
+
+        self.synced = Some(Synced {
+            header,
+            file_len: synced.file_len + tail.len() as u64,
+        });
+
+        Ok(())
+    }
+
+    /// Replaces `path` with a compacted copy of this file and keeps appending to it.
+    ///
+    /// The copy is written next to `path` and moved over it, so a failed
+    /// write leaves the previous file intact. If the move fails, this file
+    /// keeps reading from the copy.
+    pub fn compact(&mut self, path: impl AsRef<Path>) -> Result<(), RdbErr> {
+        let path = path.as_ref();
+        let temp_path = temp_path_for(path);
+        self.write_compacted(&temp_path)?;
+
+        // Windows cannot replace a file that is still mapped, so drop ours first.
+        let compression = self.compression;
+        *self = Self::new();
+        let replaced = replace_file(&temp_path, path);
+        *self = Self::load(if replaced.is_ok() { path } else { &temp_path })?;
+        self.compression = compression;
+        replaced?;
 
         Ok(())
     }
 
-    /// Load by mmap, then cast header/entries directly from the mapped bytes.
+    /// Load by mmap, then decode the header/entry table from the mapped bytes.
+    ///
+    /// Entry data stays in the mapped file until it is fetched, so the file
+    /// must not be rewritten by anyone else while it is loaded. Version 1
+    /// files are accepted; their checksums are computed
+    /// on load so a subsequent [`RDBFile::save`] produces a fully checksummed file.
     pub fn load(path: impl AsRef<Path>) -> Result<Self, RdbErr> {
         let f = File::open(path)?;
         let map = unsafe { Mmap::map(&f)? };
 
-        // Sanity checks
-        let header_sz = std::mem::size_of::<Header>();
-        if map.len() < header_sz {
-            return Err(RdbErr::TooSmall);
-        }
-        let hdr: &Header = bytemuck::from_bytes(&map[..header_sz]);
-        if hdr.magic != MAGIC || hdr.version != VERSION {
-            return Err(RdbErr::BadHeader);
-        }
+        let layout = read_layout(&map)?;
+        let (tombstones, mut entries): (Vec<_>, Vec<_>) = read_entries(&map, &layout)?
+            .into_iter()
+            .partition(|entry| entry.tombstone);
 
-        let entries_sz = (hdr.entry_count as usize) * std::mem::size_of::<Entry>();
-        let need = header_sz + entries_sz;
-        if map.len() < need {
-            return Err(RdbErr::TooSmall);
+        let data = &map[layout.data_start..map.len()];
+        if layout.version < 2 {
+            for entry in &mut entries {
+                entry.checksum = blob(data, entry).map(entry_checksum).unwrap_or(0);
+            }
         }
 
-        // Allocate aligned Vec<Entry> and memcpy the bytes into it
-        let mut entries = vec![Entry::zeroed(); hdr.entry_count as usize];
-        let dst_bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut entries);
-        dst_bytes.copy_from_slice(&map[header_sz..need]);
-
-        let data = &map[need..map.len()];
+        // Only current-version files can be appended to in place.
+        let synced = layout
+            .header
+            .filter(|_| layout.version == VERSION)
+            .map(|header| Synced {
+                header,
+                file_len: map.len() as u64,
+            });
 
         Ok(Self {
-            entries: entries.to_vec(),
-            data: data.to_vec(),
+            entries,
+            tombstones,
+            base: layout.data_start..map.len(),
+            data: Vec::new(),
+            dead_len: layout.header.map_or(0, |header| header.locator.dead_len),
+            synced,
             mmap: Some(map),
+            compression: RDBCompression::None,
         })
     }
 
     /// Releases the memory map used by this file, if any.
+    ///
+    /// Entry data still held in the mapped file is copied into memory first.
     pub fn unmap(&mut self) {
-        self.mmap = None;
+        if let Some(map) = self.mmap.take() {
+            let mut data = map[self.base.clone()].to_vec();
+            data.append(&mut self.data);
+            self.data = data;
+            self.base = 0..0;
+        }
     }
 
     /// Returns metadata for all entries contained in the file.
     pub fn entries(&self) -> Vec<RDBEntryMeta> {
-        self.entries
-            .iter()
-            .copied()
-            .map(RDBEntryMeta::from)
-            .collect()
+        self.entries.iter().map(RDBEntryMeta::from).collect()
     }
 }
 
 pub struct RDBView {
-    mmap: Mmap,
-    entries_start: usize,
+    mmap: Arc<Mmap>,
+    /// Bytes of `mmap` holding this file; a sub-range when it sits inside a bundle.
+    region: Range<usize>,
+    version: u16,
+    digest: Option<u64>,
+    digest_start: usize,
     data_start: usize,
-    entry_index: HashMap<String, Entry>,
+    dead_len: u64,
+    /// Live entries in file order.
+    entries: Vec<Entry>,
+    /// Sorted name index into `entries` for O(log n) lookups and prefix scans.
+    entry_index: BTreeMap<String, usize>,
 }
 
 impl RDBView {
     /// Fetches a deserialized value from the mapped file by entry name.
-    pub fn fetch<T: DeserializeOwned>(&mut self, name: &str) -> Result<T, RdbErr> {
-        let entry = self.entry_index.get(name).ok_or(RdbErr::BadHeader)?;
-        if entry.type_tag != portable_type_hash::<T>() as u32 {
-            return Err(RdbErr::BadHeader);
-        }
+    pub fn fetch<T: DeserializeOwned + RDBType>(&self, name: &str) -> Result<T, RdbErr> {
+        let entry = self.entry(name).ok_or(RdbErr::BadHeader)?;
+        let data = &self.bytes()[self.data_start..];
+        let stored = blob(data, entry).ok_or(RdbErr::BadHeader)?;
 
-        let data = &self.mmap[self.data_start..self.mmap.len()];
-        let data_start = entry.offset as usize;
-        let data_end = data_start
-            .checked_add(entry.len as usize)
-            .ok_or(RdbErr::BadHeader)?;
-        if data_end > data.len() {
-            return Err(RdbErr::BadHeader);
-        }
+        decode_entry(entry, stored)
+    }
 
-        Ok(from_bytes::<T>(&data[data_start..data_end]))
+    /// Borrows a slice written by [`RDBFile::add_pod_slice`] straight from the mapped file.
+    pub fn fetch_pod_slice<T: Pod + RDBType>(&self, name: &str) -> Result<&[T], RdbErr> {
+        let entry = self.entry(name).ok_or(RdbErr::BadHeader)?;
+        check_type_tag::<T>(&entry.name, entry.type_tag)?;
+
+        let data = &self.bytes()[self.data_start..];
+        let bytes = blob(data, entry).ok_or(RdbErr::BadHeader)?;
+        let reason = if !entry.pod {
+            "entry does not hold a raw POD slice".to_string()
+        } else {
+            match bytemuck::try_cast_slice(bytes) {
+                Ok(values) => return Ok(values),
+                Err(err) => format!("cannot borrow as [{}]: {err:?}", type_name::<T>()),
+            }
+        };
+
+        Err(RdbErr::Decode {
+            entry: name.to_string(),
+            type_tag: entry.type_tag,
+            reason,
+        })
     }
 
-    /// Load by mmap, then cast header/entries directly from the mapped bytes.
+    /// Load by mmap, then decode the header/entry table from the mapped bytes.
     ///
     /// Use this for fast read-only access to an existing RDB file without copying data.
     pub fn load(path: impl AsRef<Path>) -> Result<Self, RdbErr> {
         let f = File::open(path)?;
         let map = unsafe { Mmap::map(&f)? };
+        let len = map.len();
 
-        // Sanity checks
-        let header_sz = std::mem::size_of::<Header>();
-        if map.len() < header_sz {
-            return Err(RdbErr::TooSmall);
-        }
-        let hdr: &Header = bytemuck::from_bytes(&map[..header_sz]);
-        if hdr.magic != MAGIC || hdr.version != VERSION {
-            return Err(RdbErr::BadHeader);
-        }
+        Self::from_shared(Arc::new(map), 0..len)
+    }
 
-        let entries_sz = (hdr.entry_count as usize) * std::mem::size_of::<Entry>();
-        let need = header_sz + entries_sz;
-        if map.len() < need {
-            return Err(RdbErr::TooSmall);
-        }
+    /// Reads the RDB file stored in `region` of a shared mapping.
+    pub(crate) fn from_shared(mmap: Arc<Mmap>, region: Range<usize>) -> Result<Self, RdbErr> {
+        let bytes = mmap.get(region.clone()).ok_or(RdbErr::TooSmall)?;
+        let layout = read_layout(bytes)?;
+        let mut entries = read_entries(bytes, &layout)?;
+        entries.retain(|entry| !entry.tombstone);
 
-        let entries = &map[header_sz..need];
-        let mut entry_index = HashMap::with_capacity(hdr.entry_count as usize);
-        for entry in EntryIter::new(entries) {
-            let name = String::from_utf8_lossy(stored_name_bytes(&entry.name)).into_owned();
+        let mut entry_index = BTreeMap::new();
+        for (idx, entry) in entries.iter().enumerate() {
             // Keep first occurrence to preserve historical linear-scan behavior.
-            entry_index.entry(name).or_insert(entry);
+            entry_index.entry(entry.name.clone()).or_insert(idx);
         }
 
         Ok(Self {
-            mmap: map,
-            entries_start: header_sz,
-            data_start: need,
+            mmap,
+            region,
+            version: layout.version,
+            digest: layout.digest,
+            digest_start: layout.digest_start,
+            data_start: layout.data_start,
+            dead_len: layout.header.map_or(0, |header| header.locator.dead_len),
+            entries,
             entry_index,
         })
     }
 
+    fn bytes(&self) -> &[u8] {
+        &self.mmap[self.region.clone()]
+    }
+
+    fn entry(&self, name: &str) -> Option<&Entry> {
+        self.entry_index.get(name).map(|&idx| &self.entries[idx])
+    }
+
+    /// Returns the container version of the mapped file.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+
+    /// Returns the number of entries in the file's entry table.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true when the file contains no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the bytes held by tombstones and superseded entry tables.
+    ///
+    /// [`RDBFile::compact`] (or `dbgen compact`) reclaims this space.
+    pub fn dead_bytes(&self) -> u64 {
+        self.dead_len
+    }
+
+    /// Returns whether an entry with the exact given name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.entry_index.contains_key(name)
+    }
+
     /// Returns metadata for every entry stored in the mapped file.
     pub fn entries(&self) -> Vec<RDBEntryMeta> {
-        let bytes = &self.mmap[self.entries_start..self.data_start];
-        EntryIter::new(bytes).map(RDBEntryMeta::from).collect()
+        self.entries.iter().map(RDBEntryMeta::from).collect()
+    }
+
+    /// Iterates entries whose names start with `prefix`, in name order.
+    pub fn entries_with_prefix<'a>(
+        &'a self,
+        prefix: &'a str,
+    ) -> impl Iterator<Item = RDBEntryMeta> + 'a {
+        self.entry_index
+            .range::<str, _>((Bound::Included(prefix), Bound::Unbounded))
+            .take_while(move |(name, _)| name.starts_with(prefix))
+            .map(|(_, &idx)| RDBEntryMeta::from(&self.entries[idx]))
+    }
+
+    /// Detaches an entry's stored bytes so they can be decoded on another thread.
+    pub fn raw_entry(&self, name: &str) -> Option<RDBRawEntry> {
+        let entry = self.entry(name)?;
+        blob(&self.bytes()[self.data_start..], entry)?;
+        let start = self.region.start + self.data_start + entry.offset as usize;
+        Some(RDBRawEntry {
+            mmap: Arc::clone(&self.mmap),
+            range: start..start + entry.len as usize,
+            entry: entry.clone(),
+        })
+    }
+
+    /// Returns the serialized byte contents for a named entry, decompressed if needed.
+    pub fn entry_bytes(&self, name: &str) -> Result<Cow<'_, [u8]>, RdbErr> {
+        let data = &self.bytes()[self.data_start..];
+        let entry = self.entry(name).ok_or(RdbErr::BadHeader)?;
+        let stored = blob(data, entry).ok_or(RdbErr::BadHeader)?;
+
+        decompress(entry, stored)
     }
 
-    /// Returns the raw byte contents for a named entry.
-    pub fn entry_bytes(&self, name: &str) -> Result<&[u8], RdbErr> {
-        let data = &self.mmap[self.data_start..self.mmap.len()];
-        let entry = self.entry_index.get(name).ok_or(RdbErr::BadHeader)?;
+    /// Checks every entry against its stored checksum and the file against its digest.
+    ///
+    /// Version 1 files carry no hashes, so only entry bounds are checked for them.
+    pub fn verify(&self) -> RDBVerifyReport {
+        let data = &self.bytes()[self.data_start..];
+        let checksummed = self.version >= 2;
 
-        let start = entry.offset as usize;
-        let end = start
-            .checked_add(entry.len as usize)
-            .ok_or(RdbErr::BadHeader)?;
-        if end > data.len() {
-            return Err(RdbErr::BadHeader);
+        let corrupt_entries = self
+            .entries
+            .iter()
+            .filter_map(|entry| {
+                let fault = match blob(data, entry) {
+                    None => RDBEntryFault::OutOfBounds,
+                    Some(bytes) if checksummed => {
+                        let found = entry_checksum(bytes);
+                        if found == entry.checksum {
+                            return None;
+                        }
+                        RDBEntryFault::ChecksumMismatch {
+                            expected: entry.checksum,
+                            found,
+                        }
+                    }
+                    Some(_) => return None,
+                };
+                Some(RDBCorruptEntry {
+                    name: entry.name.clone(),
+                    fault,
+                })
+            })
+            .collect();
+
+        let digest_matches = self.digest.map(|digest| {
+            fnv1a64_extend(FNV_OFFSET_BASIS, &self.bytes()[self.digest_start..]) == digest
+        });
+
+        RDBVerifyReport {
+            version: self.version,
+            digest_matches,
+            corrupt_entries,
         }
+    }
+}
+/// One entry's stored bytes, sharing the mapping of the view it came from.
+pub struct RDBRawEntry {
+    mmap: Arc<Mmap>,
+    range: Range<usize>,
+    entry: Entry,
+}
+
+impl RDBRawEntry {
+    /// Returns the entry name.
+    pub fn name(&self) -> &str {
+        &self.entry.name
+    }
+
+    /// Returns the stored type tag.
+    pub fn type_tag(&self) -> u32 {
+        self.entry.type_tag
+    }
 
-        Ok(&data[start..end])
+    /// Returns whether the entry was written as a `T`.
+    pub fn holds<T: RDBType>(&self) -> bool {
+        tag_matches::<T>(self.entry.type_tag)
+    }
+
+    /// Decompresses and deserializes the entry, like [`RDBView::fetch`].
+    pub fn decode<T: DeserializeOwned + RDBType>(&self) -> Result<T, RdbErr> {
+        decode_entry(&self.entry, &self.mmap[self.range.clone()])
     }
 }
+
 // ---------------------------
 // Tiny example
 // ---------------------------
 
 #[cfg(test)]
 mod test {
-    use super::{RDBFile, RDBView};
+    use super::{
+        ENTRY_ALIGN, FileHeader, FixedEntryRecord, Header, MAGIC, MAX_NAME_LEN, RDBCompression,
+        RDBEntryFault, RDBFile, RDBType, RDBView, VERSION,
+    };
     use crate::error::RdbErr;
     use serde::{Deserialize, Serialize};
     #[test]
@@ -526,6 +1453,8 @@ mod test {
             name: Vec<String>,
         }
 
+        impl RDBType for TempObject {}
+
         let tmp = TempObject {
             data: vec![12; 32],
             name: vec!["lmao".to_string(); 32],
@@ -549,7 +1478,7 @@ mod test {
         rdb.save("target/read_write_multi.rdb")
             .expect("should be able to write multi entry file");
 
-        let mut rdb_view = RDBView::load("target/read_write_multi.rdb")
+        let rdb_view = RDBView::load("target/read_write_multi.rdb")
             .expect("Should be able to load multi entry file");
         let tmp_view = rdb_view
             .fetch::<TempObject>("obj/t.a.c.c")
@@ -590,6 +1519,8 @@ mod test {
             name: Vec<String>,
         }
 
+        impl RDBType for TempObject {}
+
         let tmp = TempObject {
             data: vec![12; 32],
             name: vec!["lmao".to_string(); 32],
@@ -622,7 +1553,7 @@ mod test {
         //////////////////// Test RDBView with the items we saved.
         //////////////////// Must be able to fetch everything correctly.
 
-        let mut rdb_view = RDBView::load("target/read_io_test.rdb")
+        let rdb_view = RDBView::load("target/read_io_test.rdb")
             .expect("Should be able to load file just saved");
 
         let tmp2 = rdb_view
@@ -650,12 +1581,16 @@ mod test {
             name: Vec<String>,
         }
 
+        impl RDBType for TempObject {}
+
         #[derive(Serialize, Deserialize)]
         struct TempObject2 {
             data: Vec<String>,
             name: Vec<u32>,
         }
 
+        impl RDBType for TempObject2 {}
+
         let tmp = TempObject {
             data: vec![12; 32],
             name: vec!["lmao".to_string(); 32],
@@ -668,9 +1603,12 @@ mod test {
 
         assert!(rdb.fetch::<TempObject>("obj/t.a.c.d").is_err());
         assert!(rdb.fetch::<TempObject>("t.a.c.d").is_err());
-        assert!(rdb.fetch::<TempObject2>("obj/t.a.c.b").is_err());
+        assert!(matches!(
+            rdb.fetch::<TempObject2>("obj/t.a.c.b"),
+            Err(RdbErr::TypeMismatch { entry, .. }) if entry == "obj/t.a.c.b"
+        ));
 
-        let long_name = "obj/".repeat(16);
+        let long_name = "x".repeat(MAX_NAME_LEN + 1);
         let err = rdb
             .add(&long_name, &tmp)
             .expect_err("Should reject overly long name");
@@ -680,6 +1618,29 @@ mod test {
             .expect("should be able to write file");
     }
 
+    #[test]
+    fn long_names_round_trip() {
+        let mut rdb = RDBFile::new();
+        let deep = format!("{}leaf", "obj/".repeat(16));
+        let unicode = "terrain/région/ünïcødé/".repeat(4);
+        rdb.add(&deep, &1u32).expect("add deep name");
+        rdb.add(&unicode, &2u32).expect("add unicode name");
+        rdb.add("short", &3u32).expect("add short name");
+        rdb.save("target/long_names.rdb")
+            .expect("should be able to write file");
+
+        let view = RDBView::load("target/long_names.rdb").expect("load view");
+        assert_eq!(view.version(), VERSION);
+        assert_eq!(view.fetch::<u32>(&deep).expect("fetch deep"), 1);
+        assert_eq!(view.fetch::<u32>(&unicode).expect("fetch unicode"), 2);
+        assert_eq!(view.fetch::<u32>("short").expect("fetch short"), 3);
+        assert_eq!(view.entries()[0].name, deep);
+        assert!(view.verify().is_ok());
+
+        let mut file = RDBFile::load("target/long_names.rdb").expect("load file");
+        assert_eq!(file.fetch::<u32>(&deep).expect("fetch deep"), 1);
+    }
+
     #[test]
     fn view_requires_exact_names() {
         let mut rdb = RDBFile::new();
@@ -689,6 +1650,8 @@ mod test {
             value: u32,
         }
 
+        impl RDBType for TempObject {}
+
         let obj_a = TempObject { value: 1 };
         let obj_ab = TempObject { value: 2 };
 
@@ -700,7 +1663,7 @@ mod test {
         rdb.save("target/view_exact_names.rdb")
             .expect("should be able to write file");
 
-        let mut view = RDBView::load("target/view_exact_names.rdb")
+        let view = RDBView::load("target/view_exact_names.rdb")
             .expect("Should be able to load view from disk");
 
         let fetched_a = view
@@ -715,4 +1678,510 @@ mod test {
 
         assert!(view.fetch::<TempObject>("obj/").is_err());
     }
+
+    #[test]
+    fn verify_accepts_untouched_file() {
+        let mut rdb = RDBFile::new();
+        rdb.add("obj/a", &vec![1u32, 2, 3]).expect("add a");
+        rdb.add("obj/b", &"payload".to_string()).expect("add b");
+        rdb.save("target/verify_clean.rdb")
+            .expect("should be able to write file");
+
+        let view = RDBView::load("target/verify_clean.rdb").expect("load view");
+        assert_eq!(view.version(), VERSION);
+
+        let report = view.verify();
+        assert!(report.has_checksums());
+        assert_eq!(report.digest_matches, Some(true));
+        assert!(report.corrupt_entries.is_empty());
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn verify_reports_corrupt_entries_by_name() {
+        let mut rdb = RDBFile::new();
+        rdb.add("obj/good", &vec![7u32; 8]).expect("add good");
+        rdb.add("obj/bad", &vec![9u32; 8]).expect("add bad");
+        rdb.save("target/verify_corrupt.rdb")
+            .expect("should be able to write file");
+
+        let bad = rdb
+            .entries()
+            .into_iter()
+            .find(|meta| meta.name == "obj/bad")
+            .expect("bad entry meta");
+        let mut bytes = std::fs::read("target/verify_corrupt.rdb").expect("read file");
+        let data_start = bytes.len() - rdb.data.len();
+        bytes[data_start + bad.offset as usize + 4] ^= 0xFF;
+        std::fs::write("target/verify_corrupt.rdb", &bytes).expect("write corrupted file");
+
+        let view = RDBView::load("target/verify_corrupt.rdb").expect("load view");
+        let report = view.verify();
+        assert!(!report.is_ok());
+        assert_eq!(report.digest_matches, Some(false));
+        assert_eq!(report.corrupt_entries.len(), 1);
+        assert_eq!(report.corrupt_entries[0].name, "obj/bad");
+        assert!(matches!(
+            report.corrupt_entries[0].fault,
+            RDBEntryFault::ChecksumMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn verify_reports_truncated_entries() {
+        let mut rdb = RDBFile::new();
+        rdb.add("obj/first", &vec![1u8; 32]).expect("add first");
+        rdb.add("obj/last", &vec![2u8; 32]).expect("add last");
+        rdb.save("target/verify_truncated.rdb")
+            .expect("should be able to write file");
+
+        let bytes = std::fs::read("target/verify_truncated.rdb").expect("read file");
+        std::fs::write("target/verify_truncated.rdb", &bytes[..bytes.len() - 8])
+            .expect("write truncated file");
+
+        let view = RDBView::load("target/verify_truncated.rdb").expect("load view");
+        let report = view.verify();
+        assert_eq!(report.corrupt_entries.len(), 1);
+        assert_eq!(report.corrupt_entries[0].name, "obj/last");
+        assert_eq!(report.corrupt_entries[0].fault, RDBEntryFault::OutOfBounds);
+    }
+
+    fn fixed_name(name: &str) -> [u8; 64] {
+        let mut out = [0u8; 64];
+        out[..name.len()].copy_from_slice(name.as_bytes());
+        out
+    }
+
+    #[test]
+    fn version1_files_still_load() {
+        let payload = bincode::serialize(&42u32).expect("serialize payload");
+        let entry = FixedEntryRecord {
+            type_tag: super::legacy_type_tag_for::<u32>(),
+            checksum: 0xDEAD_BEEF, // stands in for uninitialised padding
+            offset: 0,
+            len: payload.len() as u64,
+            name: fixed_name("legacy/value"),
+        };
+        let header = Header {
+            magic: MAGIC,
+            version: 1,
+            reserved: 0,
+            entry_count: 1,
+        };
+
+        let mut bytes = bytemuck::bytes_of(&header).to_vec();
+        bytes.extend_from_slice(bytemuck::bytes_of(&entry));
+        bytes.extend_from_slice(&payload);
+        std::fs::write("target/legacy_v1.rdb", &bytes).expect("write v1 file");
+
+        let view = RDBView::load("target/legacy_v1.rdb").expect("load v1 view");
+        assert_eq!(view.version(), 1);
+        assert_eq!(view.fetch::<u32>("legacy/value").expect("fetch"), 42);
+        let report = view.verify();
+        assert!(!report.has_checksums());
+        assert_eq!(report.digest_matches, None);
+        assert!(report.is_ok());
+
+        let mut file = RDBFile::load("target/legacy_v1.rdb").expect("load v1 file");
+        assert_eq!(file.fetch::<u32>("legacy/value").expect("fetch"), 42);
+        file.unmap();
+        file.save("target/legacy_v1_upgraded.rdb")
+            .expect("should be able to re-save in the current format");
+        let upgraded = RDBView::load("target/legacy_v1_upgraded.rdb").expect("load upgraded");
+        assert_eq!(upgraded.version(), VERSION);
+        assert!(upgraded.verify().is_ok());
+    }
+
+    #[test]
+    fn compressed_entries_round_trip() {
+        let pixels = vec![0x7Fu8; 64 * 1024];
+        let mut rdb = RDBFile::new();
+        rdb.add_compressed("image/zstd", &pixels, RDBCompression::Zstd)
+            .expect("add zstd");
+        rdb.add_compressed("image/lz4", &pixels, RDBCompression::Lz4)
+            .expect("add lz4");
+        rdb.set_compression(RDBCompression::Zstd);
+        rdb.add("image/default", &pixels).expect("add default");
+        rdb.upsert_compressed("image/lz4", &pixels, RDBCompression::Lz4)
+            .expect("upsert lz4");
+        assert_eq!(
+            rdb.fetch::<Vec<u8>>("image/lz4").expect("fetch lz4"),
+            pixels
+        );
+        rdb.save("target/compressed.rdb")
+            .expect("should be able to write file");
+
+        let view = RDBView::load("target/compressed.rdb").expect("load view");
+        assert!(view.verify().is_ok());
+        for (name, codec) in [
+            ("image/zstd", RDBCompression::Zstd),
+            ("image/lz4", RDBCompression::Lz4),
+            ("image/default", RDBCompression::Zstd),
+        ] {
+            let meta = view
+                .entries()
+                .into_iter()
+                .find(|meta| meta.name == name)
+                .expect("entry meta");
+            assert_eq!(meta.compression, codec);
+            assert!(meta.len < meta.raw_len);
+            assert_eq!(view.fetch::<Vec<u8>>(name).expect("fetch"), pixels);
+            assert_eq!(
+                view.entry_bytes(name).expect("entry bytes").len() as u64,
+                meta.raw_len
+            );
+        }
+
+        let mut file = RDBFile::load("target/compressed.rdb").expect("load file");
+        assert_eq!(
+            file.fetch::<Vec<u8>>("image/zstd").expect("fetch zstd"),
+            pixels
+        );
+    }
+
+    #[test]
+    fn incompressible_entries_are_stored_raw() {
+        let mut rdb = RDBFile::new();
+        rdb.add_compressed("tiny", &1u8, RDBCompression::Zstd)
+            .expect("add tiny");
+
+        let meta = &rdb.entries()[0];
+        assert_eq!(meta.compression, RDBCompression::None);
+        assert_eq!(meta.len, meta.raw_len);
+        assert_eq!(rdb.fetch::<u8>("tiny").expect("fetch tiny"), 1);
+    }
+
+    #[test]
+    fn compression_parses_from_build_specs() {
+        let codec: RDBCompression = serde_json::from_str("\"zstd\"").expect("parse zstd");
+        assert_eq!(codec, RDBCompression::Zstd);
+        let codec: RDBCompression = serde_json::from_str("\"lz4\"").expect("parse lz4");
+        assert_eq!(codec, RDBCompression::Lz4);
+        assert!(serde_json::from_str::<RDBCompression>("\"gzip\"").is_err());
+    }
+
+    #[test]
+    fn blobs_are_aligned_in_the_data_section() {
+        let mut rdb = RDBFile::new();
+        rdb.add("odd/a", &vec![1u8; 3]).expect("add a");
+        rdb.add("odd/b", &vec![2u8; 5]).expect("add b");
+        rdb.upsert("odd/a", &vec![3u8; 7]).expect("upsert a");
+        rdb.add_pod_slice("odd/c", &[1u16, 2, 3]).expect("add c");
+        for meta in rdb.entries() {
+            assert_eq!(meta.offset as usize % ENTRY_ALIGN, 0, "{}", meta.name);
+        }
+        rdb.save("target/aligned.rdb")
+            .expect("should be able to write file");
+
+        let view = RDBView::load("target/aligned.rdb").expect("load view");
+        assert_eq!(view.data_start % ENTRY_ALIGN, 0);
+        assert!(view.verify().is_ok());
+    }
+
+    #[test]
+    fn pod_slices_borrow_from_the_mapped_file() {
+        #[repr(C)]
+        #[derive(Clone, Copy, Debug, PartialEq, bytemuck::Zeroable, bytemuck::Pod)]
+        struct Point {
+            position: [f32; 3],
+            weight: f32,
+        }
+
+        impl RDBType for Point {}
+
+        let points: Vec<Point> = (0..5)
+            .map(|i| Point {
+                position: [i as f32, 1.0, 2.0],
+                weight: 0.5,
+            })
+            .collect();
+        let indices = vec![0u32, 1, 2, 2, 3, 4];
+        let pixels = vec![0xABu8; 13];
+
+        let mut rdb = RDBFile::new();
+        rdb.add("mesh/lod_count", &1u8).expect("add lod count");
+        rdb.add_pod_slice("mesh/points", &points)
+            .expect("add points");
+        rdb.add_pod_slice("mesh/indices", &indices)
+            .expect("add indices");
+        rdb.add_pod_slice("image/pixels", &pixels)
+            .expect("add pixels");
+        rdb.save("target/pod_slices.rdb")
+            .expect("should be able to write file");
+
+        let view = RDBView::load("target/pod_slices.rdb").expect("load view");
+        let mapped = view
+            .fetch_pod_slice::<Point>("mesh/points")
+            .expect("borrow points");
+        assert_eq!(mapped, points.as_slice());
+        let range = view.mmap.as_ptr_range();
+        assert!(range.contains(&(mapped.as_ptr() as *const u8)));
+        assert_eq!(
+            view.fetch_pod_slice::<u32>("mesh/indices")
+                .expect("borrow indices"),
+            indices.as_slice()
+        );
+        assert_eq!(
+            view.fetch_pod_slice::<u8>("image/pixels")
+                .expect("borrow pixels"),
+            pixels.as_slice()
+        );
+
+        assert!(
+            view.entries()
+                .iter()
+                .any(|meta| meta.name == "mesh/points" && meta.pod)
+        );
+        assert!(matches!(
+            view.fetch_pod_slice::<u16>("mesh/indices"),
+            Err(RdbErr::TypeMismatch { .. })
+        ));
+        assert!(matches!(
+            view.fetch_pod_slice::<u8>("mesh/lod_count"),
+            Err(RdbErr::Decode { .. })
+        ));
+        assert!(matches!(
+            view.fetch::<u32>("mesh/indices"),
+            Err(RdbErr::Decode { .. })
+        ));
+        assert!(view.verify().is_ok());
+    }
+
+    #[test]
+    fn fetch_reports_decode_errors_with_entry_name() {
+        #[derive(Serialize, Deserialize, Debug)]
+        struct Wide {
+            values: Vec<u64>,
+        }
+
+        impl RDBType for Wide {}
+
+        let mut rdb = RDBFile::new();
+        rdb.add("obj/wide", &Wide { values: vec![1; 4] })
+            .expect("add wide");
+
+        // Chop the payload so the length prefix promises more than is stored.
+        let entry = &mut rdb.entries[0];
+        entry.len -= 8;
+        let err = rdb
+            .fetch::<Wide>("obj/wide")
+            .expect_err("truncated payload should not decode");
+        match err {
+            RdbErr::Decode {
+                entry, type_tag, ..
+            } => {
+                assert_eq!(entry, "obj/wide");
+                assert_eq!(type_tag, super::type_tag_for::<Wide>());
+            }
+            other => panic!("expected decode error, got {other:?}"),
+        }
+
+        rdb.entries[0].len += 8;
+        rdb.save("target/decode_errors.rdb")
+            .expect("should be able to write file");
+        let mut bytes = std::fs::read("target/decode_errors.rdb").expect("read file");
+        let len = bytes.len();
+        bytes.truncate(len - 8);
+        std::fs::write("target/decode_errors.rdb", &bytes).expect("write truncated file");
+
+        let view = RDBView::load("target/decode_errors.rdb").expect("load view");
+        assert!(view.fetch::<Wide>("obj/wide").is_err());
+    }
+
+    #[test]
+    fn fetch_accepts_legacy_hashed_tags() {
+        use super::{legacy_type_tag_for, type_tag_for};
+        use crate::rdb::HostImage;
+
+        let mut rdb = RDBFile::new();
+        rdb.add(
+            "imagery/legacy",
+            &HostImage::new(Default::default(), vec![1, 2, 3, 4]),
+        )
+        .expect("add image");
+        assert_eq!(rdb.entries[0].type_tag, type_tag_for::<HostImage>());
+
+        // Files written before stable tags recorded the type-name hash.
+        rdb.entries[0].type_tag = legacy_type_tag_for::<HostImage>();
+        let image = rdb
+            .fetch::<HostImage>("imagery/legacy")
+            .expect("legacy tag should still match");
+        assert_eq!(image.data, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn view_indexes_names_and_prefixes() {
+        let mut rdb = RDBFile::new();
+        rdb.add("terrain/chunk_artifact/a/0_0/lod0", &1u32)
+            .expect("add a0");
+        rdb.add("terrain/chunk_state/a/0_0", &2u32)
+            .expect("add state");
+        rdb.add("terrain/chunk_artifact/a/1_0/lod0", &3u32)
+            .expect("add a1");
+        rdb.add("terrain/chunk_artifact/b/0_0/lod0", &4u32)
+            .expect("add b0");
+        rdb.save("target/view_prefix.rdb")
+            .expect("should be able to write file");
+
+        let view = RDBView::load("target/view_prefix.rdb").expect("load view");
+        assert_eq!(view.len(), 4);
+        assert!(view.contains("terrain/chunk_state/a/0_0"));
+        assert!(!view.contains("terrain/chunk_state/a"));
+
+        let names: Vec<String> = view
+            .entries_with_prefix("terrain/chunk_artifact/a/")
+            .map(|meta| meta.name)
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "terrain/chunk_artifact/a/0_0/lod0".to_string(),
+                "terrain/chunk_artifact/a/1_0/lod0".to_string(),
+            ]
+        );
+        assert_eq!(view.entries_with_prefix("terrain/").count(), 4);
+        assert_eq!(view.entries_with_prefix("imagery/").count(), 0);
+    }
+
+    #[test]
+    fn appends_leave_existing_bytes_untouched() {
+        let path = "target/append_in_place.rdb";
+        let mut rdb = RDBFile::new();
+        rdb.add("chunk/a", &vec![1u32; 64]).expect("add a");
+        rdb.add("chunk/b", &vec![2u32; 64]).expect("add b");
+        rdb.save(path).expect("save");
+        let original = std::fs::read(path).expect("read original");
+
+        let mut file = RDBFile::load(path).expect("load file");
+        file.upsert("chunk/a", &vec![3u32; 64]).expect("upsert a");
+        file.add("chunk/c", &vec![4u32; 8]).expect("add c");
+        file.append(path).expect("append");
+
+        let appended = std::fs::read(path).expect("read appended");
+        assert!(appended.len() > original.len());
+        assert_eq!(
+            &appended[FileHeader::LEN..original.len()],
+            &original[FileHeader::LEN..]
+        );
+
+        let view = RDBView::load(path).expect("load view");
+        assert_eq!(view.len(), 3);
+        assert_eq!(view.fetch::<Vec<u32>>("chunk/a").expect("a"), vec![3; 64]);
+        assert_eq!(view.fetch::<Vec<u32>>("chunk/b").expect("b"), vec![2; 64]);
+        assert_eq!(view.fetch::<Vec<u32>>("chunk/c").expect("c"), vec![4; 8]);
+        assert!(view.dead_bytes() > 0);
+        assert!(view.verify().is_ok());
+
+        file.upsert("chunk/b", &vec![5u32; 64]).expect("upsert b");
+        file.append(path).expect("second append");
+        let view = RDBView::load(path).expect("reload view");
+        assert_eq!(view.fetch::<Vec<u32>>("chunk/b").expect("b"), vec![5; 64]);
+        assert!(view.verify().is_ok());
+
+        let mut reloaded = RDBFile::load(path).expect("reload file");
+        assert_eq!(reloaded.entries().len(), 3);
+        assert_eq!(reloaded.tombstones.len(), 2);
+        assert_eq!(
+            reloaded.fetch::<Vec<u32>>("chunk/a").expect("a"),
+            vec![3; 64]
+        );
+    }
+
+    #[test]
+    fn load_reads_entries_from_the_mapped_file() {
+        let path = "target/load_mapped.rdb";
+        let mut rdb = RDBFile::new();
+        rdb.add("chunk/a", &vec![1u8; 128]).expect("add a");
+        rdb.save(path).expect("save");
+
+        let mut file = RDBFile::load(path).expect("load file");
+        assert!(file.data.is_empty());
+        file.add("chunk/b", &vec![2u8; 16]).expect("add b");
+        assert_eq!(file.fetch::<Vec<u8>>("chunk/a").expect("a"), vec![1; 128]);
+
+        file.save(path).expect("save over the loaded file");
+        let view = RDBView::load(path).expect("load view");
+        assert_eq!(view.fetch::<Vec<u8>>("chunk/a").expect("a"), vec![1; 128]);
+        assert_eq!(view.fetch::<Vec<u8>>("chunk/b").expect("b"), vec![2; 16]);
+
+        file.unmap();
+        assert_eq!(file.fetch::<Vec<u8>>("chunk/a").expect("a"), vec![1; 128]);
+        assert_eq!(file.fetch::<Vec<u8>>("chunk/b").expect("b"), vec![2; 16]);
+    }
+
+    #[test]
+    fn append_rewrites_files_changed_on_disk() {
+        let path = "target/append_changed.rdb";
+        let mut rdb = RDBFile::new();
+        rdb.add("value", &1u32).expect("add value");
+        rdb.save(path).expect("save");
+
+        let mut file = RDBFile::load(path).expect("load file");
+        let mut other = RDBFile::new();
+        other.add("other", &2u32).expect("add other");
+        other.save(path).expect("overwrite");
+
+        file.upsert("value", &3u32).expect("upsert value");
+        file.append(path).expect("append");
+
+        let view = RDBView::load(path).expect("load view");
+        assert_eq!(view.len(), 1);
+        assert_eq!(view.fetch::<u32>("value").expect("value"), 3);
+        assert_eq!(view.dead_bytes(), 0);
+        assert!(view.verify().is_ok());
+    }
+
+    #[test]
+    fn compact_reclaims_dead_bytes() {
+        let path = "target/append_compact.rdb";
+        let mut rdb = RDBFile::new();
+        rdb.add("chunk/a", &vec![1u8; 256]).expect("add a");
+        rdb.add("chunk/b", &vec![2u8; 256]).expect("add b");
+        rdb.save(path).expect("save");
+
+        let mut file = RDBFile::load(path).expect("load file");
+        for value in 3..6u8 {
+            file.upsert("chunk/a", &vec![value; 256]).expect("upsert a");
+            file.append(path).expect("append");
+        }
+        let grown = std::fs::metadata(path).expect("stat").len();
+
+        file.compact(path).expect("compact");
+        assert!(file.tombstones.is_empty());
+        assert!(std::fs::metadata(path).expect("stat").len() < grown);
+
+        let view = RDBView::load(path).expect("load view");
+        assert_eq!(view.dead_bytes(), 0);
+        assert_eq!(view.fetch::<Vec<u8>>("chunk/a").expect("a"), vec![5; 256]);
+        assert_eq!(view.fetch::<Vec<u8>>("chunk/b").expect("b"), vec![2; 256]);
+        assert!(view.verify().is_ok());
+    }
+
+    #[test]
+    fn raw_entries_decode_on_other_threads() {
+        let path = "target/raw_entry.rdb";
+        let mut rdb = RDBFile::new();
+        rdb.add_compressed("chunk/z", &vec![9u8; 512], RDBCompression::Zstd)
+            .expect("add");
+        rdb.add("value", &7u32).expect("add value");
+        rdb.save(path).expect("save");
+
+        let view = RDBView::load(path).expect("load view");
+        let raw = view.raw_entry("chunk/z").expect("raw entry");
+        let value = view.raw_entry("value").expect("raw value");
+        drop(view);
+        assert!(RDBView::load(path).unwrap().raw_entry("missing").is_none());
+
+        let decoded = std::thread::spawn(move || raw.decode::<Vec<u8>>())
+            .join()
+            .expect("join");
+        assert_eq!(decoded.expect("decode"), vec![9u8; 512]);
+        assert_eq!(value.name(), "value");
+        assert!(value.holds::<u32>() && !value.holds::<String>());
+        assert!(matches!(
+            value.decode::<String>(),
+            Err(RdbErr::TypeMismatch { .. })
+        ));
+        assert_eq!(value.decode::<u32>().expect("decode value"), 7);
+    }
 }